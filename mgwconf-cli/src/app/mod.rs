@@ -2,18 +2,31 @@ use anyhow::{bail, Error, Result};
 use async_trait::async_trait;
 use core::panic;
 use log::{debug, error};
-use mgwconf_network::{event::IoEvent, mgw_configuration::apis::ResponseContent, AppTrait};
-use mgwconf_vault::{SecretType, SecretsVault};
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::{
+        apis::ResponseContent,
+        models::{
+            ApiCredentialsEntity, ApplicationProfileEntity, BusinessApplicationEntity,
+            CertificateEntity, ForwardProxyEntity, SagEntity,
+        },
+    },
+    AppTrait,
+};
+use mgwconf_vault::{SecretBackend, SecretType, SecretsVault};
 use serde::{Deserialize, Serialize};
-use std::{io::Write, sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::sync::{broadcast::Sender, Mutex, Notify};
 
 use crate::{
     command::{
-        get_business_application::GetBusinessApplication, get_certificate::GetCertificate,
-        get_profile::GetProfile, get_proxy::GetProxy, get_sag::GetSag, registry::Registry,
+        api_client_credential::CredCommand, business_application::AppCommand,
+        certificate::CertCommand, check_certificates::CertificateReport, csv_import::ImportReport,
+        lint::LintReport, profile::ProfileCommand, proxy::ProxyCommand,
+        rotate_certificate::CertificateRotation, sag::SagCommand, select, Command, CommandTrait,
     },
     config::Config,
+    output::Output,
     playbook::{error::PlaybookError, Playbook},
 };
 
@@ -34,18 +47,41 @@ pub struct CliApp {
     pub waiting_res: usize,
     error: bool,
     app_type: CliAppType,
+    pub certificate_report: Option<CertificateReport>,
+    pub certificate_rotation: Option<CertificateRotation>,
+    pub lint_report: Option<LintReport>,
+    pub import_report: Option<ImportReport>,
+    output: Output,
 }
 
 impl CliApp {
-    pub async fn new(io_tx: Sender<IoEvent>, mut config: Config, vault_key: &str) -> CliApp {
+    pub async fn new(
+        io_tx: Sender<IoEvent>,
+        mut config: Config,
+        backend: Arc<dyn SecretBackend>,
+    ) -> CliApp {
         config.init_logging();
-        let vault = match SecretsVault::new(vault_key) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("Can't decode secret vault : {}", e);
-                panic!("Can't decode secret vault");
-            }
+        let vault = SecretsVault::new(backend);
+        let certificate_report = match &config.command {
+            Some(Command::Cert(CertCommand::Check(check))) => Some(CertificateReport::new(
+                check.warning_days,
+                check.critical_days,
+            )),
+            _ => None,
+        };
+        let certificate_rotation = match (&config.command, &config.certificate_import) {
+            (Some(Command::Cert(CertCommand::Rotate(rotate))), Some(certificate)) => Some(
+                CertificateRotation::new(rotate.old_alias.to_owned(), certificate.clone()),
+            ),
+            _ => None,
         };
+        let lint_report = matches!(config.command, Some(Command::Lint)).then(LintReport::new);
+        // An invalid file has already been refused before connecting
+        let import_report = config
+            .command
+            .as_ref()
+            .and_then(Command::import_report)
+            .and_then(Result::ok);
         let app_type = if config.playbook.is_some() {
             CliAppType::Playbook
         } else {
@@ -60,6 +96,11 @@ impl CliApp {
             waiting_res: 0,
             error: false,
             app_type,
+            certificate_report,
+            certificate_rotation,
+            lint_report,
+            import_report,
+            output: Output::default(),
         }
     }
 
@@ -68,22 +109,126 @@ impl CliApp {
             error!("App is not connected, cli is aborted");
             return;
         }
-        let config = self.config.clone();
-        let registry = Registry::new(self, config.unwrap().commands.to_owned());
-        if !registry.run().await {
-            error!("Command failed");
+        let config = AppTrait::<Config>::config(self).clone();
+        let Some(command) = config.command.as_ref() else {
+            error!("No command given, see --help");
+            return;
+        };
+        match command.execute(self).await {
+            // Every gateway answers each dispatched event
+            Ok(num_op) => self.waiting_res += num_op * config.gateways().len(),
+            Err(e) => {
+                error!("Command failed : {}", e);
+                self.error = true;
+            }
+        }
+    }
+
+    /// Process exit code, only differs from 0 for monitoring commands
+    pub fn exit_code(&self) -> i32 {
+        let certificates = self
+            .certificate_report
+            .as_ref()
+            .map(CertificateReport::exit_code)
+            .unwrap_or_default();
+        let lint = self
+            .lint_report
+            .as_ref()
+            .map(LintReport::exit_code)
+            .unwrap_or_default();
+        certificates.max(lint)
+    }
+
+    /// Events of the CSV import, one per row of the file
+    pub fn import_events(&self) -> Result<Vec<IoEvent>> {
+        match &self.import_report {
+            Some(report) => Ok(report.events()),
+            None => bail!("No CSV file to import"),
         }
     }
 
-    fn clear_output_dir() {
-        log::info!("Clearing output dir");
-        for entry in std::fs::read_dir("./output").unwrap().flatten() {
-            std::fs::remove_file(entry.path()).unwrap();
+    /// Dispatch the next step of the certificate rotation, if any
+    fn next_rotation_step(&mut self) {
+        let Some(event) = self
+            .certificate_rotation
+            .as_mut()
+            .and_then(|r| r.next_event())
+        else {
+            return;
+        };
+        debug!("Rotation step {}", rotation_step_name(&event));
+        match self.io_tx.send(event) {
+            Ok(_) => self.waiting_res += 1,
+            Err(e) => {
+                if let Some(rotation) = self.certificate_rotation.as_mut() {
+                    rotation.step_failed(e.to_string());
+                }
+                self.next_rotation_step();
+            }
         }
     }
 
+    /// Keep the entities listed by a gateway, selected by the command, until every response is received
+    fn collect_output(&mut self, gateway: SocketAddr, event: &IoEvent, content: &str) {
+        let config = AppTrait::<Config>::config(self);
+        let show_secrets = config.show_secrets;
+        let selection = config
+            .command
+            .as_ref()
+            .map(Command::selection)
+            .unwrap_or_default();
+        let predicates = config.predicates.clone();
+        let output = &mut self.output;
+        let res = match event {
+            IoEvent::GetAllForwardProxyEntity => {
+                select::<ForwardProxyEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add(gateway, ProxyCommand::KIND, &e))
+            }
+            IoEvent::GetAllBusinessApplications => {
+                select::<BusinessApplicationEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add(gateway, AppCommand::KIND, &e))
+            }
+            IoEvent::GetAllCertificates | IoEvent::GetCertificate(_) => {
+                select::<CertificateEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add(gateway, CertCommand::KIND, &CertCommand::report(e)))
+            }
+            IoEvent::GetAllSags | IoEvent::GetSag(..) => {
+                select::<SagEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add(gateway, SagCommand::KIND, &e))
+            }
+            IoEvent::GetAllProfiles | IoEvent::GetProfiles(..) => {
+                select::<ApplicationProfileEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add(gateway, ProfileCommand::KIND, &e))
+            }
+            IoEvent::GetAllApiClientCredentials => {
+                select::<ApiCredentialsEntity>(content, &selection, &predicates)
+                    .and_then(|e| output.add_masked(gateway, CredCommand::KIND, &e, show_secrets))
+            }
+            _ => Ok(()),
+        };
+        // The raw response is not written, it may contain secrets
+        if let Err(e) = res {
+            error!("Cannot read response of {} : {}", gateway, e);
+        }
+    }
+
+    /// Write the collected entities to stdout, or to the `--out` file
+    fn write_output(&self) -> Result<()> {
+        if self.output.is_empty() {
+            return Ok(());
+        }
+        let config = AppTrait::<Config>::config(self);
+        let rendered = self.output.render(config.output)?;
+        match &config.out {
+            Some(path) => std::fs::write(path, rendered)?,
+            None => print!("{}", rendered),
+        }
+        Ok(())
+    }
+
     async fn run_playbook(&mut self, playbook: Playbook) -> Result<(), PlaybookError> {
-        self.waiting_res = playbook.process(self).await?;
+        let gateways = AppTrait::<Config>::config(self).gateways().len();
+        self.waiting_res = playbook.process(self).await? * gateways;
         Ok(())
     }
 }
@@ -104,34 +249,26 @@ impl AppTrait<Config> for CliApp {
         Ok(())
     }
 
-    fn ask_secrets(master: &str) -> Result<()> {
+    fn ask_secrets(backend: &dyn SecretBackend) -> Result<()> {
         let mut secret = String::new();
         for s in SecretType::iterator() {
-            <CliApp as AppTrait<Config>>::ask_secret(master, &mut secret, *s);
+            <CliApp as AppTrait<Config>>::ask_secret(backend, &mut secret, *s);
         }
         print!("\x1B[2J\x1B[1;1H");
         Ok(())
     }
 
-    #[cfg(not(feature = "store"))]
-    #[allow(unused_variables)]
-    fn ask_secret(master: &str, s: &mut String, stype: SecretType) {
-        panic!("To create a vault store you must enable store features");
-    }
-
-    #[cfg(feature = "store")]
-    fn ask_secret(master: &str, s: &mut String, stype: SecretType) {
-        use std::io::{stdin, stdout};
+    fn ask_secret(backend: &dyn SecretBackend, s: &mut String, stype: SecretType) {
+        use std::io::{stdin, stdout, Write};
         println!("Pleaser enter {} API KEY", stype);
         let _ = stdout().flush();
         stdin()
             .read_line(s)
             .expect("Did not enter a correct string");
         s.pop();
-        SecretsVault::new(master)
-            .unwrap()
-            .create_secret(stype, s.to_owned())
-            .unwrap();
+        backend
+            .write_secret(stype, s)
+            .unwrap_or_else(|e| panic!("Can't store {stype} secret : {e}"));
         s.clear()
     }
 
@@ -156,51 +293,128 @@ impl AppTrait<Config> for CliApp {
         event: IoEvent,
         res: ResponseContent<T>,
     ) {
-        debug!("Receiving response from network for io_event {event:?}");
-        match event {
-            IoEvent::GetAllForwardProxyEntity => writeln!(
-                GetProxy::output_file(),
-                "{}",
-                serde_json::to_string_pretty(&res.entity).unwrap()
-            )
-            .unwrap(),
-            IoEvent::GetAllBusinessApplications => writeln!(
-                GetBusinessApplication::output_file(),
-                "{}",
-                serde_json::to_string_pretty(&res.entity).unwrap()
-            )
-            .unwrap(),
-            IoEvent::GetAllCertificates => writeln!(
-                GetCertificate::output_file(),
-                "{}",
-                serde_json::to_string_pretty(&res.entity).unwrap()
-            )
-            .unwrap(),
-            IoEvent::GetAllSags => writeln!(
-                GetSag::output_file(),
-                "{}",
-                serde_json::to_string_pretty(&res.entity).unwrap()
-            )
-            .unwrap(),
-            IoEvent::GetAllProfiles => writeln!(
-                GetProfile::output_file(),
-                "{}",
-                serde_json::to_string_pretty(&res.entity).unwrap()
-            )
-            .unwrap(),
-            _ => {}
+        let gateway = AppTrait::<Config>::config(self).remote_addr;
+        self.handle_gateway_response(gateway, event, res);
+    }
+
+    fn handle_gateway_response<'a, T: Deserialize<'a> + Serialize>(
+        &mut self,
+        gateway: SocketAddr,
+        event: IoEvent,
+        res: ResponseContent<T>,
+    ) {
+        if let Some(rotation) = self.certificate_rotation.as_mut() {
+            let content = if res.content.is_empty() {
+                "[]"
+            } else {
+                &res.content
+            };
+            let discovered = match event {
+                IoEvent::GetAllCertificates => serde_json::from_str(content)
+                    .map(|c| rotation.add_certificates(c))
+                    .map(|_| true),
+                IoEvent::GetAllSags => serde_json::from_str(content)
+                    .map(|s| rotation.add_sags(s))
+                    .map(|_| true),
+                IoEvent::GetAllProfiles => serde_json::from_str(content)
+                    .map(|p| rotation.add_profiles(p))
+                    .map(|_| true),
+                _ => {
+                    rotation.step_succeeded();
+                    Ok(false)
+                }
+            };
+            match discovered {
+                Ok(true) => {
+                    if rotation.is_discovered() && rotation.is_success() {
+                        if let Err(e) = rotation.plan() {
+                            rotation.step_failed(e.to_string());
+                        }
+                        self.next_rotation_step();
+                    }
+                }
+                Ok(false) => self.next_rotation_step(),
+                Err(e) => rotation.step_failed(format!("{} : {}", gateway, e)),
+            }
+            self.waiting_res -= 1;
+            return;
+        }
+        if let Some(report) = self.lint_report.as_mut() {
+            let content = if res.content.is_empty() {
+                "[]"
+            } else {
+                &res.content
+            };
+            if let Err(e) = report.add_response(gateway, &event, content) {
+                report.add_error(format!("{} : {}", gateway, e));
+            }
+            self.waiting_res -= 1;
+            return;
+        }
+        if let Some(report) = self.certificate_report.as_mut() {
+            let content = if res.content.is_empty() {
+                "[]"
+            } else {
+                &res.content
+            };
+            match event {
+                IoEvent::GetAllCertificates => {
+                    match serde_json::from_str::<Vec<CertificateEntity>>(content) {
+                        Ok(certificates) => report.add_certificates(gateway, certificates),
+                        Err(e) => report.add_error(format!("{} : {}", gateway, e)),
+                    }
+                    self.waiting_res -= 1;
+                    return;
+                }
+                IoEvent::GetAllSags => {
+                    match serde_json::from_str::<Vec<SagEntity>>(content) {
+                        Ok(sags) => report.add_sags(gateway, sags),
+                        Err(e) => report.add_error(format!("{} : {}", gateway, e)),
+                    }
+                    self.waiting_res -= 1;
+                    return;
+                }
+                _ => {}
+            }
         }
+        if let Some(report) = self.import_report.as_mut() {
+            report.add_success(&event);
+        }
+        debug!("Receiving response from {gateway} for io_event {event:?}");
+        self.collect_output(gateway, &event, &res.content);
         self.waiting_res -= 1;
     }
 
     fn handle_network_error(&mut self, error: Error) {
         log::error!("{}", error);
-        if self.app_type != CliAppType::Playbook {
+        if let Some(rotation) = self.certificate_rotation.as_mut() {
+            // The rotation is rolled back instead of aborting the run
+            rotation.step_failed(error.to_string());
+            self.waiting_res -= 1;
+            self.next_rotation_step();
+            return;
+        }
+        if let Some(report) = self.lint_report.as_mut() {
+            report.add_error(error.to_string());
+        } else if let Some(report) = self.certificate_report.as_mut() {
+            report.add_error(error.to_string());
+        } else if self.app_type != CliAppType::Playbook {
             self.error = true;
         }
         self.waiting_res -= 1;
     }
 
+    fn handle_event_error(&mut self, gateway: SocketAddr, event: &IoEvent, error: Error) {
+        if let Some(report) = self.import_report.as_mut() {
+            // A refused row does not stop the other rows
+            if report.add_failure(gateway, event, &error.to_string()) {
+                self.waiting_res -= 1;
+                return;
+            }
+        }
+        self.handle_gateway_error(gateway, error);
+    }
+
     async fn run(
         app: Arc<Mutex<Self>>,
         notifier: Option<Arc<Notify>>,
@@ -221,7 +435,6 @@ impl AppTrait<Config> for CliApp {
             if let Some(playbook) = playbook {
                 app.run_playbook(playbook).await?
             } else {
-                Self::clear_output_dir();
                 let run_command = app.run_commands();
                 run_command.await;
             }
@@ -238,6 +451,35 @@ impl AppTrait<Config> for CliApp {
             }
             std::thread::sleep(Duration::from_millis(20));
         }
+        app.lock().await.write_output()?;
+        if let Some(report) = &app.lock().await.certificate_report {
+            print!("{}", report);
+        }
+        if let Some(report) = &app.lock().await.lint_report {
+            print!("{}", report);
+        }
+        if let Some(report) = &app.lock().await.import_report {
+            if !report.is_success() {
+                bail!("{}", report);
+            }
+            print!("{}", report);
+        }
+        if let Some(rotation) = &app.lock().await.certificate_rotation {
+            if !rotation.is_success() {
+                bail!("{}", rotation.summary());
+            }
+            println!("{}", rotation.summary());
+        }
         Ok(())
     }
 }
+
+fn rotation_step_name(event: &IoEvent) -> &'static str {
+    match event {
+        IoEvent::PostCertificate(_) => "create certificate",
+        IoEvent::DeleteCertificate(_) => "delete certificate",
+        IoEvent::UpdateSag(_) => "update SAG",
+        IoEvent::UpdateProfile(_) => "update profile",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{event::IoEvent, mgw_configuration::models::ApiCredentialsEntity};
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{dispatch_all, CommandTrait, EntityInput};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CredCommand {
+    /// List every API client credential
+    List,
+    /// Show a single API client credential
+    Get(CredKey),
+    /// Create API client credentials
+    Create(EntityInput),
+    /// Update API client credentials, identified by their application name
+    Update(EntityInput),
+    /// Delete an API client credential
+    Delete(CredKey),
+}
+
+/// Identifies an API client credential
+#[derive(Args, Debug, Clone)]
+pub struct CredKey {
+    /// Business application name
+    #[clap(long = "application_name")]
+    pub application_name: String,
+}
+
+impl CredCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "api_client_credentials";
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            CredCommand::Get(key) => vec![("applicationName", key.application_name.clone().into())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for CredCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            CredCommand::List | CredCommand::Get(_) => vec![IoEvent::GetAllApiClientCredentials],
+            CredCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostApiClientCredential)
+                .collect(),
+            CredCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateApiClientCredential)
+                .collect(),
+            CredCommand::Delete(key) => vec![IoEvent::DeleteApiClientCredential(
+                ApiCredentialsEntity::new(
+                    key.application_name.to_owned(),
+                    String::new(),
+                    String::new(),
+                ),
+            )],
+        };
+        dispatch_all(app, events).await
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{event::IoEvent, mgw_configuration::models::BusinessApplicationEntity};
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{csv_import::CsvImportArgs, dispatch_all, CommandTrait, EntityInput};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AppCommand {
+    /// List every business application
+    List,
+    /// Show a single business application
+    Get(AppKey),
+    /// Create business applications
+    Create(EntityInput),
+    /// Create or update business applications from a CSV file
+    Import(CsvImportArgs),
+    /// Update business applications, identified by their application name
+    Update(EntityInput),
+    /// Delete a business application
+    Delete(AppKey),
+}
+
+/// Identifies a business application
+#[derive(Args, Debug, Clone)]
+pub struct AppKey {
+    /// Business application name
+    #[clap(long = "application_name")]
+    pub application_name: String,
+}
+
+impl AppCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "business_applications";
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            AppCommand::Get(key) => vec![("applicationName", key.application_name.clone().into())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for AppCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            AppCommand::List | AppCommand::Get(_) => vec![IoEvent::GetAllBusinessApplications],
+            AppCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostBusinessApplication)
+                .collect(),
+            AppCommand::Import(_) => app.import_events()?,
+            AppCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateBusinessApplication)
+                .collect(),
+            AppCommand::Delete(key) => vec![IoEvent::DeleteBusinessApplication(
+                BusinessApplicationEntity::new(key.application_name.to_owned()),
+            )],
+        };
+        dispatch_all(app, events).await
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::CertificateEntity,
+    model::certificate::{sort_by_expiry, CertificateDetails},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{
+    check_certificates::{CheckArgs, CheckCertificates},
+    dispatch_all,
+    import_certificate::{CertificateImportArgs, ImportCertificate},
+    rotate_certificate::{RotateArgs, RotateCertificate},
+    CommandTrait, EntityInput,
+};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CertCommand {
+    /// List every certificate, sorted by expiry
+    List,
+    /// Show a single certificate
+    Get(CertificateKey),
+    /// Create certificates from their JSON entity
+    Create(EntityInput),
+    /// Import a certificate file (.pem, .crt, .der, .p12)
+    Import(CertificateImportArgs),
+    /// Update certificates, identified by their alias
+    Update(EntityInput),
+    /// Delete a certificate
+    Delete(CertificateKey),
+    /// Flag certificates about to expire, with Nagios exit codes
+    Check(CheckArgs),
+    /// Replace a certificate and repoint the SAGs and profiles using it
+    Rotate(RotateArgs),
+}
+
+/// Identifies a certificate
+#[derive(Args, Debug, Clone)]
+pub struct CertificateKey {
+    /// Certificate alias
+    #[clap(long = "alias")]
+    pub alias: String,
+}
+
+#[derive(Serialize)]
+pub struct CertificateOutput {
+    #[serde(flatten)]
+    entity: CertificateEntity,
+    details: Option<CertificateDetails>,
+}
+
+impl CertCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "certificates";
+
+    /// Enrich certificates with their parsed X.509 details, sorted by expiry (soonest first)
+    pub fn report(mut certificates: Vec<CertificateEntity>) -> Vec<CertificateOutput> {
+        sort_by_expiry(&mut certificates);
+        certificates
+            .into_iter()
+            .map(|entity| CertificateOutput {
+                details: CertificateDetails::try_from(&entity)
+                    .map_err(|e| log::warn!("{}", e))
+                    .ok(),
+                entity,
+            })
+            .collect()
+    }
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            CertCommand::Get(key) => vec![("alias", key.alias.clone().into())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for CertCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            CertCommand::List => vec![IoEvent::GetAllCertificates],
+            CertCommand::Get(key) => vec![IoEvent::GetCertificate(key.alias.to_owned())],
+            CertCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostCertificate)
+                .collect(),
+            CertCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateCertificate)
+                .collect(),
+            CertCommand::Delete(key) => vec![IoEvent::DeleteCertificate(CertificateEntity::new(
+                key.alias.to_owned(),
+                String::new(),
+            ))],
+            CertCommand::Import(_) => return ImportCertificate {}.execute(app).await,
+            CertCommand::Check(_) => return CheckCertificates {}.execute(app).await,
+            CertCommand::Rotate(_) => return RotateCertificate {}.execute(app).await,
+        };
+        dispatch_all(app, events).await
+    }
+}
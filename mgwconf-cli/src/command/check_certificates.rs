@@ -0,0 +1,365 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::{CertificateEntity, SagEntity},
+    model::certificate::CertificateDetails,
+};
+use std::{collections::BTreeMap, fmt, net::SocketAddr};
+
+use crate::app::CliApp;
+
+use super::{dispatch_all, CommandTrait};
+
+/// Expiry thresholds of the certificate check
+#[derive(Args, Debug, Clone)]
+pub struct CheckArgs {
+    /// Certificates expiring within this number of days raise a warning
+    #[clap(long = "warning_days", default_value = "30")]
+    pub warning_days: i64,
+    /// Certificates expiring within this number of days are critical
+    #[clap(long = "critical_days", default_value = "7")]
+    pub critical_days: i64,
+}
+
+impl CheckArgs {
+    /// A critical certificate is also a warning one, the critical window cannot be the larger
+    pub fn validate(&self) -> Result<(), String> {
+        if self.critical_days > self.warning_days {
+            return Err(format!(
+                "--critical_days {} is greater than --warning_days {}",
+                self.critical_days, self.warning_days
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Fetch every certificate of the targeted gateways and flag those about to expire
+pub struct CheckCertificates {}
+
+#[async_trait]
+impl CommandTrait for CheckCertificates {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        dispatch_all(app, vec![IoEvent::GetAllCertificates, IoEvent::GetAllSags]).await
+    }
+}
+
+/// Nagios-style status of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Ok = 0,
+    Warning = 1,
+    Critical = 2,
+    /// Certificates of a gateway could not be fetched
+    Unknown = 3,
+}
+
+impl CheckStatus {
+    /// Status of a certificate expiring in `days_left` days, thresholds included
+    fn of(days_left: i64, warning_days: i64, critical_days: i64) -> CheckStatus {
+        if days_left <= critical_days {
+            CheckStatus::Critical
+        } else if days_left <= warning_days {
+            CheckStatus::Warning
+        } else {
+            CheckStatus::Ok
+        }
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Ok => write!(f, "OK"),
+            CheckStatus::Warning => write!(f, "WARNING"),
+            CheckStatus::Critical => write!(f, "CRITICAL"),
+            CheckStatus::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct GatewayCertificates {
+    certificates: Vec<CertificateEntity>,
+    sags: Vec<SagEntity>,
+}
+
+#[derive(Debug)]
+struct CheckLine {
+    status: CheckStatus,
+    gateway: SocketAddr,
+    alias: String,
+    days_left: Option<i64>,
+    message: String,
+    used_by: Vec<String>,
+}
+
+/// Collects certificates and SAGs of each gateway, then builds the expiry report
+#[derive(Debug, Clone)]
+pub struct CertificateReport {
+    warning_days: i64,
+    critical_days: i64,
+    gateways: BTreeMap<SocketAddr, GatewayCertificates>,
+    errors: Vec<String>,
+}
+
+impl CertificateReport {
+    pub fn new(warning_days: i64, critical_days: i64) -> CertificateReport {
+        CertificateReport {
+            warning_days,
+            critical_days,
+            gateways: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn add_certificates(&mut self, gateway: SocketAddr, certificates: Vec<CertificateEntity>) {
+        self.gateways.entry(gateway).or_default().certificates = certificates;
+    }
+
+    pub fn add_sags(&mut self, gateway: SocketAddr, sags: Vec<SagEntity>) {
+        self.gateways.entry(gateway).or_default().sags = sags;
+    }
+
+    pub fn add_error(&mut self, error: String) {
+        self.errors.push(error);
+    }
+
+    fn lines(&self) -> Vec<CheckLine> {
+        let mut lines = self
+            .gateways
+            .iter()
+            .flat_map(|(addr, gateway)| gateway.check(*addr, self.warning_days, self.critical_days))
+            .collect::<Vec<CheckLine>>();
+        lines.sort_by(|a, b| {
+            b.status.cmp(&a.status).then(
+                a.days_left
+                    .unwrap_or(i64::MIN)
+                    .cmp(&b.days_left.unwrap_or(i64::MIN)),
+            )
+        });
+        lines
+    }
+
+    /// Overall status, unknown when a fetch failed unless a fetched certificate is critical
+    pub fn status(&self) -> CheckStatus {
+        let worst = self
+            .lines()
+            .iter()
+            .map(|l| l.status)
+            .max()
+            .unwrap_or(CheckStatus::Ok);
+        if self.errors.is_empty() || worst == CheckStatus::Critical {
+            worst
+        } else {
+            CheckStatus::Unknown
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.status() as i32
+    }
+}
+
+impl GatewayCertificates {
+    fn check(&self, gateway: SocketAddr, warning_days: i64, critical_days: i64) -> Vec<CheckLine> {
+        self.certificates
+            .iter()
+            .map(|certificate| {
+                let used_by = self
+                    .sags
+                    .iter()
+                    .filter(|s| s.public_certificate_alias.as_ref() == Some(&certificate.alias))
+                    .map(|s| format!("SAG {}:{}", s.hostname, s.port))
+                    .collect::<Vec<String>>();
+                match CertificateDetails::try_from(certificate) {
+                    Ok(details) => {
+                        let days_left = details.days_left();
+                        let status = CheckStatus::of(days_left, warning_days, critical_days);
+                        let message = if details.is_expired() {
+                            format!("expired on {}", details.not_after)
+                        } else {
+                            format!("expires on {} ({} days left)", details.not_after, days_left)
+                        };
+                        CheckLine {
+                            status,
+                            gateway,
+                            alias: certificate.alias.to_owned(),
+                            days_left: Some(days_left),
+                            message,
+                            used_by,
+                        }
+                    }
+                    Err(e) => CheckLine {
+                        status: CheckStatus::Critical,
+                        gateway,
+                        alias: certificate.alias.to_owned(),
+                        days_left: None,
+                        message: e.to_string(),
+                        used_by,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for CertificateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self.lines();
+        let count = |status: CheckStatus| lines.iter().filter(|l| l.status == status).count();
+        writeln!(
+            f,
+            "CERTIFICATES {} - {} critical, {} warning, {} ok, {} error(s) | warning={}d critical={}d",
+            self.status(),
+            count(CheckStatus::Critical),
+            count(CheckStatus::Warning),
+            count(CheckStatus::Ok),
+            self.errors.len(),
+            self.warning_days,
+            self.critical_days
+        )?;
+        for error in self.errors.iter() {
+            writeln!(f, "[ERROR] {}", error)?;
+        }
+        for line in lines.iter() {
+            write!(
+                f,
+                "[{}] {} {} {}",
+                line.status, line.gateway, line.alias, line.message
+            )?;
+            if !line.used_by.is_empty() {
+                write!(f, ", used by {}", line.used_by.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use clap::Parser;
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{X509Name, X509},
+    };
+
+    use super::*;
+    use crate::config::Args;
+
+    /// Self-signed certificate expiring `days` from now, an hour later to stay clear of the
+    /// day boundary
+    fn certificate(alias: &str, days: i64) -> CertificateEntity {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_text("CN", alias).unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::from_unix(now - 400 * 86400).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::from_unix(now + days * 86400 + 3600).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        CertificateEntity {
+            alias: alias.to_owned(),
+            certificate_x509: String::from_utf8(builder.build().to_pem().unwrap()).unwrap(),
+            private_key: None,
+        }
+    }
+
+    fn report(days: &[i64]) -> CertificateReport {
+        let mut report = CertificateReport::new(30, 7);
+        report.add_certificates(
+            "127.0.0.1:9003".parse().unwrap(),
+            days.iter()
+                .map(|days| certificate(&format!("cert{days}"), *days))
+                .collect(),
+        );
+        report
+    }
+
+    #[test]
+    fn status_boundaries() {
+        assert_eq!(CheckStatus::of(31, 30, 7), CheckStatus::Ok);
+        assert_eq!(CheckStatus::of(30, 30, 7), CheckStatus::Warning);
+        assert_eq!(CheckStatus::of(8, 30, 7), CheckStatus::Warning);
+        assert_eq!(CheckStatus::of(7, 30, 7), CheckStatus::Critical);
+        assert_eq!(CheckStatus::of(0, 30, 7), CheckStatus::Critical);
+        assert_eq!(CheckStatus::of(-1, 30, 7), CheckStatus::Critical);
+    }
+
+    #[test]
+    fn certificate_boundaries() {
+        assert_eq!(report(&[31]).status(), CheckStatus::Ok);
+        assert_eq!(report(&[30]).status(), CheckStatus::Warning);
+        assert_eq!(report(&[7]).status(), CheckStatus::Critical);
+        let expired = report(&[-3]);
+        assert_eq!(expired.status(), CheckStatus::Critical);
+        assert!(expired.to_string().contains("cert-3 expired on"));
+    }
+
+    #[test]
+    fn exit_code() {
+        assert_eq!(CertificateReport::new(30, 7).exit_code(), 0);
+        assert_eq!(report(&[90]).exit_code(), 0);
+        assert_eq!(report(&[90, 20]).exit_code(), 1);
+        assert_eq!(report(&[90, 20, 5]).exit_code(), 2);
+        let mut unknown = report(&[90, 20]);
+        unknown.add_error("127.0.0.1:9004 : connection refused".to_owned());
+        assert_eq!(unknown.exit_code(), 3);
+        let mut critical = report(&[5]);
+        critical.add_error("127.0.0.1:9004 : connection refused".to_owned());
+        assert_eq!(critical.exit_code(), 2);
+        let mut unparsable = report(&[]);
+        unparsable.add_certificates(
+            "127.0.0.1:9004".parse().unwrap(),
+            vec![CertificateEntity {
+                alias: "broken".to_owned(),
+                certificate_x509: "not a certificate".to_owned(),
+                private_key: None,
+            }],
+        );
+        assert_eq!(unparsable.exit_code(), 2);
+    }
+
+    #[test]
+    fn critical_days_cannot_exceed_warning_days() {
+        let args = |warning: &str, critical: &str| {
+            Args::try_parse_from([
+                "mgwc",
+                "cert",
+                "check",
+                "--warning_days",
+                warning,
+                "--critical_days",
+                critical,
+            ])
+            .unwrap()
+        };
+        assert!(args("30", "30").validate().is_ok());
+        let error = args("7", "30").validate().unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        assert!(error
+            .to_string()
+            .contains("--critical_days 30 is greater than --warning_days 7"));
+    }
+}
@@ -0,0 +1,564 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs::File,
+    io::Read,
+    net::SocketAddr,
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, bail, Result};
+use clap::Args;
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::{ApplicationProfileEntity, BusinessApplicationEntity, SagEntity},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// CSV file holding one entity per row, its header names the JSON fields of the entity
+///
+/// Multi-valued cells, such as userDNs, are separated by `;` like in `--output csv`
+#[derive(Args, Debug, Clone)]
+pub struct CsvImportArgs {
+    /// CSV file, its first line names the columns
+    pub path: PathBuf,
+    /// Map a spreadsheet column to an entity field, such as `DN=userDNs`, can be repeated
+    #[clap(long = "column", value_parser = parse_column)]
+    pub columns: Vec<(String, String)>,
+    /// Cell separator
+    #[clap(long = "delimiter", default_value_t = ',')]
+    pub delimiter: char,
+    /// Update existing entities instead of creating them
+    #[clap(long = "update", action = clap::ArgAction::SetTrue, default_value = "false")]
+    pub update: bool,
+    /// Validate the file and show the operations, without connecting
+    #[clap(long = "dry_run", action = clap::ArgAction::SetTrue, default_value = "false")]
+    pub dry_run: bool,
+}
+
+fn parse_column(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((column, field)) if !column.is_empty() && !field.is_empty() => {
+            Ok((column.to_owned(), field.to_owned()))
+        }
+        _ => Err(format!("{s} is not a column=field mapping")),
+    }
+}
+
+/// How the cells of a column are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellType {
+    Text,
+    Integer,
+    Boolean,
+    /// Values separated by `;`
+    List,
+}
+
+/// Entities that can be imported from CSV rows
+pub trait CsvEntity: DeserializeOwned {
+    /// Name of the entity in reports
+    const NAME: &'static str;
+    /// Fields accepted as columns, by their JSON name
+    const FIELDS: &'static [(&'static str, CellType)];
+    /// Fields identifying the entity, required on every row
+    const KEY: &'static [&'static str];
+
+    fn key(&self) -> String;
+    fn create(self) -> IoEvent;
+    fn update(self) -> IoEvent;
+
+    /// Checks on a parsed entity, beyond the type of its cells
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A distinguished name has at least one `attribute=value` component
+fn check_dns(dns: &[String]) -> Result<(), String> {
+    match dns
+        .iter()
+        .find(|dn| dn.split(',').any(|rdn| !rdn.contains('=')))
+    {
+        Some(dn) => Err(format!("{dn} is not a distinguished name")),
+        None => Ok(()),
+    }
+}
+
+impl CsvEntity for ApplicationProfileEntity {
+    const NAME: &'static str = "profile";
+    const FIELDS: &'static [(&'static str, CellType)] = &[
+        ("applicationName", CellType::Text),
+        ("profileName", CellType::Text),
+        ("rbacScope", CellType::Text),
+        ("userDNs", CellType::List),
+        ("certificateAlias", CellType::Text),
+    ];
+    const KEY: &'static [&'static str] = &["applicationName", "profileName"];
+
+    fn key(&self) -> String {
+        format!("{}/{}", self.application_name, self.profile_name)
+    }
+
+    fn create(self) -> IoEvent {
+        IoEvent::PostProfile(self)
+    }
+
+    fn update(self) -> IoEvent {
+        IoEvent::UpdateProfile(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_dns(self.user_dns.as_deref().unwrap_or_default())
+    }
+}
+
+impl CsvEntity for SagEntity {
+    const NAME: &'static str = "SAG";
+    const FIELDS: &'static [(&'static str, CellType)] = &[
+        ("hostname", CellType::Text),
+        ("port", CellType::Integer),
+        ("messagePartnerName", CellType::Text),
+        ("userDNs", CellType::List),
+        ("lauKey", CellType::Text),
+        ("sslDN", CellType::Text),
+        ("active", CellType::Boolean),
+        ("publicCertificateAlias", CellType::Text),
+    ];
+    const KEY: &'static [&'static str] = &["hostname", "port"];
+
+    fn key(&self) -> String {
+        format!("{}:{}", self.hostname, self.port)
+    }
+
+    fn create(self) -> IoEvent {
+        IoEvent::PostSag(self)
+    }
+
+    fn update(self) -> IoEvent {
+        IoEvent::UpdateSag(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=65535).contains(&self.port) {
+            return Err(format!("port {} is out of range", self.port));
+        }
+        check_dns(&self.user_dns)
+    }
+}
+
+impl CsvEntity for BusinessApplicationEntity {
+    const NAME: &'static str = "business application";
+    const FIELDS: &'static [(&'static str, CellType)] = &[
+        ("applicationName", CellType::Text),
+        ("sharedSecret", CellType::Text),
+    ];
+    const KEY: &'static [&'static str] = &["applicationName"];
+
+    fn key(&self) -> String {
+        self.application_name.to_owned()
+    }
+
+    fn create(self) -> IoEvent {
+        IoEvent::PostBusinessApplication(self)
+    }
+
+    fn update(self) -> IoEvent {
+        IoEvent::UpdateBusinessApplication(self)
+    }
+}
+
+/// A parsed row, or why it was rejected
+#[derive(Debug)]
+struct Row<E> {
+    line: u64,
+    entity: Result<E, String>,
+}
+
+/// Event of a valid row, with the row line and the entity key for the reports
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub line: u64,
+    pub key: String,
+    pub event: IoEvent,
+}
+
+/// Rows imported on each gateway, a row refused by a gateway does not stop the others
+#[derive(Debug, Clone)]
+pub struct ImportReport {
+    name: &'static str,
+    rows: Vec<ImportRow>,
+    imported: usize,
+    failures: Vec<String>,
+}
+
+impl ImportReport {
+    pub fn new(name: &'static str, rows: Vec<ImportRow>) -> ImportReport {
+        ImportReport {
+            name,
+            rows,
+            imported: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Events to dispatch, one per row
+    pub fn events(&self) -> Vec<IoEvent> {
+        self.rows.iter().map(|row| row.event.clone()).collect()
+    }
+
+    fn row(&self, event: &IoEvent) -> Option<&ImportRow> {
+        self.rows.iter().find(|row| row.event == *event)
+    }
+
+    /// Whether `event` is the event of a row, counted as imported
+    pub fn add_success(&mut self, event: &IoEvent) -> bool {
+        let found = self.row(event).is_some();
+        if found {
+            self.imported += 1;
+        }
+        found
+    }
+
+    /// Whether `event` is the event of a row, its failure is reported against the row
+    pub fn add_failure(&mut self, gateway: SocketAddr, event: &IoEvent, error: &str) -> bool {
+        let Some(row) = self.row(event) else {
+            return false;
+        };
+        let failure = format!(
+            "  line {} : {} {} refused by {} : {}",
+            row.line, self.name, row.key, gateway, error
+        );
+        self.failures.push(failure);
+        true
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl std::fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} {} row(s) : {} accepted, {} refused by the gateways",
+            self.rows.len(),
+            self.name,
+            self.imported,
+            self.failures.len()
+        )?;
+        for failure in self.failures.iter() {
+            writeln!(f, "{failure}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CsvImportArgs {
+    /// Report tracking the create or update events of the file entities
+    pub fn report<E: CsvEntity>(&self) -> Result<ImportReport> {
+        Ok(ImportReport::new(E::NAME, self.import_rows::<E>()?))
+    }
+
+    /// Events of the file entities with their row, fails with every invalid row when one is found
+    fn import_rows<E: CsvEntity>(&self) -> Result<Vec<ImportRow>> {
+        let rows = self.rows::<E>()?;
+        let errors = Self::errors(&rows);
+        if !errors.is_empty() {
+            bail!(
+                "{:?} : {} invalid row(s)\n{}",
+                self.path,
+                errors.len(),
+                errors.join("\n")
+            );
+        }
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let entity = row.entity.ok()?;
+                Some(ImportRow {
+                    line: row.line,
+                    key: entity.key(),
+                    event: if self.update {
+                        entity.update()
+                    } else {
+                        entity.create()
+                    },
+                })
+            })
+            .collect())
+    }
+
+    /// The file is checked before connecting, a dry run only reports the operations
+    pub fn run_offline<E: CsvEntity>(&self) -> Option<Result<String>> {
+        if !self.dry_run {
+            return self.import_rows::<E>().err().map(Err);
+        }
+        Some(self.rows::<E>().and_then(|rows| {
+            let operation = if self.update { "update" } else { "create" };
+            let errors = Self::errors(&rows);
+            let mut report = format!(
+                "{:?} : {} row(s), {} invalid\n",
+                self.path,
+                rows.len(),
+                errors.len()
+            );
+            for row in rows.iter() {
+                let _ = match &row.entity {
+                    Ok(e) => writeln!(
+                        report,
+                        "  line {} : {operation} {} {}",
+                        row.line,
+                        E::NAME,
+                        e.key()
+                    ),
+                    Err(e) => writeln!(report, "  line {} : INVALID {}", row.line, e),
+                };
+            }
+            if errors.is_empty() {
+                Ok(report)
+            } else {
+                Err(anyhow!(report))
+            }
+        }))
+    }
+
+    fn errors<E>(rows: &[Row<E>]) -> Vec<String> {
+        rows.iter()
+            .filter_map(|r| {
+                r.entity
+                    .as_ref()
+                    .err()
+                    .map(|e| format!("  line {} : {}", r.line, e))
+            })
+            .collect()
+    }
+
+    fn rows<E: CsvEntity>(&self) -> Result<Vec<Row<E>>> {
+        self.read_rows(File::open(&self.path)?)
+    }
+
+    fn read_rows<E: CsvEntity, R: Read>(&self, input: R) -> Result<Vec<Row<E>>> {
+        let delimiter = u8::try_from(self.delimiter)
+            .map_err(|_| anyhow!("Delimiter {} is not an ASCII character", self.delimiter))?;
+        // Rows with a wrong number of cells are reported with the other invalid rows
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(input);
+        let renames = self
+            .columns
+            .iter()
+            .cloned()
+            .collect::<HashMap<String, String>>();
+        // Other spreadsheet columns, such as comments, are left out
+        let columns = reader
+            .headers()?
+            .iter()
+            .map(|header| {
+                let field = renames.get(header).map(String::as_str).unwrap_or(header);
+                let column = E::FIELDS.iter().find(|(name, _)| *name == field).copied();
+                if column.is_none() {
+                    log::warn!("Column {header} is not a {} field, it is ignored", E::NAME);
+                }
+                column
+            })
+            .collect::<Vec<Option<(&str, CellType)>>>();
+        let mut given = HashSet::new();
+        if let Some((field, _)) = columns
+            .iter()
+            .flatten()
+            .find(|(field, _)| !given.insert(*field))
+        {
+            bail!("Field {field} is given by several columns");
+        }
+        for key in E::KEY {
+            if !given.contains(key) {
+                bail!("Column {key} is required to import a {}", E::NAME);
+            }
+        }
+        let mut keys: HashMap<String, u64> = HashMap::new();
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let (line, entity) = match record {
+                Ok(record) => {
+                    let line = record.position().map(|p| p.line()).unwrap_or_default();
+                    let entity = Self::entity::<E>(&columns, &record).and_then(|entity| match keys
+                        .get(&entity.key())
+                    {
+                        Some(first) => Err(format!(
+                            "{} {} already given line {}",
+                            E::NAME,
+                            entity.key(),
+                            first
+                        )),
+                        None => {
+                            keys.insert(entity.key(), line);
+                            Ok(entity)
+                        }
+                    });
+                    (line, entity)
+                }
+                Err(e) => (
+                    e.position().map(|p| p.line()).unwrap_or_default(),
+                    Err(e.to_string()),
+                ),
+            };
+            rows.push(Row { line, entity });
+        }
+        Ok(rows)
+    }
+
+    fn entity<E: CsvEntity>(
+        columns: &[Option<(&str, CellType)>],
+        record: &csv::StringRecord,
+    ) -> Result<E, String> {
+        if record.len() != columns.len() {
+            return Err(format!(
+                "{} cell(s) found, {} expected",
+                record.len(),
+                columns.len()
+            ));
+        }
+        let mut fields = Map::new();
+        for ((field, cell_type), cell) in columns
+            .iter()
+            .zip(record.iter())
+            .filter_map(|(column, cell)| column.map(|column| (column, cell)))
+        {
+            let value = match cell_type {
+                CellType::List => Value::from(
+                    cell.split(';')
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .collect::<Vec<&str>>(),
+                ),
+                _ if cell.is_empty() => continue,
+                CellType::Text => Value::from(cell),
+                CellType::Integer => Value::from(
+                    cell.parse::<i64>()
+                        .map_err(|_| format!("{field} {cell} is not a number"))?,
+                ),
+                CellType::Boolean => Value::from(match cell.to_lowercase().as_str() {
+                    "true" | "yes" | "1" => true,
+                    "false" | "no" | "0" => false,
+                    _ => return Err(format!("{field} {cell} is not a boolean")),
+                }),
+            };
+            fields.insert(field.to_string(), value);
+        }
+        for key in E::KEY {
+            if !fields.contains_key(*key) {
+                return Err(format!("{key} is empty"));
+            }
+        }
+        let entity =
+            serde_json::from_value::<E>(Value::Object(fields)).map_err(|e| e.to_string())?;
+        entity.validate()?;
+        Ok(entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(columns: &[(&str, &str)]) -> CsvImportArgs {
+        CsvImportArgs {
+            path: PathBuf::from("sags.csv"),
+            columns: columns
+                .iter()
+                .map(|(c, f)| (c.to_string(), f.to_string()))
+                .collect(),
+            delimiter: ',',
+            update: false,
+            dry_run: false,
+        }
+    }
+
+    fn sags(args: &CsvImportArgs, csv: &str) -> Result<Vec<Row<SagEntity>>> {
+        args.read_rows::<SagEntity, _>(csv.as_bytes())
+    }
+
+    #[test]
+    fn valid_row() {
+        let rows = sags(
+            &args(&[("DN", "userDNs")]),
+            "hostname,port,DN,active\nsag1,48002,\"cn=a,o=swift;cn=b,o=swift\",yes\n",
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].line, 2);
+        let sag = rows[0].entity.as_ref().unwrap();
+        assert_eq!(sag.key(), "sag1:48002");
+        assert_eq!(sag.user_dns, vec!["cn=a,o=swift", "cn=b,o=swift"]);
+        assert_eq!(sag.active, Some(true));
+    }
+
+    #[test]
+    fn missing_required_column() {
+        let error = sags(&args(&[]), "hostname,active\nsag1,true\n").unwrap_err();
+        assert_eq!(error.to_string(), "Column port is required to import a SAG");
+    }
+
+    #[test]
+    fn unknown_column_is_ignored() {
+        let rows = sags(
+            &args(&[]),
+            "hostname,comment,port,userDNs\nsag1,primary site,48002,cn=a\n",
+        )
+        .unwrap();
+        assert_eq!(rows[0].entity.as_ref().unwrap().key(), "sag1:48002");
+    }
+
+    #[test]
+    fn bad_value_rejects_its_row() {
+        let rows = sags(
+            &args(&[]),
+            "hostname,port,userDNs,active\nsag1,abc,cn=a,true\nsag2,48002,cn=a,maybe\nsag3,48002,cn=a,no\n",
+        )
+        .unwrap();
+        let errors = CsvImportArgs::errors(&rows);
+        assert_eq!(
+            errors,
+            vec![
+                "  line 2 : port abc is not a number",
+                "  line 3 : active maybe is not a boolean",
+            ]
+        );
+        assert!(rows[2].entity.is_ok());
+    }
+
+    #[test]
+    fn gateway_failure_is_reported_against_its_row() {
+        let rows = sags(
+            &args(&[]),
+            "hostname,port,userDNs\nsag1,48002,\nsag2,48003,\n",
+        )
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let sag = row.entity.unwrap();
+            ImportRow {
+                line: row.line,
+                key: sag.key(),
+                event: sag.create(),
+            }
+        })
+        .collect();
+        let mut report = ImportReport::new(SagEntity::NAME, rows);
+        let events = report.events();
+        let gateway = "127.0.0.1:9003".parse().unwrap();
+        assert!(report.add_success(&events[0]));
+        assert!(report.add_failure(gateway, &events[1], "409 Conflict"));
+        assert!(!report.add_failure(gateway, &IoEvent::GetAllSags, "500"));
+        assert!(!report.is_success());
+        assert_eq!(
+            report.to_string(),
+            "2 SAG row(s) : 1 accepted, 1 refused by the gateways\n  line 3 : SAG sag2:48003 refused by 127.0.0.1:9003 : 409 Conflict\n"
+        );
+    }
+}
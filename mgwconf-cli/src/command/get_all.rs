@@ -1,42 +1,27 @@
+use anyhow::Result;
 use async_trait::async_trait;
-use mgwconf_network::AppTrait;
+use mgwconf_network::event::IoEvent;
 
-use crate::{app::CliApp, config::Config};
+use crate::app::CliApp;
 
-use super::CommandTrait;
+use super::{dispatch_all, CommandTrait};
 
 pub struct GetAll {}
 
 #[async_trait]
 impl CommandTrait for GetAll {
-    async fn execute(app: &CliApp) {
-        tokio::try_join!(
-            <CliApp as AppTrait<Config>>::dispatch(
-                app,
-                mgwconf_network::event::IoEvent::GetAllForwardProxyEntity
-            ),
-            <CliApp as AppTrait<Config>>::dispatch(
-                app,
-                mgwconf_network::event::IoEvent::GetAllBusinessApplications
-            ),
-            <CliApp as AppTrait<Config>>::dispatch(
-                app,
-                mgwconf_network::event::IoEvent::GetAllCertificates
-            ),
-            <CliApp as AppTrait<Config>>::dispatch(
-                app,
-                mgwconf_network::event::IoEvent::GetAllSags
-            ),
-            <CliApp as AppTrait<Config>>::dispatch(
-                app,
-                mgwconf_network::event::IoEvent::GetAllProfiles
-            ),
-            // <CliApp as AppTrait<Config>>::dispatch(app, mgwconf_network::event::IoEvent::GetAllApplicationProfileEntity),
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        dispatch_all(
+            app,
+            vec![
+                IoEvent::GetAllForwardProxyEntity,
+                IoEvent::GetAllBusinessApplications,
+                IoEvent::GetAllCertificates,
+                IoEvent::GetAllSags,
+                IoEvent::GetAllProfiles,
+                IoEvent::GetAllApiClientCredentials,
+            ],
         )
-        .unwrap();
-    }
-
-    fn num_op() -> usize {
-        6
+        .await
     }
 }
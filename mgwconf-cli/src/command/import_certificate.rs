@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use clap::Args;
+use mgwconf_network::{event::IoEvent, model::certificate::CertificateSource, AppTrait};
+use std::path::PathBuf;
+
+use crate::{app::CliApp, config::Config};
+
+use super::{dispatch_all, CommandTrait};
+
+/// Certificate file to import, loaded when the configuration is read
+#[derive(Args, Debug, Clone)]
+pub struct CertificateImportArgs {
+    /// Certificate file to import (.pem, .crt, .der, .p12)
+    #[clap(long = "cert_file")]
+    pub cert_file: PathBuf,
+    /// Alias of the imported certificate
+    #[clap(long = "alias")]
+    pub alias: String,
+    /// Private key file of the imported certificate, when not embedded in the certificate file
+    #[clap(long = "key_file")]
+    pub key_file: Option<PathBuf>,
+    /// Password of a PKCS#12 certificate file, prefer the MGWC_CERT_PASSWORD variable which is not
+    /// shown by ps
+    #[clap(
+        long = "cert_password",
+        env = "MGWC_CERT_PASSWORD",
+        hide_env_values = true
+    )]
+    pub cert_password: Option<String>,
+    /// Import the certificate even if it is expired
+    #[clap(long = "force", action = clap::ArgAction::SetTrue, default_value = "false")]
+    pub force: bool,
+}
+
+impl CertificateImportArgs {
+    pub fn source(&self) -> CertificateSource {
+        CertificateSource {
+            alias: self.alias.to_owned(),
+            path: self.cert_file.to_owned(),
+            private_key: self.key_file.clone(),
+            password: self.cert_password.clone(),
+            force: self.force,
+        }
+    }
+}
+
+/// Import the certificate file given with `--cert_file` under the `--alias` alias
+pub struct ImportCertificate {}
+
+#[async_trait]
+impl CommandTrait for ImportCertificate {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let entity = <CliApp as AppTrait<Config>>::config(app)
+            .certificate_import
+            .clone()
+            .ok_or(anyhow!("--cert_file is required to import a certificate"))?;
+        dispatch_all(app, vec![IoEvent::PostCertificate(entity)]).await
+    }
+}
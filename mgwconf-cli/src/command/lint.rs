@@ -0,0 +1,245 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::{
+        ApiCredentialsEntity, ApplicationProfileEntity, BusinessApplicationEntity,
+        CertificateEntity, SagEntity,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    net::SocketAddr,
+};
+
+use crate::app::CliApp;
+
+use super::{dispatch_all, CommandTrait};
+
+/// Load every entity kind of the targeted gateways and report broken or suspicious references
+pub struct Lint {}
+
+#[async_trait]
+impl CommandTrait for Lint {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        dispatch_all(
+            app,
+            vec![
+                IoEvent::GetAllCertificates,
+                IoEvent::GetAllSags,
+                IoEvent::GetAllProfiles,
+                IoEvent::GetAllBusinessApplications,
+                IoEvent::GetAllApiClientCredentials,
+            ],
+        )
+        .await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Warning => write!(f, "WARNING"),
+            LintSeverity::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LintIssue {
+    severity: LintSeverity,
+    gateway: SocketAddr,
+    message: String,
+}
+
+#[derive(Debug, Default, Clone)]
+struct GatewayEntities {
+    certificates: Vec<CertificateEntity>,
+    sags: Vec<SagEntity>,
+    profiles: Vec<ApplicationProfileEntity>,
+    business_applications: Vec<BusinessApplicationEntity>,
+    api_client_credentials: Vec<ApiCredentialsEntity>,
+}
+
+/// Collects every entity of each gateway, then checks references between them
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    gateways: BTreeMap<SocketAddr, GatewayEntities>,
+    errors: Vec<String>,
+}
+
+impl LintReport {
+    pub fn new() -> LintReport {
+        LintReport::default()
+    }
+
+    /// Store the entities returned for `event`, in the JSON `content` of the response
+    pub fn add_response(
+        &mut self,
+        gateway: SocketAddr,
+        event: &IoEvent,
+        content: &str,
+    ) -> Result<(), serde_json::Error> {
+        let entities = self.gateways.entry(gateway).or_default();
+        match event {
+            IoEvent::GetAllCertificates => entities.certificates = serde_json::from_str(content)?,
+            IoEvent::GetAllSags => entities.sags = serde_json::from_str(content)?,
+            IoEvent::GetAllProfiles => entities.profiles = serde_json::from_str(content)?,
+            IoEvent::GetAllBusinessApplications => {
+                entities.business_applications = serde_json::from_str(content)?
+            }
+            IoEvent::GetAllApiClientCredentials => {
+                entities.api_client_credentials = serde_json::from_str(content)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn add_error(&mut self, error: String) {
+        self.errors.push(error);
+    }
+
+    fn issues(&self) -> Vec<LintIssue> {
+        let mut issues = self
+            .gateways
+            .iter()
+            .flat_map(|(addr, gateway)| gateway.lint(*addr))
+            .collect::<Vec<LintIssue>>();
+        issues.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.gateway.cmp(&b.gateway)));
+        issues
+    }
+
+    /// Non zero when an error has been found or a gateway could not be read
+    pub fn exit_code(&self) -> i32 {
+        let failed = !self.errors.is_empty()
+            || self
+                .issues()
+                .iter()
+                .any(|i| i.severity == LintSeverity::Error);
+        i32::from(failed)
+    }
+}
+
+impl GatewayEntities {
+    fn lint(&self, gateway: SocketAddr) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |severity: LintSeverity, message: String| {
+            issues.push(LintIssue {
+                severity,
+                gateway,
+                message,
+            })
+        };
+        let aliases = self
+            .certificates
+            .iter()
+            .map(|c| c.alias.as_str())
+            .collect::<HashSet<&str>>();
+        let applications = self
+            .business_applications
+            .iter()
+            .map(|b| b.application_name.as_str())
+            .collect::<HashSet<&str>>();
+        for sag in self.sags.iter() {
+            if let Some(alias) = &sag.public_certificate_alias {
+                if !aliases.contains(alias.as_str()) {
+                    issue(
+                        LintSeverity::Error,
+                        format!(
+                            "SAG {}:{} uses unknown certificate {}",
+                            sag.hostname, sag.port, alias
+                        ),
+                    );
+                }
+            }
+            if sag.active == Some(false) {
+                issue(
+                    LintSeverity::Warning,
+                    format!("SAG {}:{} is inactive", sag.hostname, sag.port),
+                );
+            }
+        }
+        let mut user_dns: HashMap<&str, Vec<String>> = HashMap::new();
+        for profile in self.profiles.iter() {
+            let name = format!("{}/{}", profile.application_name, profile.profile_name);
+            if !applications.contains(profile.application_name.as_str()) {
+                issue(
+                    LintSeverity::Error,
+                    format!(
+                        "Profile {} belongs to unknown business application {}",
+                        name, profile.application_name
+                    ),
+                );
+            }
+            if let Some(alias) = &profile.certificate_alias {
+                if !aliases.contains(alias.as_str()) {
+                    issue(
+                        LintSeverity::Error,
+                        format!("Profile {} uses unknown certificate {}", name, alias),
+                    );
+                }
+            }
+            for dn in profile.user_dns.iter().flatten() {
+                user_dns.entry(dn).or_default().push(name.to_owned());
+            }
+        }
+        for credential in self.api_client_credentials.iter() {
+            if !applications.contains(credential.application_name.as_str()) {
+                issue(
+                    LintSeverity::Error,
+                    format!(
+                        "API client credential {} belongs to unknown business application {}",
+                        credential.consumer_key, credential.application_name
+                    ),
+                );
+            }
+        }
+        let mut duplicates = user_dns
+            .into_iter()
+            .filter(|(_, profiles)| profiles.len() > 1)
+            .collect::<Vec<(&str, Vec<String>)>>();
+        duplicates.sort();
+        for (dn, profiles) in duplicates {
+            issue(
+                LintSeverity::Warning,
+                format!("userDN {} is used by profiles {}", dn, profiles.join(", ")),
+            );
+        }
+        issues
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issues = self.issues();
+        let count =
+            |severity: LintSeverity| issues.iter().filter(|i| i.severity == severity).count();
+        writeln!(
+            f,
+            "LINT - {} gateway(s), {} error(s), {} warning(s), {} fetch error(s)",
+            self.gateways.len(),
+            count(LintSeverity::Error),
+            count(LintSeverity::Warning),
+            self.errors.len()
+        )?;
+        for error in self.errors.iter() {
+            writeln!(f, "[FETCH ERROR] {}", error)?;
+        }
+        for issue in issues.iter() {
+            writeln!(
+                f,
+                "[{}] {} {}",
+                issue.severity, issue.gateway, issue.message
+            )?;
+        }
+        Ok(())
+    }
+}
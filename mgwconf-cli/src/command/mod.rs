@@ -1,28 +1,357 @@
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::{ApplicationProfileEntity, BusinessApplicationEntity, SagEntity},
+    AppTrait,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{fs::read_to_string, path::PathBuf, str::FromStr};
 
-use crate::app::CliApp;
+use crate::{app::CliApp, config::Config};
 
+use self::{
+    api_client_credential::CredCommand, business_application::AppCommand, certificate::CertCommand,
+    csv_import::ImportReport, get_all::GetAll, lint::Lint, playbook::PlaybookCommand,
+    profile::ProfileCommand, proxy::ProxyCommand, sag::SagCommand,
+};
+
+pub mod api_client_credential;
+pub mod business_application;
+pub mod certificate;
+pub mod check_certificates;
+pub mod csv_import;
 pub mod get_all;
-pub mod get_api_client_credential;
-pub mod get_business_application;
-pub mod get_certificate;
-pub mod get_profile;
-pub mod get_proxy;
-pub mod get_sag;
-pub mod registry;
+pub mod import_certificate;
+pub mod lint;
+pub mod playbook;
+pub mod profile;
+pub mod proxy;
+pub mod rotate_certificate;
+pub mod sag;
+#[cfg(feature = "store")]
+pub mod vault;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Manage SAG connections
+    #[clap(subcommand)]
+    Sag(SagCommand),
+    /// Manage certificates
+    #[clap(subcommand)]
+    Cert(CertCommand),
+    /// Manage application profiles
+    #[clap(subcommand)]
+    Profile(ProfileCommand),
+    /// Manage forward proxies
+    #[clap(subcommand)]
+    Proxy(ProxyCommand),
+    /// Manage business applications
+    #[clap(subcommand)]
+    App(AppCommand),
+    /// Manage API client credentials
+    #[clap(subcommand)]
+    Cred(CredCommand),
+    /// Run, validate or plan a playbook
+    #[clap(subcommand)]
+    Playbook(PlaybookCommand),
+    /// Fetch every entity
+    All,
+    /// Report broken or suspicious references between entities
+    Lint,
+    /// Manage the secrets vault
+    #[cfg(feature = "store")]
+    #[clap(subcommand)]
+    Vault(vault::VaultCommand),
+}
+
+impl Command {
+    /// Fields identifying the entity shown by a `get` subcommand, by their JSON name
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            Command::Sag(c) => c.selection(),
+            Command::Cert(c) => c.selection(),
+            Command::Profile(c) => c.selection(),
+            Command::Proxy(c) => c.selection(),
+            Command::App(c) => c.selection(),
+            Command::Cred(c) => c.selection(),
+            Command::Playbook(_) | Command::All | Command::Lint => Vec::new(),
+            #[cfg(feature = "store")]
+            Command::Vault(_) => Vec::new(),
+        }
+    }
 
-pub trait CommandRegistryTrait {
-    fn execute(
-        &self,
-        app: CliApp,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = usize> + Send>>;
+    /// Report of a command that does not need a gateway, such as a dry run
+    pub fn run_offline(&self) -> Option<Result<String>> {
+        match self {
+            Command::Playbook(c) => c.run_offline().map(|r| r.map_err(Into::into)),
+            Command::Sag(SagCommand::Import(args)) => args.run_offline::<SagEntity>(),
+            Command::Profile(ProfileCommand::Import(args)) => {
+                args.run_offline::<ApplicationProfileEntity>()
+            }
+            Command::App(AppCommand::Import(args)) => {
+                args.run_offline::<BusinessApplicationEntity>()
+            }
+            _ => None,
+        }
+    }
 
-    fn name(&self) -> &'static str;
+    /// Report of an import, built before connecting so that gateway failures are told by row
+    pub fn import_report(&self) -> Option<Result<ImportReport>> {
+        match self {
+            Command::Sag(SagCommand::Import(args)) => Some(args.report::<SagEntity>()),
+            Command::Profile(ProfileCommand::Import(args)) => {
+                Some(args.report::<ApplicationProfileEntity>())
+            }
+            Command::App(AppCommand::Import(args)) => {
+                Some(args.report::<BusinessApplicationEntity>())
+            }
+            _ => None,
+        }
+    }
 }
 
 #[async_trait]
 pub trait CommandTrait {
-    async fn execute(app: &CliApp);
+    /// Dispatch the events of the command, returns the number of responses expected from each gateway
+    async fn execute(&self, app: &CliApp) -> Result<usize>;
+}
+
+#[async_trait]
+impl CommandTrait for Command {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        match self {
+            Command::Sag(c) => c.execute(app).await,
+            Command::Cert(c) => c.execute(app).await,
+            Command::Profile(c) => c.execute(app).await,
+            Command::Proxy(c) => c.execute(app).await,
+            Command::App(c) => c.execute(app).await,
+            Command::Cred(c) => c.execute(app).await,
+            Command::Playbook(c) => c.execute(app).await,
+            Command::All => GetAll {}.execute(app).await,
+            Command::Lint => Lint {}.execute(app).await,
+            #[cfg(feature = "store")]
+            Command::Vault(c) => c.execute(app).await,
+        }
+    }
+}
+
+/// Entities given inline or in a file, used by create and update subcommands
+#[derive(Args, Debug, Clone)]
+#[group(required = true, multiple = false)]
+pub struct EntityInput {
+    /// JSON file containing an array of entities
+    #[clap(long = "file")]
+    pub file: Option<PathBuf>,
+    /// A single entity as JSON
+    #[clap(long = "json")]
+    pub json: Option<String>,
+}
+
+impl EntityInput {
+    pub fn entities<E: DeserializeOwned>(&self) -> Result<Vec<E>> {
+        if let Some(json) = &self.json {
+            Ok(vec![serde_json::from_str(json)?])
+        } else if let Some(file) = &self.file {
+            Ok(serde_json::from_str(&read_to_string(file)?)?)
+        } else {
+            bail!("Either --file or --json is required")
+        }
+    }
+}
+
+/// Dispatch every event, returns the number of responses to wait for
+pub async fn dispatch_all(app: &CliApp, events: Vec<IoEvent>) -> Result<usize> {
+    let num_op = events.len();
+    for event in events.into_iter() {
+        <CliApp as AppTrait<Config>>::dispatch(app, event).await?;
+    }
+    Ok(num_op)
+}
+
+/// Field predicate given with `--where`, such as `active=false` or `certificateAlias!=old`
+///
+/// Fields use the JSON names of entities, nested fields are separated by dots.
+/// Values are compared to the text of the field, a missing field reads as empty
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Predicate {
+    field: String,
+    value: String,
+    negated: bool,
+}
+
+impl FromStr for Predicate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The first `=` is the operator, values such as DNs may hold others
+        let Some((field, value)) = s.split_once('=') else {
+            return Err(format!(
+                "{s} is not a field=value or field!=value predicate"
+            ));
+        };
+        let (field, negated) = match field.strip_suffix('!') {
+            Some(field) => (field, true),
+            None => (field, false),
+        };
+        if field.is_empty() {
+            return Err(format!("{s} has no field name"));
+        }
+        Ok(Predicate {
+            field: field.to_owned(),
+            value: value.to_owned(),
+            negated,
+        })
+    }
+}
+
+impl Predicate {
+    pub fn matches(&self, entity: &Value) -> bool {
+        // JSON pointer tokens escape `~` and `/`, see RFC 6901
+        let pointer = self
+            .field
+            .split('.')
+            .map(|name| format!("/{}", name.replace('~', "~0").replace('/', "~1")))
+            .collect::<String>();
+        let text = match entity.pointer(&pointer) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.to_owned(),
+            Some(v) => v.to_string(),
+        };
+        (text == self.value) != self.negated
+    }
+}
+
+/// Parse the entities of a response, keeping those matching every field of the selection and every predicate
+///
+/// Gateways answer a filtered query with either a list or a single entity
+pub fn select<E: DeserializeOwned>(
+    content: &str,
+    selection: &[(&str, Value)],
+    predicates: &[Predicate],
+) -> Result<Vec<E>, serde_json::Error> {
+    let entities =
+        match serde_json::from_str::<Value>(if content.is_empty() { "[]" } else { content })? {
+            Value::Array(entities) => entities,
+            entity => vec![entity],
+        };
+    entities
+        .into_iter()
+        .filter(|entity| {
+            selection
+                .iter()
+                .all(|(field, value)| entity.get(field) == Some(value))
+                && predicates.iter().all(|p| p.matches(entity))
+        })
+        .map(serde_json::from_value)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use mgwconf_network::mgw_configuration::models::SagEntity;
+    use serde_json::json;
+
+    use super::*;
+
+    fn predicate(s: &str) -> Predicate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_predicates() {
+        assert_eq!(
+            predicate("active=false"),
+            Predicate {
+                field: "active".to_owned(),
+                value: "false".to_owned(),
+                negated: false,
+            }
+        );
+        assert_eq!(
+            predicate("certificateAlias!=old"),
+            Predicate {
+                field: "certificateAlias".to_owned(),
+                value: "old".to_owned(),
+                negated: true,
+            }
+        );
+        assert_eq!(predicate("sslDN=cn=a,o=b!=c").value, "cn=a,o=b!=c");
+        assert_eq!(predicate("alias=").value, "");
+    }
+
+    #[test]
+    fn parse_malformed_predicates() {
+        assert_eq!(
+            "foo".parse::<Predicate>(),
+            Err("foo is not a field=value or field!=value predicate".to_owned())
+        );
+        assert_eq!(
+            "=bar".parse::<Predicate>(),
+            Err("=bar has no field name".to_owned())
+        );
+        assert_eq!(
+            "!=bar".parse::<Predicate>(),
+            Err("!=bar has no field name".to_owned())
+        );
+    }
+
+    #[test]
+    fn match_fields() {
+        let sag = json!({
+            "hostname": "sag1",
+            "port": 48002,
+            "active": false,
+            "tls": {"mode": "TWO_WAY", "peer": {"dn": "cn=a"}},
+            "path/to": "slash",
+            "a~b": "tilde",
+        });
+        assert!(predicate("hostname=sag1").matches(&sag));
+        assert!(!predicate("hostname!=sag1").matches(&sag));
+        assert!(predicate("port=48002").matches(&sag));
+        assert!(predicate("active=false").matches(&sag));
+        assert!(predicate("tls.mode=TWO_WAY").matches(&sag));
+        assert!(predicate("tls.peer.dn=cn=a").matches(&sag));
+        assert!(predicate("tls.peer.dn!=cn=b").matches(&sag));
+        assert!(predicate("path/to=slash").matches(&sag));
+        assert!(predicate("a~b=tilde").matches(&sag));
+        // A missing field reads as empty
+        assert!(predicate("lauKey=").matches(&sag));
+        assert!(!predicate("lauKey!=").matches(&sag));
+        assert!(!predicate("tls.missing=TWO_WAY").matches(&sag));
+    }
 
-    fn num_op() -> usize;
+    #[test]
+    fn select_entities() {
+        let content = json!([
+            {"hostname": "sag1", "port": 48002, "userDNs": [], "active": true},
+            {"hostname": "sag2", "port": 48002, "userDNs": [], "active": false},
+            {"hostname": "sag2", "port": 48003, "userDNs": [], "active": true},
+        ])
+        .to_string();
+        let hostnames = |selection: &[(&str, Value)], predicates: &[Predicate]| {
+            select::<SagEntity>(&content, selection, predicates)
+                .unwrap()
+                .into_iter()
+                .map(|sag| format!("{}:{}", sag.hostname, sag.port))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            hostnames(&[("hostname", json!("sag2"))], &[]),
+            vec!["sag2:48002", "sag2:48003"]
+        );
+        assert_eq!(
+            hostnames(&[("hostname", json!("sag2"))], &[predicate("active=true")]),
+            vec!["sag2:48003"]
+        );
+        assert_eq!(
+            hostnames(&[], &[predicate("port!=48002")]),
+            vec!["sag2:48003"]
+        );
+        let single = json!({"hostname": "sag1", "port": 48002, "userDNs": []}).to_string();
+        assert_eq!(select::<SagEntity>(&single, &[], &[]).unwrap().len(), 1);
+        assert!(select::<SagEntity>("", &[], &[]).unwrap().is_empty());
+    }
 }
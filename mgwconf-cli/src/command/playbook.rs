@@ -0,0 +1,56 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use crate::{
+    app::CliApp,
+    playbook::{error::PlaybookError, Playbook},
+};
+
+use super::CommandTrait;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PlaybookCommand {
+    /// Apply a playbook on its hosts
+    Run(PlaybookArgs),
+    /// Check that a playbook and the files it references can be read, without connecting
+    Validate(PlaybookArgs),
+    /// Show the operations of a playbook, without connecting
+    Plan(PlaybookArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PlaybookArgs {
+    /// Playbook yaml file
+    pub path: PathBuf,
+}
+
+impl PlaybookCommand {
+    /// Validate and plan do not need a gateway, their report is returned when the playbook is fine
+    pub fn run_offline(&self) -> Option<Result<String, PlaybookError>> {
+        match self {
+            PlaybookCommand::Run(_) => None,
+            PlaybookCommand::Validate(args) => Some(
+                Playbook::read(&args.path)
+                    .and_then(|p| p.plan())
+                    .map(|ops| format!("{:?} is valid, {} operation(s)", args.path, ops.len())),
+            ),
+            PlaybookCommand::Plan(args) => Some(Playbook::read(&args.path).and_then(|p| {
+                let mut report = format!("Hosts : {:?}\n", p.entries.hosts);
+                for operation in p.plan()? {
+                    report.push_str(&format!("  {operation}\n"));
+                }
+                Ok(report)
+            })),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for PlaybookCommand {
+    /// Playbooks are processed by the app from the configuration, nothing to dispatch here
+    async fn execute(&self, _app: &CliApp) -> Result<usize> {
+        Ok(0)
+    }
+}
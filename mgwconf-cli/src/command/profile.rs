@@ -0,0 +1,97 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{event::IoEvent, mgw_configuration::models::ApplicationProfileEntity};
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{csv_import::CsvImportArgs, dispatch_all, CommandTrait, EntityInput};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProfileCommand {
+    /// List every application profile
+    List,
+    /// Show the profiles of a business application, or a single one
+    Get(ProfileQuery),
+    /// Create application profiles
+    Create(EntityInput),
+    /// Create or update application profiles from a CSV file
+    Import(CsvImportArgs),
+    /// Update application profiles, identified by their application and profile names
+    Update(EntityInput),
+    /// Delete an application profile
+    Delete(ProfileKey),
+}
+
+/// Identifies an application profile
+#[derive(Args, Debug, Clone)]
+pub struct ProfileKey {
+    /// Business application name
+    #[clap(long = "application_name")]
+    pub application_name: String,
+    /// Profile name
+    #[clap(long = "profile_name")]
+    pub profile_name: String,
+}
+
+/// Profiles of a business application, optionally narrowed to one profile
+#[derive(Args, Debug, Clone)]
+pub struct ProfileQuery {
+    /// Business application name
+    #[clap(long = "application_name")]
+    pub application_name: String,
+    /// Profile name
+    #[clap(long = "profile_name")]
+    pub profile_name: Option<String>,
+}
+
+impl ProfileCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "profiles";
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            ProfileCommand::Get(query) => {
+                let mut selection =
+                    vec![("applicationName", query.application_name.clone().into())];
+                if let Some(profile_name) = &query.profile_name {
+                    selection.push(("profileName", profile_name.clone().into()));
+                }
+                selection
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for ProfileCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            ProfileCommand::List => vec![IoEvent::GetAllProfiles],
+            ProfileCommand::Get(query) => vec![IoEvent::GetProfiles(
+                query.application_name.to_owned(),
+                query.profile_name.to_owned(),
+            )],
+            ProfileCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostProfile)
+                .collect(),
+            ProfileCommand::Import(_) => app.import_events()?,
+            ProfileCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateProfile)
+                .collect(),
+            ProfileCommand::Delete(key) => {
+                vec![IoEvent::DeleteProfile(ApplicationProfileEntity::new(
+                    key.application_name.to_owned(),
+                    key.profile_name.to_owned(),
+                ))]
+            }
+        };
+        dispatch_all(app, events).await
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{event::IoEvent, mgw_configuration::models::ForwardProxyEntity};
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{dispatch_all, CommandTrait, EntityInput};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyCommand {
+    /// List every forward proxy
+    List,
+    /// Show a single forward proxy
+    Get(ProxyKey),
+    /// Create forward proxies
+    Create(EntityInput),
+    /// Update forward proxies, identified by their hostname and port
+    Update(EntityInput),
+    /// Delete a forward proxy
+    Delete(ProxyKey),
+}
+
+/// Identifies a forward proxy
+#[derive(Args, Debug, Clone)]
+pub struct ProxyKey {
+    /// Proxy hostname
+    #[clap(long = "hostname")]
+    pub hostname: String,
+    /// Proxy port
+    #[clap(long = "port")]
+    pub port: i32,
+}
+
+impl ProxyCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "proxies";
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            ProxyCommand::Get(key) => vec![
+                ("hostname", key.hostname.clone().into()),
+                ("port", key.port.into()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for ProxyCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            ProxyCommand::List | ProxyCommand::Get(_) => vec![IoEvent::GetAllForwardProxyEntity],
+            ProxyCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostForwardProxyEntity)
+                .collect(),
+            ProxyCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateForwardProxyEntity)
+                .collect(),
+            ProxyCommand::Delete(key) => vec![IoEvent::DeleteForwardProxyEntity(
+                ForwardProxyEntity::new(key.hostname.to_owned(), key.port),
+            )],
+        };
+        dispatch_all(app, events).await
+    }
+}
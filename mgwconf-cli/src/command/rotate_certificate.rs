@@ -0,0 +1,411 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use clap::Args;
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::models::{ApplicationProfileEntity, CertificateEntity, SagEntity},
+};
+
+use crate::app::CliApp;
+
+use super::{dispatch_all, import_certificate::CertificateImportArgs, CommandTrait};
+
+/// Certificate replaced by a rotation, and the file of its successor
+#[derive(Args, Debug, Clone)]
+pub struct RotateArgs {
+    /// Alias of the certificate to replace
+    #[clap(long = "old_alias")]
+    pub old_alias: String,
+    #[command(flatten)]
+    pub import: CertificateImportArgs,
+}
+
+/// Replace the certificate `--old_alias` by the one given with `--cert_file` and `--alias`
+///
+/// References are discovered first, the rotation itself is driven by `CertificateRotation`
+pub struct RotateCertificate {}
+
+#[async_trait]
+impl CommandTrait for RotateCertificate {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        dispatch_all(
+            app,
+            vec![
+                IoEvent::GetAllCertificates,
+                IoEvent::GetAllSags,
+                IoEvent::GetAllProfiles,
+            ],
+        )
+        .await
+    }
+}
+
+/// A single change applied on the gateway during a rotation
+#[derive(Debug, Clone)]
+enum RotationStep {
+    CreateCertificate(CertificateEntity),
+    UpdateSag {
+        before: SagEntity,
+        after: SagEntity,
+    },
+    UpdateProfile {
+        before: ApplicationProfileEntity,
+        after: ApplicationProfileEntity,
+    },
+    DeleteCertificate(CertificateEntity),
+}
+
+impl RotationStep {
+    fn event(&self) -> IoEvent {
+        match self {
+            RotationStep::CreateCertificate(c) => IoEvent::PostCertificate(c.clone()),
+            RotationStep::UpdateSag { after, .. } => IoEvent::UpdateSag(after.clone()),
+            RotationStep::UpdateProfile { after, .. } => IoEvent::UpdateProfile(after.clone()),
+            RotationStep::DeleteCertificate(c) => IoEvent::DeleteCertificate(c.clone()),
+        }
+    }
+
+    /// Event reverting this step once it has been applied
+    fn undo(&self) -> IoEvent {
+        match self {
+            RotationStep::CreateCertificate(c) => IoEvent::DeleteCertificate(c.clone()),
+            RotationStep::UpdateSag { before, .. } => IoEvent::UpdateSag(before.clone()),
+            RotationStep::UpdateProfile { before, .. } => IoEvent::UpdateProfile(before.clone()),
+            RotationStep::DeleteCertificate(c) => IoEvent::PostCertificate(c.clone()),
+        }
+    }
+}
+
+/// Rotation state machine, one request is in flight at a time so that a failure can be rolled back
+///
+/// Steps are: create the new certificate, repoint every SAG then every profile, delete the old certificate.
+/// On failure, applied steps are reverted in reverse order.
+#[derive(Debug, Clone)]
+pub struct CertificateRotation {
+    old_alias: String,
+    certificate: CertificateEntity,
+    certificates: Option<Vec<CertificateEntity>>,
+    sags: Option<Vec<SagEntity>>,
+    profiles: Option<Vec<ApplicationProfileEntity>>,
+    pending: VecDeque<RotationStep>,
+    current: Option<RotationStep>,
+    applied: Vec<RotationStep>,
+    rollback: VecDeque<IoEvent>,
+    rolling_back: bool,
+    errors: Vec<String>,
+    rollback_errors: Vec<String>,
+}
+
+impl CertificateRotation {
+    pub fn new(old_alias: String, certificate: CertificateEntity) -> CertificateRotation {
+        CertificateRotation {
+            old_alias,
+            certificate,
+            certificates: None,
+            sags: None,
+            profiles: None,
+            pending: VecDeque::new(),
+            current: None,
+            applied: Vec::new(),
+            rollback: VecDeque::new(),
+            rolling_back: false,
+            errors: Vec::new(),
+            rollback_errors: Vec::new(),
+        }
+    }
+
+    pub fn add_certificates(&mut self, certificates: Vec<CertificateEntity>) {
+        self.certificates = Some(certificates);
+    }
+
+    pub fn add_sags(&mut self, sags: Vec<SagEntity>) {
+        self.sags = Some(sags);
+    }
+
+    pub fn add_profiles(&mut self, profiles: Vec<ApplicationProfileEntity>) {
+        self.profiles = Some(profiles);
+    }
+
+    /// Whether references have been discovered and steps can be planned
+    pub fn is_discovered(&self) -> bool {
+        self.certificates.is_some() && self.sags.is_some() && self.profiles.is_some()
+    }
+
+    /// Compute the steps of the rotation from the discovered entities
+    ///
+    /// Updates put the listed entities back, SAGs whose LAU key is not returned are refused since
+    /// their update would wipe it. Profiles hold no secret
+    pub fn plan(&mut self) -> Result<()> {
+        let certificates = self
+            .certificates
+            .as_ref()
+            .ok_or(anyhow!("Certificates not fetched"))?;
+        let old = certificates
+            .iter()
+            .find(|c| c.alias == self.old_alias)
+            .ok_or_else(|| anyhow!("Certificate {} does not exist", self.old_alias))?;
+        if certificates
+            .iter()
+            .any(|c| c.alias == self.certificate.alias)
+        {
+            bail!("Certificate {} already exists", self.certificate.alias);
+        }
+        let mut steps = vec![RotationStep::CreateCertificate(self.certificate.clone())];
+        for sag in self.sags.iter().flatten() {
+            if sag.public_certificate_alias.as_ref() == Some(&self.old_alias) {
+                if sag
+                    .lau_key
+                    .as_ref()
+                    .is_none_or(|key| key.is_empty() || key.chars().all(|c| c == '*'))
+                {
+                    bail!(
+                        "SAG {}:{} does not return its LAU key, repoint it to {} by hand",
+                        sag.hostname,
+                        sag.port,
+                        self.certificate.alias
+                    );
+                }
+                let mut after = sag.clone();
+                after.public_certificate_alias = Some(self.certificate.alias.to_owned());
+                steps.push(RotationStep::UpdateSag {
+                    before: sag.clone(),
+                    after,
+                });
+            }
+        }
+        for profile in self.profiles.iter().flatten() {
+            if profile.certificate_alias.as_ref() == Some(&self.old_alias) {
+                let mut after = profile.clone();
+                after.certificate_alias = Some(self.certificate.alias.to_owned());
+                steps.push(RotationStep::UpdateProfile {
+                    before: profile.clone(),
+                    after,
+                });
+            }
+        }
+        steps.push(RotationStep::DeleteCertificate(old.clone()));
+        log::info!(
+            "Rotating certificate {} to {} in {} steps",
+            self.old_alias,
+            self.certificate.alias,
+            steps.len()
+        );
+        self.pending = steps.into();
+        Ok(())
+    }
+
+    /// Next event to dispatch, `None` once the rotation or its rollback is over
+    pub fn next_event(&mut self) -> Option<IoEvent> {
+        if self.rolling_back {
+            return self.rollback.pop_front();
+        }
+        self.current = self.pending.pop_front();
+        self.current.as_ref().map(RotationStep::event)
+    }
+
+    /// The in flight request succeeded
+    pub fn step_succeeded(&mut self) {
+        if let Some(step) = self.current.take() {
+            self.applied.push(step);
+        }
+    }
+
+    /// The in flight request failed, every applied step is reverted
+    pub fn step_failed(&mut self, error: String) {
+        if self.rolling_back {
+            self.rollback_errors.push(error);
+            return;
+        }
+        self.errors.push(error);
+        self.rolling_back = true;
+        self.current = None;
+        self.pending.clear();
+        self.rollback = self.applied.iter().rev().map(RotationStep::undo).collect();
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.is_success() {
+            format!(
+                "Certificate {} rotated to {}, {} step(s) applied",
+                self.old_alias,
+                self.certificate.alias,
+                self.applied.len()
+            )
+        } else if !self.rollback_errors.is_empty() {
+            format!(
+                "Certificate rotation from {} to {} failed : {}, its rollback failed too, check the gateway : {}",
+                self.old_alias,
+                self.certificate.alias,
+                self.errors.join(", "),
+                self.rollback_errors.join(", ")
+            )
+        } else {
+            format!(
+                "Certificate rotation from {} to {} failed and was rolled back : {}",
+                self.old_alias,
+                self.certificate.alias,
+                self.errors.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate(alias: &str) -> CertificateEntity {
+        CertificateEntity {
+            alias: alias.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn sag(hostname: &str, alias: &str) -> SagEntity {
+        SagEntity {
+            hostname: hostname.to_owned(),
+            port: 48002,
+            lau_key: Some("0123456789abcdef".to_owned()),
+            public_certificate_alias: Some(alias.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    fn profile(name: &str, alias: &str) -> ApplicationProfileEntity {
+        ApplicationProfileEntity {
+            application_name: "app".to_owned(),
+            profile_name: name.to_owned(),
+            certificate_alias: Some(alias.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    fn discovered(sags: Vec<SagEntity>) -> CertificateRotation {
+        let mut rotation = CertificateRotation::new("old".to_owned(), certificate("new"));
+        rotation.add_certificates(vec![certificate("old"), certificate("other")]);
+        rotation.add_sags(sags);
+        rotation.add_profiles(vec![profile("p1", "old"), profile("p2", "other")]);
+        rotation
+    }
+
+    fn repointed<T: Clone>(entity: &T, repoint: fn(&mut T)) -> T {
+        let mut entity = entity.clone();
+        repoint(&mut entity);
+        entity
+    }
+
+    #[test]
+    fn references_are_repointed_before_the_old_certificate_is_deleted() {
+        let sags = vec![sag("s1", "old"), sag("s2", "other")];
+        let mut rotation = discovered(sags.clone());
+        assert!(rotation.is_discovered());
+        rotation.plan().unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = rotation.next_event() {
+            events.push(event);
+            rotation.step_succeeded();
+        }
+        assert_eq!(
+            events,
+            vec![
+                IoEvent::PostCertificate(certificate("new")),
+                IoEvent::UpdateSag(repointed(&sags[0], |s| {
+                    s.public_certificate_alias = Some("new".to_owned())
+                })),
+                IoEvent::UpdateProfile(repointed(&profile("p1", "old"), |p| {
+                    p.certificate_alias = Some("new".to_owned())
+                })),
+                IoEvent::DeleteCertificate(certificate("old")),
+            ]
+        );
+        assert!(rotation.is_success());
+        assert_eq!(
+            rotation.summary(),
+            "Certificate old rotated to new, 4 step(s) applied"
+        );
+    }
+
+    #[test]
+    fn plan_checks_the_certificates() {
+        let mut rotation = discovered(Vec::new());
+        rotation.old_alias = "missing".to_owned();
+        assert!(rotation.plan().is_err());
+
+        let mut rotation = discovered(Vec::new());
+        rotation.certificate = certificate("other");
+        assert!(rotation.plan().is_err());
+
+        let mut rotation = CertificateRotation::new("old".to_owned(), certificate("new"));
+        assert!(!rotation.is_discovered());
+        assert!(rotation.plan().is_err());
+    }
+
+    #[test]
+    fn sag_without_its_lau_key_is_not_rotated() {
+        for lau_key in [None, Some(""), Some("****")] {
+            let mut sag = sag("s1", "old");
+            sag.lau_key = lau_key.map(str::to_owned);
+            let mut rotation = discovered(vec![sag]);
+            assert!(rotation.plan().is_err(), "{lau_key:?}");
+            assert!(rotation.next_event().is_none());
+        }
+    }
+
+    #[test]
+    fn applied_steps_are_reverted_in_reverse_order() {
+        let sags = vec![sag("s1", "old")];
+        let mut rotation = discovered(sags.clone());
+        rotation.plan().unwrap();
+        for _ in 0..2 {
+            rotation.next_event().unwrap();
+            rotation.step_succeeded();
+        }
+        // The profile update is refused
+        rotation.next_event().unwrap();
+        rotation.step_failed("refused".to_owned());
+
+        let mut events = Vec::new();
+        while let Some(event) = rotation.next_event() {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            vec![
+                IoEvent::UpdateSag(sags[0].clone()),
+                IoEvent::DeleteCertificate(certificate("new")),
+            ]
+        );
+        assert!(!rotation.is_success());
+        assert_eq!(
+            rotation.summary(),
+            "Certificate rotation from old to new failed and was rolled back : refused"
+        );
+    }
+
+    #[test]
+    fn failed_rollback_is_reported() {
+        let mut rotation = discovered(Vec::new());
+        rotation.plan().unwrap();
+        rotation.next_event().unwrap();
+        rotation.step_succeeded();
+        rotation.next_event().unwrap();
+        rotation.step_failed("refused".to_owned());
+
+        assert_eq!(
+            rotation.next_event(),
+            Some(IoEvent::DeleteCertificate(certificate("new")))
+        );
+        rotation.step_failed("unreachable".to_owned());
+        assert!(rotation.next_event().is_none());
+        let summary = rotation.summary();
+        assert!(!summary.contains("was rolled back"), "{summary}");
+        assert!(summary.contains("unreachable"), "{summary}");
+    }
+}
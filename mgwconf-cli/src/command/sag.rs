@@ -0,0 +1,78 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_network::{event::IoEvent, mgw_configuration::models::SagEntity};
+use serde_json::Value;
+
+use crate::app::CliApp;
+
+use super::{csv_import::CsvImportArgs, dispatch_all, CommandTrait, EntityInput};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SagCommand {
+    /// List every SAG
+    List,
+    /// Show a single SAG
+    Get(SagKey),
+    /// Create SAGs
+    Create(EntityInput),
+    /// Create or update SAGs from a CSV file
+    Import(CsvImportArgs),
+    /// Update SAGs, identified by their hostname and port
+    Update(EntityInput),
+    /// Delete a SAG
+    Delete(SagKey),
+}
+
+/// Identifies a SAG
+#[derive(Args, Debug, Clone)]
+pub struct SagKey {
+    /// SAG hostname
+    #[clap(long = "hostname")]
+    pub hostname: String,
+    /// SAG port
+    #[clap(long = "port")]
+    pub port: i32,
+}
+
+impl SagCommand {
+    /// Name of the listed entities in outputs
+    pub const KIND: &'static str = "sags";
+
+    pub fn selection(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            SagCommand::Get(key) => vec![
+                ("hostname", key.hostname.clone().into()),
+                ("port", key.port.into()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for SagCommand {
+    async fn execute(&self, app: &CliApp) -> Result<usize> {
+        let events = match self {
+            SagCommand::List => vec![IoEvent::GetAllSags],
+            SagCommand::Get(key) => vec![IoEvent::GetSag(key.hostname.to_owned(), key.port)],
+            SagCommand::Create(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::PostSag)
+                .collect(),
+            SagCommand::Import(_) => app.import_events()?,
+            SagCommand::Update(input) => input
+                .entities()?
+                .into_iter()
+                .map(IoEvent::UpdateSag)
+                .collect(),
+            SagCommand::Delete(key) => vec![IoEvent::DeleteSag(SagEntity::new(
+                key.hostname.to_owned(),
+                key.port,
+                Vec::new(),
+            ))],
+        };
+        dispatch_all(app, events).await
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use mgwconf_vault::{FileStore, SecretType, VaultLocation};
+use std::{
+    fmt::Write as _,
+    io::{stdin, stdout, Write},
+};
+use zeroize::Zeroize;
+
+use crate::app::CliApp;
+
+use super::CommandTrait;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum VaultCommand {
+    /// Show which secrets are stored and when they were written
+    Status,
+    /// Replace a single secret, read from stdin
+    Set(SetSecretArgs),
+    /// Change the master key and re-encrypt every secret
+    Rekey,
+    /// Check that the master key decrypts every secret
+    Verify,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SetSecretArgs {
+    /// configuration, monitoring, management or encrypt
+    pub secret_type: SecretType,
+}
+
+fn read_line(prompt: &str) -> Result<String> {
+    print!("{prompt}");
+    stdout().flush()?;
+    let mut line = String::new();
+    stdin().read_line(&mut line)?;
+    let value = line.trim_end_matches(['\r', '\n']).to_owned();
+    line.zeroize();
+    Ok(value)
+}
+
+impl VaultCommand {
+    /// Status only reads file metadata, the other commands decrypt secrets
+    pub fn needs_master_key(&self) -> bool {
+        !matches!(self, VaultCommand::Status)
+    }
+
+    /// Vault commands run before connecting to a gateway, their report is returned
+    pub fn run(&self, master: Option<&str>, location: VaultLocation) -> Result<String> {
+        let master = || master.ok_or_else(|| anyhow::anyhow!("The master vault key is required"));
+        let mut report = String::new();
+        match self {
+            VaultCommand::Status => {
+                writeln!(report, "Vault {:?}", location.dir())?;
+                for status in FileStore::status(&location) {
+                    let modified = status
+                        .modified
+                        .map(httpdate::fmt_http_date)
+                        .unwrap_or_else(|| String::from("-"));
+                    writeln!(
+                        report,
+                        "  {:<14} {:<30} {}",
+                        status.stype.to_string(),
+                        modified,
+                        status.state
+                    )?;
+                }
+            }
+            VaultCommand::Set(args) => {
+                let vault = FileStore::new(master()?, location)?;
+                let value = read_line(&format!("Please enter {} API KEY : ", args.secret_type))?;
+                if value.is_empty() {
+                    bail!("Empty secret, {} is unchanged", args.secret_type);
+                }
+                vault.update_secret(args.secret_type, value)?;
+                writeln!(report, "{} secret updated", args.secret_type)?;
+            }
+            VaultCommand::Rekey => {
+                let vault = FileStore::new(master()?, location)?;
+                let mut new_master = read_line("Please enter the NEW MASTER VAULT KEY : ")?;
+                let mut confirmation = read_line("Please confirm the NEW MASTER VAULT KEY : ")?;
+                let res = if new_master.is_empty() {
+                    Err(anyhow::anyhow!("Empty master key, the vault is unchanged"))
+                } else if new_master != confirmation {
+                    Err(anyhow::anyhow!(
+                        "Master keys differ, the vault is unchanged"
+                    ))
+                } else {
+                    vault.rekey(&new_master).map_err(Into::into)
+                };
+                new_master.zeroize();
+                confirmation.zeroize();
+                writeln!(report, "{} secret(s) re-encrypted", res?)?;
+            }
+            VaultCommand::Verify => {
+                let dir = location.dir();
+                let results = FileStore::new(master()?, location)?.verify();
+                if results.is_empty() {
+                    bail!("No secret found in {:?}", dir);
+                }
+                let mut failed = 0;
+                for (stype, res) in results.iter() {
+                    match res {
+                        Ok(_) => writeln!(report, "  {:<14} OK", stype.to_string())?,
+                        Err(e) => {
+                            failed += 1;
+                            writeln!(report, "  {:<14} FAILED {}", stype.to_string(), e)?
+                        }
+                    }
+                }
+                if failed > 0 {
+                    bail!("{failed} secret(s) cannot be decrypted\n{report}");
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl CommandTrait for VaultCommand {
+    /// Vault commands are run before connecting, nothing to dispatch here
+    async fn execute(&self, _app: &CliApp) -> Result<usize> {
+        Ok(0)
+    }
+}
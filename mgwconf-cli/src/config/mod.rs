@@ -1,5 +1,10 @@
 use log::{debug, info};
-use mgwconf_network::{AppConfig, Identity};
+use mgwconf_network::{
+    context::{ConfigFile, GatewayContext},
+    mgw_configuration::models::CertificateEntity,
+    AppConfig, Identity,
+};
+use mgwconf_vault::{SecretBackend, SecretSource, VaultLocation};
 use std::{
     any::Any,
     error::Error,
@@ -7,14 +12,20 @@ use std::{
     io::Read,
     net::{IpAddr, SocketAddr, ToSocketAddrs},
     path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 use tracing_subscriber::{
     prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter,
 };
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 
-use crate::playbook::Playbook;
+use crate::{
+    command::{certificate::CertCommand, playbook::PlaybookCommand, Command, Predicate},
+    output::OutputFormat,
+    playbook::Playbook,
+};
 
 #[derive(Parser, Debug, Default, Clone)]
 #[clap(author, version, about, long_about = None)]
@@ -25,19 +36,76 @@ pub struct Args {
     /// create secret
     #[clap(long = "create_secret", action = clap::ArgAction::SetTrue, default_value = "false")]
     pub create_secret: bool,
-    /// pass vault key
-    #[clap(long = "key")]
+    /// pass vault key, prefer the MGWC_VAULT_KEY variable which is not shown by ps
+    #[clap(long = "key", env = "MGWC_VAULT_KEY", hide_env_values = true)]
     pub vault_key: Option<String>,
-    #[clap(short = 'c', long = "command", required = false)]
-    pub commands: Option<Vec<String>>,
-    #[clap(short = 'p', long = "playbook", required = false)]
-    pub playbook: Option<String>,
-    #[clap(long = "remote_addr")]
-    pub remote_addr: Option<String>,
+    /// Where API keys are read from: prompt, store, env, keyring or command
+    #[clap(long = "secrets")]
+    pub secrets: Option<SecretSource>,
+    /// Command printing an API key, `{type}` and `{namespace}` are replaced
+    #[clap(long = "secret_command")]
+    pub secret_command: Option<String>,
+    /// Vault directory, `~/.local/share/mgwconf/vault` by default
+    #[clap(long = "vault_dir")]
+    pub vault_dir: Option<PathBuf>,
+    /// Vault namespace holding the API keys of the gateway, the context name by default
+    #[clap(long = "vault_namespace")]
+    pub vault_namespace: Option<String>,
+    /// Configuration file, `~/.config/mgwconf/config.toml` by default
+    #[clap(long = "config")]
+    pub config_file: Option<PathBuf>,
+    /// Gateway context of the configuration file, its current context by default
+    #[clap(long = "context")]
+    pub context: Option<String>,
+    /// Gateway address, several gateways can be given separated by commas
+    #[clap(long = "remote_addr", value_delimiter = ',')]
+    pub remote_addr: Option<Vec<String>>,
+    /// CA certificate of the gateway
+    #[clap(long = "ca")]
+    pub root_ca_path: Option<String>,
     #[clap(long = "identity")]
     pub identity: Option<String>,
+    /// Accept invalid gateway certificates
     #[clap(short = 'k', action = clap::ArgAction::SetTrue, default_value = "false")]
     pub unsecure: bool,
+    /// Verify gateway certificates even if the context is unsecure
+    #[clap(long = "secure", action = clap::ArgAction::SetTrue, default_value = "false", conflicts_with = "unsecure")]
+    pub secure: bool,
+    /// Do not mask secrets (consumer secrets) in outputs
+    #[clap(long = "show_secrets", action = clap::ArgAction::SetTrue, default_value = "false")]
+    pub show_secrets: bool,
+    /// Format of listed entities
+    #[clap(long = "output", value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+    /// Write listed entities to this file instead of stdout
+    #[clap(long = "out")]
+    pub out: Option<PathBuf>,
+    /// Only list entities matching field=value or field!=value, can be repeated
+    #[clap(long = "where")]
+    pub predicates: Vec<Predicate>,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    /// Checks between arguments that clap cannot express, failing like a clap parsing error
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if let Some(Command::Cert(CertCommand::Check(check))) = &self.command {
+            check
+                .validate()
+                .map_err(|e| Args::command().error(ErrorKind::ArgumentConflict, e))?;
+        }
+        Ok(())
+    }
+
+    /// `-k` or `--secure` when given, the context decides otherwise
+    pub fn tls_unsecure(&self) -> Option<bool> {
+        match (self.unsecure, self.secure) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,49 +113,110 @@ pub struct Config {
     pub debug: bool,
     loaded: bool,
     pub remote_addr: SocketAddr,
+    pub hosts: Vec<SocketAddr>,
     pub identity: Option<Identity>,
     pub root_ca_path: String,
 
     pub tick_rate: u64,
-    pub commands: Vec<String>,
+    pub connect_timeout: Duration,
+    pub request_timeout: Option<Duration>,
+    pub vault_dir: Option<PathBuf>,
+    pub vault_namespace: Option<String>,
+    pub secrets: SecretSource,
+    pub secret_command: Option<String>,
+    pub command: Option<Command>,
     pub playbook: Option<Playbook>,
+    pub show_secrets: bool,
+    pub output: OutputFormat,
+    pub out: Option<PathBuf>,
+    pub predicates: Vec<Predicate>,
+    pub certificate_import: Option<CertificateEntity>,
     unsecure: bool,
 }
 
 impl Config {
     pub fn init(args: &Args) -> Result<Config, Box<dyn Error>> {
-        let remote_addr = if let Some(ip) = &args.remote_addr {
-            ip.to_socket_addrs()
-                .expect("Unable to resolve domain")
-                .next()
-                .unwrap()
-        } else {
-            "127.0.0.1:9003".parse().unwrap()
-        };
+        let config_file = ConfigFile::load(args.config_file.as_deref())?;
+        let context = config_file.context(args.context.as_deref())?;
+        let hosts = match &args.remote_addr {
+            Some(remote_addr) => remote_addr.clone(),
+            None => context.address.iter().cloned().collect(),
+        }
+        .iter()
+        .map(|ip| resolve(ip))
+        .collect::<Result<Vec<SocketAddr>, String>>()?;
+        let remote_addr = hosts
+            .first()
+            .copied()
+            .unwrap_or_else(|| "127.0.0.1:9003".parse().unwrap());
         let config = Config {
-            commands: args.commands.clone().unwrap_or_default(),
+            command: args.command.clone(),
             debug: args.debug,
             loaded: false,
             remote_addr,
-            identity: Self::read_pem(args)?,
-            root_ca_path: "CA.pem".to_owned(),
-            tick_rate: 250,
-            playbook: args.playbook.to_owned().map(|v| v.into()),
-            unsecure: args.unsecure,
+            hosts,
+            identity: Self::read_pem(args, &context)?,
+            root_ca_path: args
+                .root_ca_path
+                .clone()
+                .or_else(|| context.ca.as_ref().map(|ca| ca.display().to_string()))
+                .unwrap_or_else(|| "CA.pem".to_owned()),
+            tick_rate: context.tick_rate.unwrap_or(250),
+            connect_timeout: Duration::from_secs(context.connect_timeout.unwrap_or(15)),
+            request_timeout: context.request_timeout.map(Duration::from_secs),
+            vault_dir: args.vault_dir.clone().or_else(|| context.vault.clone()),
+            vault_namespace: args.vault_namespace.clone().or_else(|| {
+                config_file
+                    .context_name(args.context.as_deref())
+                    .map(str::to_owned)
+            }),
+            secrets: match (args.secrets, &context.secrets) {
+                (Some(secrets), _) => secrets,
+                (None, Some(secrets)) => secrets.parse()?,
+                (None, None) => SecretSource::default(),
+            },
+            secret_command: args
+                .secret_command
+                .clone()
+                .or_else(|| context.secret_command.clone()),
+            playbook: match &args.command {
+                Some(Command::Playbook(PlaybookCommand::Run(playbook))) => {
+                    Some(Playbook::read(&playbook.path)?)
+                }
+                _ => None,
+            },
+            show_secrets: args.show_secrets,
+            output: args.output,
+            out: args.out.clone(),
+            predicates: args.predicates.clone(),
+            certificate_import: Self::read_certificate(args)?,
+            unsecure: args.tls_unsecure().unwrap_or(context.unsecure),
         };
+        if matches!(config.command, Some(Command::Cert(CertCommand::Rotate(_))))
+            && config.gateways().len() > 1
+        {
+            return Err("A certificate rotation targets a single gateway".into());
+        }
         info!("Config has been loadded successfully");
         debug!("Config values {:?}", config);
         Ok(config)
     }
 
-    fn read_pem(args: &Args) -> Result<Option<Identity>, std::io::Error> {
+    fn read_pem(args: &Args, context: &GatewayContext) -> Result<Option<Identity>, std::io::Error> {
+        let path = |default: &Option<PathBuf>, file: &str| {
+            args.identity
+                .as_ref()
+                .map(PathBuf::from)
+                .or_else(|| default.clone())
+                .unwrap_or_else(|| PathBuf::from(file))
+        };
         let mut buf_pub: Vec<u8> = Vec::new();
         let mut buf_priv: Vec<u8> = Vec::new();
-        match File::open(args.identity.as_ref().unwrap_or(&"./mgw.key".to_string())) {
+        match File::open(path(&context.identity_key, "./mgw.key")) {
             Ok(mut f) => f.read_to_end(&mut buf_priv)?,
             Err(_) => return Ok(None),
         };
-        match File::open(args.identity.as_ref().unwrap_or(&"./mgw.pub".to_string())) {
+        match File::open(path(&context.identity_cert, "./mgw.pub")) {
             Ok(mut f) => f.read_to_end(&mut buf_pub)?,
             Err(_) => return Ok(None),
         };
@@ -100,10 +229,44 @@ impl Config {
         }
     }
 
+    /// Certificate file given to `cert import` or `cert rotate`, loaded before connecting
+    fn read_certificate(args: &Args) -> anyhow::Result<Option<CertificateEntity>> {
+        let import = match &args.command {
+            Some(Command::Cert(CertCommand::Import(import))) => import,
+            Some(Command::Cert(CertCommand::Rotate(rotate))) => &rotate.import,
+            _ => return Ok(None),
+        };
+        Ok(Some(import.source().load()?))
+    }
+
     pub fn playbook(&self) -> &Option<Playbook> {
         &self.playbook
     }
 
+    /// Gateways targeted by this run: playbook hosts, `--remote_addr` values or the default address
+    pub fn gateways(&self) -> Vec<SocketAddr> {
+        if let Some(playbook) = &self.playbook {
+            playbook.entries.hosts.clone()
+        } else if !self.hosts.is_empty() {
+            self.hosts.clone()
+        } else {
+            vec![self.remote_addr]
+        }
+    }
+
+    pub fn vault_location(&self) -> VaultLocation {
+        VaultLocation::new(self.vault_dir.clone(), self.vault_namespace.as_deref())
+    }
+
+    /// Backend of the API keys, `master` is only used by the store
+    pub fn secret_backend(&self, master: &str) -> anyhow::Result<Arc<dyn SecretBackend>> {
+        self.secrets.open(
+            master,
+            &self.vault_location(),
+            self.secret_command.as_deref(),
+        )
+    }
+
     #[allow(dead_code)]
     pub fn is_loaded(&self) -> bool {
         self.loaded
@@ -116,7 +279,7 @@ impl Config {
 
         let mut log_path = PathBuf::from("./logs");
         if !log_path.is_dir() {
-            println!("logs directory doesn't exist");
+            eprintln!("logs directory doesn't exist");
         }
         log_path.push(env!("CARGO_PKG_NAME"));
         let file_appender = tracing_appender::rolling::daily(
@@ -137,7 +300,8 @@ impl Config {
                     .with_line_number(true)
                     .with_ansi(false),
             )
-            .with(tracing_subscriber::fmt::layer())
+            // stdout is kept for command outputs
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
             .init();
         info!("Config has been loadded successfully");
         self.loaded = true;
@@ -164,6 +328,14 @@ impl AppConfig for Config {
         self.tick_rate
     }
 
+    fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -176,3 +348,12 @@ impl AppConfig for Config {
         self.unsecure
     }
 }
+
+/// First socket address of a `host:port` gateway address
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    address
+        .to_socket_addrs()
+        .map_err(|e| format!("Unable to resolve {address} : {e}"))?
+        .next()
+        .ok_or_else(|| format!("{address} does not resolve to any address"))
+}
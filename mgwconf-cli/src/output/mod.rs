@@ -0,0 +1,332 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use mgwconf_network::model::Masked;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Format of the entities written by listing commands
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Yaml,
+    Table,
+    Csv,
+}
+
+/// Longest cell of a table, longer values such as PEM certificates are truncated
+const TABLE_CELL_WIDTH: usize = 48;
+
+/// Entities received from the gateways, rendered once every response has been received
+///
+/// Sections are ordered by gateway then entity kind so that the output does not depend on the order of responses
+#[derive(Debug, Clone, Default)]
+pub struct Output {
+    sections: BTreeMap<(SocketAddr, &'static str), Vec<Value>>,
+}
+
+impl Output {
+    pub fn add<E: Serialize>(
+        &mut self,
+        gateway: SocketAddr,
+        kind: &'static str,
+        entities: &[E],
+    ) -> Result<(), serde_json::Error> {
+        let values = entities
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+        self.sections.insert((gateway, kind), values);
+        Ok(())
+    }
+
+    /// Same as `add`, secrets are masked unless `show_secrets`
+    pub fn add_masked<E: Serialize + Masked>(
+        &mut self,
+        gateway: SocketAddr,
+        kind: &'static str,
+        entities: &[E],
+        show_secrets: bool,
+    ) -> Result<(), serde_json::Error> {
+        if show_secrets {
+            self.add(gateway, kind, entities)
+        } else {
+            self.add(
+                gateway,
+                kind,
+                &entities.iter().map(Masked::masked).collect::<Vec<E>>(),
+            )
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Json and yaml documents are always grouped by gateway and kind, a single table or csv
+    /// section is rendered without its title
+    pub fn render(&self, format: OutputFormat) -> Result<String> {
+        Ok(match format {
+            OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(&self.document())?),
+            OutputFormat::Yaml => serde_yaml::to_string(&self.document())?,
+            OutputFormat::Table => self.render_sections(|rows| Ok(table(rows)))?,
+            OutputFormat::Csv => self.render_sections(csv)?,
+        })
+    }
+
+    fn document(&self) -> Value {
+        let mut gateways = Map::new();
+        for ((gateway, kind), rows) in self.sections.iter() {
+            if let Value::Object(kinds) = gateways
+                .entry(gateway.to_string())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                kinds.insert(kind.to_string(), Value::Array(rows.clone()));
+            }
+        }
+        Value::Object(gateways)
+    }
+
+    fn render_sections(&self, render: fn(&[Value]) -> Result<String>) -> Result<String> {
+        if let Some((_, rows)) = self.single_section() {
+            return render(rows);
+        }
+        Ok(self
+            .sections
+            .iter()
+            .map(|((gateway, kind), rows)| Ok(format!("# {kind} @ {gateway}\n{}", render(rows)?)))
+            .collect::<Result<Vec<String>>>()?
+            .join("\n"))
+    }
+
+    fn single_section(&self) -> Option<(&(SocketAddr, &'static str), &Vec<Value>)> {
+        if self.sections.len() == 1 {
+            self.sections.iter().next()
+        } else {
+            None
+        }
+    }
+}
+
+/// Flatten an entity into columns, nested objects use dotted names and lists are joined with `;`
+fn flatten(value: &Value, prefix: &str, columns: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter() {
+                let name = if prefix.is_empty() {
+                    key.to_owned()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(value, &name, columns);
+            }
+        }
+        Value::Array(values) => {
+            let cell = values
+                .iter()
+                .map(|v| match v {
+                    Value::String(s) => s.to_owned(),
+                    v => v.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(";");
+            columns.insert(prefix.to_owned(), cell);
+        }
+        Value::String(s) => {
+            columns.insert(prefix.to_owned(), s.to_owned());
+        }
+        Value::Null => {
+            columns.insert(prefix.to_owned(), String::new());
+        }
+        v => {
+            columns.insert(prefix.to_owned(), v.to_string());
+        }
+    }
+}
+
+/// Column names, in a stable order, and the cells of every row
+fn rows(values: &[Value]) -> (Vec<String>, Vec<BTreeMap<String, String>>) {
+    let rows = values
+        .iter()
+        .map(|v| {
+            let mut columns = BTreeMap::new();
+            flatten(v, "", &mut columns);
+            columns
+        })
+        .collect::<Vec<BTreeMap<String, String>>>();
+    let header = rows
+        .iter()
+        .flat_map(|r| r.keys().cloned())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect();
+    (header, rows)
+}
+
+fn table(values: &[Value]) -> String {
+    let (header, rows) = rows(values);
+    let cells = rows
+        .iter()
+        .map(|row| {
+            header
+                .iter()
+                .map(|column| {
+                    let cell = row
+                        .get(column)
+                        .map(|c| c.replace('\n', " "))
+                        .unwrap_or_default();
+                    if cell.chars().count() > TABLE_CELL_WIDTH {
+                        let mut cell = cell.chars().take(TABLE_CELL_WIDTH - 3).collect::<String>();
+                        cell.push_str("...");
+                        cell
+                    } else {
+                        cell
+                    }
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<Vec<String>>>();
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<usize>>();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    let mut output = format!("{}\n", line(&header));
+    for row in cells.iter() {
+        output.push_str(&line(row));
+        output.push('\n');
+    }
+    output
+}
+
+fn csv(values: &[Value]) -> Result<String> {
+    let (header, rows) = rows(values);
+    if header.is_empty() {
+        return Ok(String::new());
+    }
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&header)?;
+    for row in rows.iter() {
+        writer.write_record(
+            header
+                .iter()
+                .map(|column| row.get(column).map(String::as_str).unwrap_or_default()),
+        )?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use mgwconf_network::mgw_configuration::models::ApiCredentialsEntity;
+    use serde_json::json;
+
+    use super::*;
+
+    const SECRET: &str = "consumer-secret-0042";
+
+    fn gateway(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn credentials() -> Vec<ApiCredentialsEntity> {
+        vec![ApiCredentialsEntity::new(
+            "app".to_owned(),
+            "key".to_owned(),
+            SECRET.to_owned(),
+        )]
+    }
+
+    #[test]
+    fn csv_header_and_column_order() {
+        let mut output = Output::default();
+        output
+            .add(
+                gateway(9003),
+                "sags",
+                &[
+                    json!({"port": 48002, "hostname": "sag1", "userDNs": ["cn=a", "cn=b"]}),
+                    json!({"hostname": "sag2", "port": 48003, "tls": {"mode": "TWO_WAY"}, "active": true}),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            output.render(OutputFormat::Csv).unwrap(),
+            "active,hostname,port,tls.mode,userDNs\n\
+             ,sag1,48002,,cn=a;cn=b\n\
+             true,sag2,48003,TWO_WAY,\n"
+        );
+    }
+
+    #[test]
+    fn sections_are_ordered_by_gateway_and_kind() {
+        let mut output = Output::default();
+        output
+            .add(gateway(9004), "sags", &[json!({"hostname": "b"})])
+            .unwrap();
+        output
+            .add(gateway(9003), "sags", &[json!({"hostname": "a"})])
+            .unwrap();
+        output
+            .add(gateway(9003), "profiles", &[json!({"profileName": "p"})])
+            .unwrap();
+        assert_eq!(
+            output.render(OutputFormat::Csv).unwrap(),
+            "# profiles @ 127.0.0.1:9003\nprofileName\np\n\n\
+             # sags @ 127.0.0.1:9003\nhostname\na\n\n\
+             # sags @ 127.0.0.1:9004\nhostname\nb\n"
+        );
+        assert_eq!(
+            output.render(OutputFormat::Json).unwrap(),
+            format!(
+                "{}\n",
+                serde_json::to_string_pretty(&json!({
+                    "127.0.0.1:9003": {"profiles": [{"profileName": "p"}], "sags": [{"hostname": "a"}]},
+                    "127.0.0.1:9004": {"sags": [{"hostname": "b"}]},
+                }))
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn secrets_are_masked_in_every_format() {
+        let mut output = Output::default();
+        output
+            .add_masked(gateway(9003), "credentials", &credentials(), false)
+            .unwrap();
+        for format in OutputFormat::value_variants() {
+            let rendered = output.render(*format).unwrap();
+            assert!(!rendered.contains(SECRET), "{format:?} : {rendered}");
+            assert!(rendered.contains("********0042"), "{format:?} : {rendered}");
+        }
+        output
+            .add_masked(gateway(9003), "credentials", &credentials(), true)
+            .unwrap();
+        for format in OutputFormat::value_variants() {
+            assert!(output.render(*format).unwrap().contains(SECRET));
+        }
+    }
+}
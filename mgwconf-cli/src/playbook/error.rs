@@ -14,4 +14,6 @@ pub enum PlaybookError {
     DispatchErr(#[from] anyhow::Error),
     #[error("Playbook contains malformed data {0}")]
     MalformedPlaybook(&'static str),
+    #[error("Environment variable {0} of the playbook is not set")]
+    MissingVariable(String),
 }
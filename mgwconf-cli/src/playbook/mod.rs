@@ -5,11 +5,9 @@ use std::hash::Hash;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
-use mgwconf_network::mgw_configuration::models::BusinessApplicationEntity;
-use mgwconf_network::mgw_configuration::models::{
-    ApiCredentialsEntity, ApplicationProfileEntity, CertificateEntity, ForwardProxyEntity,
-};
-use mgwconf_network::{event::IoEvent, mgw_configuration::models::SagEntity, AppTrait};
+use mgwconf_network::model::certificate::CertificateSource;
+use mgwconf_network::{event::IoEvent, AppTrait};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::app::CliApp;
@@ -19,6 +17,12 @@ use self::error::PlaybookError;
 
 pub mod error;
 
+const DELETE_UNSUPPORTED: &str = "Delete commands are not supported yet";
+
+/// PKCS#12 password of the certificates of a playbook giving neither `password` nor
+/// `password_env`, as for `cert import`
+const CERT_PASSWORD_VARIABLE: &str = "MGWC_CERT_PASSWORD";
+
 #[derive(Debug, Clone)]
 pub struct Playbook {
     pub path: PathBuf,
@@ -38,73 +42,117 @@ impl Playbook {
 
     pub async fn process(&self, app: &CliApp) -> Result<usize, PlaybookError> {
         log::info!("Processing playbook");
-        let mut num_op = 0;
-        for i in self.entries.commands.iter() {
-            num_op += match i {
-                CommandType::Delete(e) => self.process_delete(e, app).await?,
-                CommandType::Create(e) => self.process_create(e, app).await?,
-            }
-        }
-        if num_op == 0 {
+        // Entries are read before the first dispatch, a rejected playbook changes nothing
+        let events = self.events()?;
+        if events.is_empty() {
             log::warn!("This playbook seems to be empty");
         }
+        let num_op = events.len();
+        for event in events {
+            <CliApp as AppTrait<Config>>::dispatch(app, event).await?;
+        }
         Ok(num_op)
     }
 
-    async fn process_delete(&self, _e: &EntityType, _app: &CliApp) -> Result<usize, PlaybookError> {
-        todo!();
+    /// Describe every operation of the playbook without running it, failing on malformed entries
+    pub fn plan(&self) -> Result<Vec<String>, PlaybookError> {
+        Ok(self.events()?.iter().map(describe_create).collect())
     }
 
-    async fn process_create(&self, e: &EntityType, app: &CliApp) -> Result<usize, PlaybookError> {
-        let mut num_op = 0;
-        macro_rules! handle {
-            ($h:tt, $io:expr, $e:tt) => {
-                if $h.json.is_none() && $h.file.is_none() {
-                    return Err(PlaybookError::MalformedPlaybook(
-                        "Sag import must contains either file or json input",
-                    ));
+    /// Events of every command, certificate files are read and decrypted once
+    fn events(&self) -> Result<Vec<IoEvent>, PlaybookError> {
+        let mut events = Vec::new();
+        for command in self.entries.commands.iter() {
+            match command {
+                CommandType::Create(e) => events.extend(e.create_events()?),
+                CommandType::Delete(_) => {
+                    return Err(PlaybookError::MalformedPlaybook(DELETE_UNSUPPORTED))
                 }
-                if let Some(j) = &$h.json {
-                    <CliApp as AppTrait<Config>>::dispatch(
-                        app,
-                        $io(serde_json::from_str::<$e>(&j)?),
-                    )
-                    .await?;
-                } else {
-                    let file = read_to_string::<&str>(&$h.file.as_ref().unwrap())?;
-                    let entities: Vec<$e> = serde_json::from_str(&file)?;
-                    for e in entities {
-                        <CliApp as AppTrait<Config>>::dispatch(app, $io(e)).await?;
-                    }
-                }
-                num_op += 1;
-            };
-        }
-        match e {
-            EntityType::Sag(h) => {
-                handle!(h, IoEvent::PostSag, SagEntity);
-            }
-            EntityType::Proxy(p) => {
-                handle!(p, IoEvent::PostForwardProxyEntity, ForwardProxyEntity);
-            }
-            EntityType::Profile(p) => {
-                handle!(p, IoEvent::PostProfile, ApplicationProfileEntity);
-            }
-            EntityType::BusinessApplication(b) => {
-                handle!(
-                    b,
-                    IoEvent::PostBusinessApplication,
-                    BusinessApplicationEntity
-                );
             }
+        }
+        Ok(events)
+    }
+}
+
+impl EntityType {
+    /// Creation events of the entities given inline, in a json file or in a certificate file
+    fn create_events(&self) -> Result<Vec<IoEvent>, PlaybookError> {
+        Ok(match self {
+            EntityType::Sag(h) => read_entities(&h.file, &h.json)?
+                .into_iter()
+                .map(IoEvent::PostSag)
+                .collect(),
+            EntityType::Proxy(p) => read_entities(&p.file, &p.json)?
+                .into_iter()
+                .map(IoEvent::PostForwardProxyEntity)
+                .collect(),
+            EntityType::Profile(p) => read_entities(&p.file, &p.json)?
+                .into_iter()
+                .map(IoEvent::PostProfile)
+                .collect(),
+            EntityType::BusinessApplication(b) => read_entities(&b.file, &b.json)?
+                .into_iter()
+                .map(IoEvent::PostBusinessApplication)
+                .collect(),
             EntityType::Certificate(c) => {
-                handle!(c, IoEvent::PostCertificate, CertificateEntity);
-            }
-            EntityType::ApiClientCredential(a) => {
-                handle!(a, IoEvent::PostApiClientCredential, ApiCredentialsEntity);
+                if let Some(path) = &c.certificate {
+                    let entity = CertificateSource {
+                        alias: c.alias.clone().ok_or(PlaybookError::MalformedPlaybook(
+                            "Certificate import from a certificate file must contains an alias",
+                        ))?,
+                        path: path.into(),
+                        private_key: c.private_key.as_ref().map(PathBuf::from),
+                        password: c.password()?,
+                        force: c.force,
+                    }
+                    .load()?;
+                    vec![IoEvent::PostCertificate(entity)]
+                } else {
+                    read_entities(&c.file, &c.json)?
+                        .into_iter()
+                        .map(IoEvent::PostCertificate)
+                        .collect()
+                }
             }
+            EntityType::ApiClientCredential(a) => read_entities(&a.file, &a.json)?
+                .into_iter()
+                .map(IoEvent::PostApiClientCredential)
+                .collect(),
+        })
+    }
+}
+
+/// A single entity given as json, or an array of entities in a json file
+fn read_entities<E: DeserializeOwned>(
+    file: &Option<String>,
+    json: &Option<String>,
+) -> Result<Vec<E>, PlaybookError> {
+    if let Some(j) = json {
+        Ok(vec![serde_json::from_str::<E>(j)?])
+    } else if let Some(f) = file {
+        Ok(serde_json::from_str(&read_to_string(f)?)?)
+    } else {
+        Err(PlaybookError::MalformedPlaybook(
+            "Import must contains either file or json input",
+        ))
+    }
+}
+
+fn describe_create(event: &IoEvent) -> String {
+    match event {
+        IoEvent::PostSag(e) => format!("create SAG {}:{}", e.hostname, e.port),
+        IoEvent::PostForwardProxyEntity(e) => format!("create proxy {}:{}", e.hostname, e.port),
+        IoEvent::PostProfile(e) => {
+            format!("create profile {}/{}", e.application_name, e.profile_name)
         }
-        Ok(num_op)
+        IoEvent::PostBusinessApplication(e) => {
+            format!("create business application {}", e.application_name)
+        }
+        IoEvent::PostCertificate(e) => format!("create certificate {}", e.alias),
+        IoEvent::PostApiClientCredential(e) => {
+            format!("create API client credential {}", e.application_name)
+        }
+        _ => "unknown operation".to_owned(),
     }
 }
 
@@ -170,6 +218,32 @@ struct BusinessApplicationImport {
 struct CertificateImport {
     file: Option<String>,
     json: Option<String>,
+    /// Certificate file (.pem, .crt, .der, .p12) imported under `alias`
+    certificate: Option<String>,
+    alias: Option<String>,
+    private_key: Option<String>,
+    /// PKCS#12 password, prefer `password_env` which keeps it out of the playbook
+    password: Option<String>,
+    /// Environment variable holding the PKCS#12 password
+    password_env: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+impl CertificateImport {
+    /// `password`, the `password_env` variable, or `MGWC_CERT_PASSWORD` when neither is given
+    fn password(&self) -> Result<Option<String>, PlaybookError> {
+        match (&self.password, &self.password_env) {
+            (Some(_), Some(_)) => Err(PlaybookError::MalformedPlaybook(
+                "Certificate import cannot contain both password and password_env",
+            )),
+            (Some(password), None) => Ok(Some(password.to_owned())),
+            (None, Some(variable)) => std::env::var(variable)
+                .map(Some)
+                .map_err(|_| PlaybookError::MissingVariable(variable.to_owned())),
+            (None, None) => Ok(std::env::var(CERT_PASSWORD_VARIABLE).ok()),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -200,3 +274,96 @@ where
         Ok(vec)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playbook(yaml: &str) -> Playbook {
+        Playbook {
+            path: PathBuf::from("playbook.yaml"),
+            entries: serde_yaml::from_str(yaml).unwrap(),
+        }
+    }
+
+    fn certificate(password: Option<&str>, password_env: Option<&str>) -> CertificateImport {
+        CertificateImport {
+            file: None,
+            json: None,
+            certificate: Some("mgw.p12".to_owned()),
+            alias: Some("mgw".to_owned()),
+            private_key: None,
+            password: password.map(str::to_owned),
+            password_env: password_env.map(str::to_owned),
+            force: false,
+        }
+    }
+
+    #[test]
+    fn plan() {
+        let playbook = playbook(
+            r#"
+hosts: ["127.0.0.1:9003"]
+commands:
+  - type: Create
+    entity_type: Sag
+    json: '{"hostname": "sag1", "port": 48002, "userDNs": []}'
+  - type: Create
+    entity_type: BusinessApplication
+    json: '{"applicationName": "app"}'
+"#,
+        );
+        assert_eq!(
+            playbook.plan().unwrap(),
+            vec!["create SAG sag1:48002", "create business application app"]
+        );
+    }
+
+    #[test]
+    fn delete_is_refused() {
+        let playbook = playbook(
+            r#"
+hosts: ["127.0.0.1:9003"]
+commands:
+  - type: Delete
+    entity_type: Sag
+    json: '{"hostname": "sag1", "port": 48002, "userDNs": []}'
+"#,
+        );
+        assert!(matches!(
+            playbook.plan(),
+            Err(PlaybookError::MalformedPlaybook(DELETE_UNSUPPORTED))
+        ));
+    }
+
+    #[test]
+    fn certificate_password() {
+        // The only test reading these variables
+        assert_eq!(
+            certificate(Some("inline"), None).password().unwrap(),
+            Some("inline".to_owned())
+        );
+        std::env::set_var("MGWC_TEST_PLAYBOOK_PASSWORD", "from env");
+        assert_eq!(
+            certificate(None, Some("MGWC_TEST_PLAYBOOK_PASSWORD"))
+                .password()
+                .unwrap(),
+            Some("from env".to_owned())
+        );
+        assert!(matches!(
+            certificate(None, Some("MGWC_TEST_PLAYBOOK_UNSET")).password(),
+            Err(PlaybookError::MissingVariable(v)) if v == "MGWC_TEST_PLAYBOOK_UNSET"
+        ));
+        assert!(matches!(
+            certificate(Some("inline"), Some("MGWC_TEST_PLAYBOOK_PASSWORD")).password(),
+            Err(PlaybookError::MalformedPlaybook(_))
+        ));
+        std::env::set_var(CERT_PASSWORD_VARIABLE, "default");
+        assert_eq!(
+            certificate(None, None).password().unwrap(),
+            Some("default".to_owned())
+        );
+        std::env::remove_var(CERT_PASSWORD_VARIABLE);
+        assert_eq!(certificate(None, None).password().unwrap(), None);
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(unused_imports)]
+#![allow(unused_imports, clippy::derivable_impls)]
 
 #[macro_use]
 extern crate serde_derive;
@@ -0,0 +1,149 @@
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+/// Settings shared by mgwc and mgwc_ui, read from `$XDG_CONFIG_HOME/mgwconf/config.toml`
+///
+/// ```toml
+/// current_context = "prod"
+/// locale = "fr"
+///
+/// [contexts.prod]
+/// address = "mgw.example.com:9003"
+/// ca = "/etc/mgwconf/prod/CA.pem"
+/// connect_timeout = 5
+/// live = true
+/// secrets = "command"
+/// secret_command = "pass show mgwconf/{namespace}/{type}"
+///
+/// [keys]
+/// down = ["<Down>", "j"]
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Context used when none is selected with `--context`
+    pub current_context: Option<String>,
+    #[serde(default)]
+    pub contexts: BTreeMap<String, GatewayContext>,
+    /// Language of mgwc_ui, `en` or `fr`, from `LANG` when unset
+    pub locale: Option<String>,
+    /// Keys of the mgwc_ui actions, replacing their default keys
+    #[serde(default)]
+    pub keys: BTreeMap<String, Vec<String>>,
+}
+
+/// A named gateway, values given on the command line take precedence
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatewayContext {
+    /// Gateway address, as host:port
+    pub address: Option<String>,
+    /// CA certificate of the gateway
+    pub ca: Option<PathBuf>,
+    /// PKCS#8 private key of the client identity
+    pub identity_key: Option<PathBuf>,
+    /// Certificate of the client identity
+    pub identity_cert: Option<PathBuf>,
+    /// Accept invalid gateway certificates
+    pub unsecure: bool,
+    /// The gateway serves the LIVE environment, every deletion must be confirmed by typing LIVE
+    pub live: bool,
+    /// Directory of the secrets vault
+    pub vault: Option<PathBuf>,
+    /// Secret backend: prompt, store, env, keyring or command
+    pub secrets: Option<String>,
+    /// Command printing a secret, for the command backend
+    pub secret_command: Option<String>,
+    /// Seconds to wait for a connection
+    pub connect_timeout: Option<u64>,
+    /// Seconds to wait for a response
+    pub request_timeout: Option<u64>,
+    /// Milliseconds between two ticks of the UI
+    pub tick_rate: Option<u64>,
+}
+
+impl ConfigFile {
+    /// `config.toml` in the `mgwconf` directory of the user configuration, `~/.config` on Linux
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("mgwconf").join("config.toml"))
+    }
+
+    pub fn read(path: &Path) -> Result<ConfigFile> {
+        let content = read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read config file {:?} : {}", path, e))?;
+        toml::from_str(&content).map_err(|e| anyhow!("Invalid config file {:?} : {}", path, e))
+    }
+
+    /// Read the given file, or the default one which may not exist
+    pub fn load(path: Option<&Path>) -> Result<ConfigFile> {
+        match path {
+            Some(path) => Self::read(path),
+            None => match Self::default_path() {
+                Some(path) if path.is_file() => Self::read(&path),
+                _ => Ok(ConfigFile::default()),
+            },
+        }
+    }
+
+    /// Name of the context selected by name, or of the current one
+    pub fn context_name<'a>(&'a self, name: Option<&'a str>) -> Option<&'a str> {
+        name.or(self.current_context.as_deref())
+    }
+
+    /// Context selected by name, or the current one, an empty context when none is set
+    pub fn context(&self, name: Option<&str>) -> Result<GatewayContext> {
+        match self.context_name(name) {
+            Some(name) => self.contexts.get(name).cloned().ok_or_else(|| {
+                anyhow!(
+                    "Context {} is not defined, known contexts : {}",
+                    name,
+                    self.contexts
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }),
+            None => Ok(GatewayContext::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example of the `ConfigFile` documentation
+    const EXAMPLE: &str = r#"
+current_context = "prod"
+locale = "fr"
+
+[contexts.prod]
+address = "mgw.example.com:9003"
+ca = "/etc/mgwconf/prod/CA.pem"
+connect_timeout = 5
+live = true
+secrets = "command"
+secret_command = "pass show mgwconf/{namespace}/{type}"
+
+[keys]
+down = ["<Down>", "j"]
+"#;
+
+    #[test]
+    fn documented_example_is_valid() {
+        let config_file = toml::from_str::<ConfigFile>(EXAMPLE).unwrap();
+        assert_eq!(config_file.locale.as_deref(), Some("fr"));
+        assert_eq!(config_file.keys["down"], ["<Down>", "j"]);
+        let context = config_file.context(None).unwrap();
+        assert_eq!(context.address.as_deref(), Some("mgw.example.com:9003"));
+        assert_eq!(context.connect_timeout, Some(5));
+        assert!(context.live);
+    }
+}
@@ -3,7 +3,7 @@ use mgw_configuration::models::{
     BusinessApplicationEntity, CertificateEntity, ForwardProxyEntity, SagEntity,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IoEvent {
     Ping,
     GetAllApiGatewayInfoEntity,
@@ -13,6 +13,12 @@ pub enum IoEvent {
     GetAllCertificates,
     GetAllSags,
     GetAllProfiles,
+    /// SAG identified by its hostname and port
+    GetSag(String, i32),
+    /// Certificate identified by its alias
+    GetCertificate(String),
+    /// Profiles of a business application, or a single one when the profile name is given
+    GetProfiles(String, Option<String>),
     PostBusinessApplication(BusinessApplicationEntity),
     PostApiGatewayInfoEntity(ApiGatewayInfoEntity),
     PostForwardProxyEntity(ForwardProxyEntity),
@@ -26,4 +32,11 @@ pub enum IoEvent {
     DeleteCertificate(CertificateEntity),
     DeleteSag(SagEntity),
     DeleteProfile(ApplicationProfileEntity),
+    DeleteApiClientCredential(ApiCredentialsEntity),
+    UpdateApiClientCredential(ApiCredentialsEntity),
+    UpdateSag(SagEntity),
+    UpdateProfile(ApplicationProfileEntity),
+    UpdateCertificate(CertificateEntity),
+    UpdateForwardProxyEntity(ForwardProxyEntity),
+    UpdateBusinessApplication(BusinessApplicationEntity),
 }
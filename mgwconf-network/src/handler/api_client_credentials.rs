@@ -1,5 +1,8 @@
 use mgw_configuration::apis::{
-    api_client_credentials_api::{api_credentials_info_create, api_credentials_info_get},
+    api_client_credentials_api::{
+        api_credentials_info_create, api_credentials_info_delete, api_credentials_info_get,
+        api_credentials_info_update,
+    },
     configuration::{ApiKey, Configuration},
 };
 use mgwconf_vault::SecretType;
@@ -39,7 +42,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -51,7 +54,11 @@ where
                     None,
                 )
                 .await?;
-                app.handle_network_response(IoEvent::GetAllApiClientCredentials, entities);
+                app.handle_gateway_response(
+                    config.remote_addr(),
+                    IoEvent::GetAllApiClientCredentials,
+                    entities,
+                );
             }
             IoEvent::PostApiClientCredential(entity) => {
                 log::debug!("handling {:#?}", entity);
@@ -64,7 +71,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -76,7 +83,57 @@ where
                     entity.to_owned(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), r);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), r);
+            }
+            IoEvent::DeleteApiClientCredential(entity) => {
+                let r = api_credentials_info_delete(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.application_name,
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), r);
+            }
+            IoEvent::UpdateApiClientCredential(entity) => {
+                log::debug!("handling {:#?}", entity);
+                let r = api_credentials_info_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.application_name,
+                    entity.to_owned(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), r);
             }
             _ => {}
         }
@@ -39,7 +39,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -51,7 +51,11 @@ where
                     None,
                 )
                 .await?;
-                app.handle_network_response(IoEvent::GetAllBusinessApplications, entities);
+                app.handle_gateway_response(
+                    config.remote_addr(),
+                    IoEvent::GetAllBusinessApplications,
+                    entities,
+                );
             }
             IoEvent::PostBusinessApplication(entity) => {
                 log::debug!("handling {:#?}", entity);
@@ -64,7 +68,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -76,10 +80,10 @@ where
                     entity.clone(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), res);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
-            IoEvent::DeleteBusinessApplication(e) => {
-                business_application_api::business_application_delete(
+            IoEvent::DeleteBusinessApplication(entity) => {
+                let res = business_application_api::business_application_delete(
                     &Configuration {
                         base_path: format!(
                             "{}/swift/mgw/mgw-configuration-api/2.0.0",
@@ -88,7 +92,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -97,9 +101,36 @@ where
                         }),
                         ..Default::default()
                     },
-                    &e.application_name,
+                    &entity.application_name,
                 )
                 .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
+            }
+            IoEvent::UpdateBusinessApplication(entity) => {
+                log::debug!("handling {:#?}", entity);
+                let res = business_application_api::business_application_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.application_name,
+                    entity.clone(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
             _ => {}
         }
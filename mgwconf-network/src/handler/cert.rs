@@ -29,7 +29,11 @@ where
     ) -> Result<(), anyhow::Error> {
         let mut app = app.lock().await;
         match e {
-            IoEvent::GetAllCertificates => {
+            IoEvent::GetAllCertificates | IoEvent::GetCertificate(_) => {
+                let alias = match e {
+                    IoEvent::GetCertificate(alias) => Some(alias.as_str()),
+                    _ => None,
+                };
                 let entities = certificate_api::certificate_get(
                     &Configuration {
                         base_path: format!(
@@ -39,7 +43,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -48,14 +52,15 @@ where
                         }),
                         ..Default::default()
                     },
-                    None,
+                    alias,
                 )
                 .await?;
                 log::info!("{:#?}", entities);
-                app.handle_network_response(IoEvent::GetAllCertificates, entities);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), entities);
             }
             IoEvent::PostCertificate(entity) => {
-                log::debug!("handling {:#?}", entity);
+                // Do not log the whole entity, it may contain a private key
+                log::debug!("handling certificate {}", entity.alias);
                 let res = certificate_api::certificate_create(
                     &Configuration {
                         base_path: format!(
@@ -65,7 +70,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -77,10 +82,10 @@ where
                     entity.clone(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), res);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
-            IoEvent::DeleteCertificate(e) => {
-                certificate_api::certificate_delete(
+            IoEvent::DeleteCertificate(entity) => {
+                let res = certificate_api::certificate_delete(
                     &Configuration {
                         base_path: format!(
                             "{}/swift/mgw/mgw-configuration-api/2.0.0",
@@ -89,7 +94,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -98,9 +103,37 @@ where
                         }),
                         ..Default::default()
                     },
-                    &e.alias,
+                    &entity.alias,
                 )
                 .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
+            }
+            IoEvent::UpdateCertificate(entity) => {
+                // Do not log the whole entity, it may contain a private key
+                log::debug!("handling certificate {}", entity.alias);
+                let res = certificate_api::certificate_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.alias,
+                    entity.clone(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
             _ => {}
         }
@@ -39,7 +39,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -52,7 +52,11 @@ where
                     None,
                 )
                 .await?;
-                app.handle_network_response(IoEvent::GetAllForwardProxyEntity, entities);
+                app.handle_gateway_response(
+                    config.remote_addr(),
+                    IoEvent::GetAllForwardProxyEntity,
+                    entities,
+                );
             }
             IoEvent::PostForwardProxyEntity(entity) => {
                 log::debug!("handling {:#?}", entity);
@@ -65,7 +69,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -77,10 +81,10 @@ where
                     entity.clone(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), res);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
-            IoEvent::DeleteForwardProxyEntity(e) => {
-                forward_proxy_api::forward_proxy_info_delete(
+            IoEvent::DeleteForwardProxyEntity(entity) => {
+                let res = forward_proxy_api::forward_proxy_info_delete(
                     &Configuration {
                         base_path: format!(
                             "{}/swift/mgw/mgw-configuration-api/2.0.0",
@@ -89,7 +93,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -98,10 +102,38 @@ where
                         }),
                         ..Default::default()
                     },
-                    &e.hostname,
-                    &e.port.to_string(),
+                    &entity.hostname,
+                    &entity.port.to_string(),
                 )
                 .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
+            }
+            IoEvent::UpdateForwardProxyEntity(entity) => {
+                log::debug!("handling {:#?}", entity);
+                let res = forward_proxy_api::forward_proxy_info_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.hostname,
+                    &entity.port.to_string(),
+                    entity.clone(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
             _ => {}
         }
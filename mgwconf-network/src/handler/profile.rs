@@ -29,7 +29,13 @@ where
     ) -> Result<(), anyhow::Error> {
         let mut app = app.lock().await;
         match e {
-            IoEvent::GetAllProfiles => {
+            IoEvent::GetAllProfiles | IoEvent::GetProfiles(..) => {
+                let (application_name, profile_name) = match e {
+                    IoEvent::GetProfiles(application_name, profile_name) => {
+                        (Some(application_name.as_str()), profile_name.as_deref())
+                    }
+                    _ => (None, None),
+                };
                 let entities = profile_api::application_profile_get(
                     &Configuration {
                         base_path: format!(
@@ -39,7 +45,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -48,11 +54,11 @@ where
                         }),
                         ..Default::default()
                     },
-                    None,
-                    None,
+                    application_name,
+                    profile_name,
                 )
                 .await?;
-                app.handle_network_response(IoEvent::GetAllProfiles, entities);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), entities);
             }
             IoEvent::PostProfile(entity) => {
                 log::debug!("handling {:#?}", entity);
@@ -65,7 +71,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -77,10 +83,10 @@ where
                     entity.clone(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), res);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
-            IoEvent::DeleteProfile(e) => {
-                profile_api::application_profile_delete(
+            IoEvent::DeleteProfile(entity) => {
+                let res = profile_api::application_profile_delete(
                     &Configuration {
                         base_path: format!(
                             "{}/swift/mgw/mgw-configuration-api/2.0.0",
@@ -89,7 +95,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -98,10 +104,38 @@ where
                         }),
                         ..Default::default()
                     },
-                    &e.application_name,
-                    &e.profile_name,
+                    &entity.application_name,
+                    &entity.profile_name,
                 )
                 .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
+            }
+            IoEvent::UpdateProfile(entity) => {
+                log::debug!("handling {:#?}", entity);
+                let res = profile_api::application_profile_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.application_name,
+                    &entity.profile_name,
+                    entity.clone(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
             _ => {}
         }
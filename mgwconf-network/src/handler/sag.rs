@@ -29,7 +29,11 @@ where
     ) -> Result<(), anyhow::Error> {
         let mut app = app.lock().await;
         match e {
-            IoEvent::GetAllSags => {
+            IoEvent::GetAllSags | IoEvent::GetSag(..) => {
+                let (hostname, port) = match e {
+                    IoEvent::GetSag(hostname, port) => (Some(hostname.as_str()), Some(*port)),
+                    _ => (None, None),
+                };
                 let entities = sag_api::sag_get(
                     &Configuration {
                         base_path: format!(
@@ -39,7 +43,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -48,11 +52,11 @@ where
                         }),
                         ..Default::default()
                     },
-                    None,
-                    None,
+                    hostname,
+                    port,
                 )
                 .await?;
-                app.handle_network_response(IoEvent::GetAllSags, entities);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), entities);
             }
             IoEvent::PostSag(entity) => {
                 log::debug!("handling {:#?}", entity);
@@ -65,7 +69,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -77,10 +81,10 @@ where
                     entity.clone(),
                 )
                 .await?;
-                app.handle_network_response(e.clone(), res);
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
-            IoEvent::DeleteSag(e) => {
-                sag_api::sag_delete(
+            IoEvent::DeleteSag(entity) => {
+                let res = sag_api::sag_delete(
                     &Configuration {
                         base_path: format!(
                             "{}/swift/mgw/mgw-configuration-api/2.0.0",
@@ -89,7 +93,7 @@ where
                         client: client.clone(),
                         api_key: Some(ApiKey {
                             key: app
-                                .vault()
+                                .gateway_vault(config.remote_addr())
                                 .as_ref()
                                 .unwrap()
                                 .get_secret(SecretType::Configuration)
@@ -98,10 +102,38 @@ where
                         }),
                         ..Default::default()
                     },
-                    &e.hostname,
-                    e.port,
+                    &entity.hostname,
+                    entity.port,
                 )
                 .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
+            }
+            IoEvent::UpdateSag(entity) => {
+                log::debug!("handling {:#?}", entity);
+                let res = sag_api::sag_update(
+                    &Configuration {
+                        base_path: format!(
+                            "{}/swift/mgw/mgw-configuration-api/2.0.0",
+                            base_url(config)
+                        ),
+                        client: client.clone(),
+                        api_key: Some(ApiKey {
+                            key: app
+                                .gateway_vault(config.remote_addr())
+                                .as_ref()
+                                .unwrap()
+                                .get_secret(SecretType::Configuration)
+                                .to_owned(),
+                            prefix: None,
+                        }),
+                        ..Default::default()
+                    },
+                    &entity.hostname,
+                    entity.port,
+                    entity.clone(),
+                )
+                .await?;
+                app.handle_gateway_response(config.remote_addr(), e.clone(), res);
             }
             _ => {}
         }
@@ -15,7 +15,7 @@ use event::IoEvent;
 use log::debug;
 use log::{error, info};
 use mgw_configuration::apis::ResponseContent;
-use mgwconf_vault::{SecretType, SecretsVault};
+use mgwconf_vault::{SecretBackend, SecretType, SecretsVault};
 pub use reqwest::Identity;
 use reqwest::{Certificate, Client, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -29,8 +29,10 @@ use crate::handler::forward_proxy::ForwardProxyHandler;
 use crate::handler::profile::ProfileHandler;
 use crate::handler::{sag::SagHandler, Handler};
 
+pub mod context;
 pub mod event;
 pub mod handler;
+pub mod model;
 pub use mgw_configuration;
 
 #[async_trait]
@@ -44,6 +46,12 @@ pub trait AppConfig: Send + Sync + Any {
     fn unsecure(&self) -> bool {
         false
     }
+    fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(15)
+    }
+    fn request_timeout(&self) -> Option<Duration> {
+        None
+    }
 
     fn as_any(&self) -> &dyn Any;
 }
@@ -56,13 +64,17 @@ where
     async fn init(&mut self) -> Result<()>;
     async fn dispatch(&self, io_event: IoEvent) -> Result<(), anyhow::Error>;
 
-    fn ask_secrets(master: &str) -> Result<()>;
-    fn ask_secret(master: &str, s: &mut String, stype: SecretType);
+    fn ask_secrets(backend: &dyn SecretBackend) -> Result<()>;
+    fn ask_secret(backend: &dyn SecretBackend, s: &mut String, stype: SecretType);
 
     fn is_connected(&self) -> bool;
     fn set_connected(&mut self, connected: bool);
 
     fn vault(&self) -> Option<&SecretsVault>;
+    /// Vault holding the API keys of `gateway`
+    fn gateway_vault(&self, _gateway: SocketAddr) -> Option<&SecretsVault> {
+        self.vault()
+    }
     fn config(&self) -> &C;
 
     fn handle_network_response<'a, T: Deserialize<'a> + Serialize>(
@@ -70,7 +82,43 @@ where
         event: IoEvent,
         res: ResponseContent<T>,
     );
+    /// Same as `handle_network_response`, knowing which gateway answered
+    fn handle_gateway_response<'a, T: Deserialize<'a> + Serialize>(
+        &mut self,
+        _gateway: SocketAddr,
+        event: IoEvent,
+        res: ResponseContent<T>,
+    ) {
+        self.handle_network_response(event, res);
+    }
     fn handle_network_error(&mut self, error: Error);
+    /// Same as `handle_network_error`, knowing which gateway failed
+    fn handle_gateway_error(&mut self, _gateway: SocketAddr, error: Error) {
+        self.set_connected(false);
+        self.handle_network_error(error);
+    }
+    /// Same as `handle_gateway_error`, knowing which event failed
+    fn handle_event_error(&mut self, gateway: SocketAddr, _event: &IoEvent, error: Error) {
+        self.handle_gateway_error(gateway, error);
+    }
+    /// Result of a ping, the HTTP status answered by the gateway or why it could not be reached
+    fn handle_gateway_ping(&mut self, _gateway: SocketAddr, res: Result<u16, Error>) {
+        match res {
+            Ok(status) => {
+                self.set_connected(
+                    ![StatusCode::OK, StatusCode::NO_CONTENT]
+                        .map(|s| s.as_u16())
+                        .contains(&status),
+                );
+                info!(
+                    "Send ping result : {} -> connected : {}",
+                    status,
+                    self.is_connected()
+                );
+            }
+            Err(e) => self.handle_network_error(e),
+        }
+    }
 
     async fn run(app: Arc<Mutex<Self>>, notifier: Option<Arc<Notify>>)
         -> Result<(), anyhow::Error>;
@@ -101,11 +149,15 @@ where
                 "/",
                 env!("CARGO_PKG_VERSION")
             ))
-            .connect_timeout(Duration::from_secs(15))
+            .connect_timeout(config.connect_timeout())
             .add_root_certificate(certificate)
             .https_only(true)
             .danger_accept_invalid_certs(config.unsecure())
             .tls_sni(false);
+        let builder = match config.request_timeout() {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        };
         let client = if let Some(identity) = config.identity() {
             builder.identity(identity.to_owned()).build().unwrap()
         } else {
@@ -125,25 +177,14 @@ where
             self.config.remote_port(),
             "mgw-monitoring-api/1.0.0/health"
         );
-        match self.client.get(route).send().await {
-            Ok(res) => {
-                let mut app = self.app.lock().await;
-                if [StatusCode::OK, StatusCode::NO_CONTENT].contains(&res.status()) {
-                    app.set_connected(false);
-                } else {
-                    app.set_connected(true);
-                }
-                info!(
-                    "Send ping result : {} -> connected : {}",
-                    res.status(),
-                    app.is_connected()
-                );
-            }
-            Err(e) => {
-                error!("{}", e);
-                self.app.lock().await.handle_network_error(e.into());
-            }
+        let res = self.client.get(route).send().await;
+        if let Err(e) = &res {
+            error!("{}", e);
         }
+        self.app.lock().await.handle_gateway_ping(
+            self.config.remote_addr(),
+            res.map(|res| res.status().as_u16()).map_err(Error::from),
+        );
         Ok(())
     }
 
@@ -151,29 +192,44 @@ where
         debug!("Network handling {io_event:?}");
         match io_event {
             IoEvent::Ping => self.ping_mgw().await?,
-            IoEvent::GetAllSags | IoEvent::PostSag(_) | IoEvent::DeleteSag(_) => {
+            IoEvent::GetAllSags
+            | IoEvent::GetSag(..)
+            | IoEvent::PostSag(_)
+            | IoEvent::DeleteSag(_)
+            | IoEvent::UpdateSag(_) => {
                 SagHandler::handle(&self.client, self.app, self.config, io_event).await?;
             }
             IoEvent::GetAllCertificates
+            | IoEvent::GetCertificate(_)
             | IoEvent::PostCertificate(_)
-            | IoEvent::DeleteCertificate(_) => {
+            | IoEvent::DeleteCertificate(_)
+            | IoEvent::UpdateCertificate(_) => {
                 CertHandler::handle(&self.client, self.app, self.config, io_event).await?;
             }
-            IoEvent::GetAllProfiles | IoEvent::PostProfile(_) | IoEvent::DeleteProfile(_) => {
+            IoEvent::GetAllProfiles
+            | IoEvent::GetProfiles(..)
+            | IoEvent::PostProfile(_)
+            | IoEvent::DeleteProfile(_)
+            | IoEvent::UpdateProfile(_) => {
                 ProfileHandler::handle(&self.client, self.app, self.config, io_event).await?;
             }
             IoEvent::GetAllForwardProxyEntity
             | IoEvent::PostForwardProxyEntity(_)
-            | IoEvent::DeleteForwardProxyEntity(_) => {
+            | IoEvent::DeleteForwardProxyEntity(_)
+            | IoEvent::UpdateForwardProxyEntity(_) => {
                 ForwardProxyHandler::handle(&self.client, self.app, self.config, io_event).await?;
             }
             IoEvent::GetAllBusinessApplications
             | IoEvent::PostBusinessApplication(_)
-            | IoEvent::DeleteBusinessApplication(_) => {
+            | IoEvent::DeleteBusinessApplication(_)
+            | IoEvent::UpdateBusinessApplication(_) => {
                 BusinessApplicationHandler::handle(&self.client, self.app, self.config, io_event)
                     .await?;
             }
-            IoEvent::GetAllApiClientCredentials | IoEvent::PostApiClientCredential(_) => {
+            IoEvent::GetAllApiClientCredentials
+            | IoEvent::PostApiClientCredential(_)
+            | IoEvent::DeleteApiClientCredential(_)
+            | IoEvent::UpdateApiClientCredential(_) => {
                 ApiClientCredentialHandler::handle(&self.client, self.app, self.config, io_event)
                     .await?;
             }
@@ -187,9 +243,10 @@ where
         match self.handle_io_event(&io_event).await {
             Ok(_) => Ok(()),
             Err(e) => {
-                let mut app = self.app.lock().await;
-                app.set_connected(false);
-                app.handle_network_error(e);
+                self.app
+                    .lock()
+                    .await
+                    .handle_event_error(self.config.remote_addr(), &io_event, e);
                 Err(Error::msg("Network Error"))
             }
         }
@@ -0,0 +1,12 @@
+use mgw_configuration::models::ApiCredentialsEntity;
+
+use super::{mask_secret, Masked};
+
+impl Masked for ApiCredentialsEntity {
+    fn masked(&self) -> Self {
+        ApiCredentialsEntity {
+            consumer_secret: mask_secret(&self.consumer_secret),
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    fs,
+    net::IpAddr,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Result};
+use mgw_configuration::models::CertificateEntity;
+use openssl::{
+    asn1::{Asn1Time, Asn1TimeRef},
+    hash::MessageDigest,
+    pkcs12::Pkcs12,
+    pkey::{Id, PKey, Private},
+    x509::{X509NameRef, X509},
+};
+use serde::Serialize;
+
+/// Details extracted from the X.509 certificate of a `CertificateEntity`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateDetails {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    /// Expiry date as a unix timestamp, used for sorting and thresholds
+    pub expires_at: i64,
+    pub key_type: String,
+    pub key_bits: u32,
+    pub sha256_fingerprint: String,
+}
+
+impl CertificateDetails {
+    /// Parse the first certificate found in a PEM string
+    pub fn from_pem(pem: &str) -> Result<CertificateDetails> {
+        let x509 = X509::from_pem(pem.as_bytes())?;
+        CertificateDetails::from_x509(&x509)
+    }
+
+    pub fn from_x509(x509: &X509) -> Result<CertificateDetails> {
+        let public_key = x509.public_key()?;
+        let key_type = match public_key.id() {
+            Id::RSA => "RSA",
+            Id::RSA_PSS => "RSA-PSS",
+            Id::EC => "EC",
+            Id::DSA => "DSA",
+            Id::ED25519 => "Ed25519",
+            Id::ED448 => "Ed448",
+            _ => "Unknown",
+        };
+        let sans = x509
+            .subject_alt_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| {
+                        if let Some(dns) = name.dnsname() {
+                            Some(format!("DNS:{dns}"))
+                        } else if let Some(ip) = name.ipaddress() {
+                            format_ip(ip).map(|ip| format!("IP:{ip}"))
+                        } else if let Some(email) = name.email() {
+                            Some(format!("email:{email}"))
+                        } else {
+                            name.uri().map(|uri| format!("URI:{uri}"))
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(CertificateDetails {
+            subject: format_name(x509.subject_name()),
+            issuer: format_name(x509.issuer_name()),
+            sans,
+            not_before: x509.not_before().to_string(),
+            not_after: x509.not_after().to_string(),
+            expires_at: to_unix(x509.not_after())?,
+            key_type: key_type.to_owned(),
+            key_bits: public_key.bits(),
+            sha256_fingerprint: x509
+                .digest(MessageDigest::sha256())?
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect::<Vec<String>>()
+                .join(":"),
+        })
+    }
+
+    /// Number of whole days before expiry, negative when already expired
+    pub fn days_left(&self) -> i64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        (self.expires_at - now).div_euclid(86400)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.days_left() < 0
+    }
+}
+
+impl TryFrom<&CertificateEntity> for CertificateDetails {
+    type Error = anyhow::Error;
+
+    fn try_from(entity: &CertificateEntity) -> Result<Self, Self::Error> {
+        CertificateDetails::from_pem(&entity.certificate_x509)
+            .map_err(|e| anyhow!("Cannot parse certificate {} : {}", entity.alias, e))
+    }
+}
+
+/// Sort certificates by expiry date, soonest first.
+/// Certificates that cannot be parsed are moved to the end.
+pub fn sort_by_expiry(certificates: &mut [CertificateEntity]) {
+    certificates.sort_by_cached_key(|c| {
+        CertificateDetails::try_from(c)
+            .map(|d| d.expires_at)
+            .unwrap_or(i64::MAX)
+    });
+}
+
+/// A certificate file (`.pem`, `.crt`, `.der` or `.p12`) to import under a given alias
+#[derive(Debug, Clone, Default)]
+pub struct CertificateSource {
+    pub alias: String,
+    pub path: PathBuf,
+    /// Separate private key file (PEM or DER), the key may also be embedded in `path`
+    pub private_key: Option<PathBuf>,
+    /// PKCS#12 password
+    pub password: Option<String>,
+    /// Import expired certificates anyway
+    pub force: bool,
+}
+
+impl CertificateSource {
+    /// Read the certificate chain and optional private key into a `CertificateEntity`
+    ///
+    /// The leaf certificate must match the private key and must not be expired unless `force` is set
+    pub fn load(&self) -> Result<CertificateEntity> {
+        if self.alias.is_empty() {
+            bail!("An alias is required to import {:?}", self.path);
+        }
+        let buf = fs::read(&self.path)?;
+        let extension = self
+            .path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let (chain, mut private_key) = if ["p12", "pfx"].contains(&extension.as_str()) {
+            let parsed = Pkcs12::from_der(&buf)?.parse2(self.password.as_deref().unwrap_or(""))?;
+            let mut chain = parsed.cert.into_iter().collect::<Vec<X509>>();
+            chain.extend(parsed.ca.into_iter().flatten());
+            (chain, parsed.pkey)
+        } else if String::from_utf8_lossy(&buf).contains("-----BEGIN") {
+            let key = if String::from_utf8_lossy(&buf).contains("PRIVATE KEY-----") {
+                Some(PKey::private_key_from_pem(&buf)?)
+            } else {
+                None
+            };
+            (X509::stack_from_pem(&buf)?, key)
+        } else {
+            (vec![X509::from_der(&buf)?], None)
+        };
+        if let Some(path) = &self.private_key {
+            let buf = fs::read(path)?;
+            private_key = Some(if buf.starts_with(b"-----BEGIN") {
+                PKey::private_key_from_pem(&buf)?
+            } else {
+                PKey::private_key_from_der(&buf)?
+            });
+        }
+        let leaf = chain
+            .first()
+            .ok_or_else(|| anyhow!("No certificate found in {:?}", self.path))?;
+        check_private_key(leaf, private_key.as_ref())?;
+        let details = CertificateDetails::from_x509(leaf)?;
+        if details.is_expired() && !self.force {
+            bail!(
+                "Certificate {} expired on {}, use force to import it anyway",
+                details.subject,
+                details.not_after
+            );
+        }
+        let mut certificate_x509 = String::new();
+        for certificate in chain.iter() {
+            certificate_x509.push_str(&String::from_utf8(certificate.to_pem()?)?);
+        }
+        Ok(CertificateEntity {
+            alias: self.alias.to_owned(),
+            certificate_x509: certificate_x509.trim_end().to_owned(),
+            private_key: private_key
+                .map(|k| k.private_key_to_pem_pkcs8())
+                .transpose()?
+                .map(String::from_utf8)
+                .transpose()?
+                .map(|k| k.trim_end().to_owned()),
+        })
+    }
+}
+
+fn check_private_key(leaf: &X509, private_key: Option<&PKey<Private>>) -> Result<()> {
+    if let Some(key) = private_key {
+        if !leaf.public_key()?.public_eq(key) {
+            bail!("Private key does not match the certificate");
+        }
+    }
+    Ok(())
+}
+
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            format!("{key}={value}")
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn format_ip(ip: &[u8]) -> Option<IpAddr> {
+    match ip.len() {
+        4 => <[u8; 4]>::try_from(ip).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(ip).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+fn to_unix(time: &Asn1TimeRef) -> Result<i64> {
+    let diff = Asn1Time::from_unix(0)?.diff(time)?;
+    Ok(diff.days as i64 * 86400 + diff.secs as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        x509::{extension::SubjectAlternativeName, X509Name},
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    fn key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    /// Self-signed certificate expiring `days` from now, an hour later to stay clear of the
+    /// day boundary
+    fn certificate(key: &PKey<Private>, days: i64) -> X509 {
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_text("CN", "mgw.example.com").unwrap();
+        name.append_entry_by_text("O", "Example").unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::from_unix(now() - 400 * DAY).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::from_unix(now() + days * DAY + 3600).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns("mgw.example.com")
+            .ip("127.0.0.1")
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(key, MessageDigest::sha256()).unwrap();
+        builder.build()
+    }
+
+    fn pem(certificate: &X509) -> String {
+        String::from_utf8(certificate.to_pem().unwrap()).unwrap()
+    }
+
+    fn entity(alias: &str, certificate_x509: String) -> CertificateEntity {
+        CertificateEntity {
+            alias: alias.to_owned(),
+            certificate_x509,
+            private_key: None,
+        }
+    }
+
+    fn source(dir: &TempDir, file: &str, content: &[u8]) -> CertificateSource {
+        let path = dir.path().join(file);
+        fs::write(&path, content).unwrap();
+        CertificateSource {
+            alias: "mgw".to_owned(),
+            path,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn details_from_pem() {
+        let details = CertificateDetails::from_pem(&pem(&certificate(&key(), 30))).unwrap();
+        assert_eq!(details.subject, "CN=mgw.example.com, O=Example");
+        assert_eq!(details.issuer, details.subject);
+        assert_eq!(details.sans, vec!["DNS:mgw.example.com", "IP:127.0.0.1"]);
+        assert_eq!(details.key_type, "EC");
+        assert_eq!(details.key_bits, 256);
+        assert_eq!(details.days_left(), 30);
+        assert!(!details.is_expired());
+        assert_eq!(details.sha256_fingerprint.split(':').count(), 32);
+        assert!(CertificateDetails::from_pem("not a certificate").is_err());
+    }
+
+    #[test]
+    fn expired_details() {
+        let details = CertificateDetails::from_pem(&pem(&certificate(&key(), -2))).unwrap();
+        assert_eq!(details.days_left(), -2);
+        assert!(details.is_expired());
+    }
+
+    #[test]
+    fn sort_by_expiry_puts_unparsable_last() {
+        let key = key();
+        let mut certificates = vec![
+            entity("broken", "not a certificate".to_owned()),
+            entity("late", pem(&certificate(&key, 90))),
+            entity("soon", pem(&certificate(&key, 10))),
+            entity("expired", pem(&certificate(&key, -1))),
+        ];
+        sort_by_expiry(&mut certificates);
+        let aliases = certificates
+            .iter()
+            .map(|c| c.alias.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(aliases, vec!["expired", "soon", "late", "broken"]);
+    }
+
+    #[test]
+    fn load_pem_with_its_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key();
+        let certificate = certificate(&key, 30);
+        let mut content = certificate.to_pem().unwrap();
+        content.extend(key.private_key_to_pem_pkcs8().unwrap());
+        let entity = source(&dir, "mgw.pem", &content).load().unwrap();
+        assert_eq!(entity.alias, "mgw");
+        assert_eq!(entity.certificate_x509, pem(&certificate).trim_end());
+        let private_key =
+            PKey::private_key_from_pem(entity.private_key.unwrap().as_bytes()).unwrap();
+        assert!(private_key.public_eq(&key));
+    }
+
+    #[test]
+    fn load_pem_with_a_separate_der_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key();
+        let mut source = source(&dir, "mgw.crt", &certificate(&key, 30).to_pem().unwrap());
+        let key_path = dir.path().join("mgw.key");
+        fs::write(&key_path, key.private_key_to_der().unwrap()).unwrap();
+        source.private_key = Some(key_path);
+        assert!(source.load().unwrap().private_key.is_some());
+    }
+
+    #[test]
+    fn load_pkcs12() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key();
+        let certificate = certificate(&key, 30);
+        let pkcs12 = Pkcs12::builder()
+            .name("mgw")
+            .pkey(&key)
+            .cert(&certificate)
+            .build2("secret")
+            .unwrap();
+        let mut source = source(&dir, "mgw.p12", &pkcs12.to_der().unwrap());
+        assert!(source.load().is_err());
+        source.password = Some("secret".to_owned());
+        let entity = source.load().unwrap();
+        assert_eq!(entity.certificate_x509, pem(&certificate).trim_end());
+        assert!(entity.private_key.is_some());
+    }
+
+    #[test]
+    fn load_refuses_a_key_of_another_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut content = certificate(&key(), 30).to_pem().unwrap();
+        content.extend(key().private_key_to_pem_pkcs8().unwrap());
+        assert_eq!(
+            source(&dir, "mgw.pem", &content)
+                .load()
+                .unwrap_err()
+                .to_string(),
+            "Private key does not match the certificate"
+        );
+    }
+
+    #[test]
+    fn load_refuses_an_expired_certificate_unless_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let certificate = certificate(&key(), -1);
+        let mut source = source(&dir, "mgw.der", &certificate.to_der().unwrap());
+        let error = source.load().unwrap_err().to_string();
+        assert_eq!(
+            error,
+            format!(
+                "Certificate CN=mgw.example.com, O=Example expired on {}, use force to import it anyway",
+                certificate.not_after()
+            )
+        );
+        source.force = true;
+        assert!(source.load().is_ok());
+        source.alias.clear();
+        assert!(source.load().is_err());
+    }
+}
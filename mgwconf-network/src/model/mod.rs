@@ -0,0 +1,21 @@
+pub mod api_credentials;
+pub mod certificate;
+
+/// Placeholder used when a secret value is hidden
+pub const MASK: &str = "********";
+
+/// Entities carrying secrets that must not be displayed by default
+pub trait Masked {
+    /// Returns a copy of the entity with every secret replaced by [`MASK`]
+    fn masked(&self) -> Self;
+}
+
+/// Hide a secret, only keeping its last 4 characters when it is long enough
+pub fn mask_secret(secret: &str) -> String {
+    let len = secret.chars().count();
+    if len <= 8 {
+        return MASK.to_owned();
+    }
+    let suffix: String = secret.chars().skip(len - 4).collect();
+    format!("{MASK}{suffix}")
+}
@@ -8,27 +8,45 @@ use crossterm::{
     ExecutableCommand,
 };
 use mgwconf_network::{
-    event::IoEvent,
-    mgw_configuration::{apis::ResponseContent, models::*},
-    AppConfig, AppTrait,
+    event::IoEvent, mgw_configuration::apis::ResponseContent, AppConfig, AppTrait,
 };
-use mgwconf_vault::{SecretType, SecretsVault};
+use mgwconf_vault::{SecretBackend, SecretType, SecretsVault};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use serde::{Deserialize, Serialize};
-use std::{io::stdout, sync::Arc};
-use tokio::sync::mpsc::Sender;
+use std::{
+    io::stdout,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::{Mutex, Notify};
 
 pub mod state;
+#[cfg(test)]
+mod tests;
 
 use crate::{
     config::Config,
-    event::{Event, Events, Key},
+    event::{
+        keymap::{Action, KeyMap},
+        Event, EventConfig, Events,
+    },
     handler::{handle_app, handle_input},
+    i18n::{self, tr, tr_args, Message},
     ui::draw_main_layout,
 };
 
-use self::state::{configuration::ConfigurationState, State};
+use self::state::{
+    change::Change,
+    compare::{Compare, CompareRow},
+    configuration::ConfigurationState,
+    gateway::{Gateway, Health},
+    notification::Notifications,
+    State,
+};
+
+/// Gateways are pinged at this interval while the home screen is shown
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 
 #[async_trait]
 pub trait UiAppTrait<C: AppConfig>: AppTrait<C> {
@@ -53,26 +71,56 @@ pub trait UiAppTrait<C: AppConfig>: AppTrait<C> {
     fn get_force_exit(&self) -> bool;
     fn force_exit(&mut self);
 
-    fn pop_error(&self) -> Option<&Error>;
+    fn keymap(&self) -> &KeyMap;
+    /// Show the help overlay over the current block, until `hide_help`
+    fn show_help(&mut self);
+    fn hide_help(&mut self);
+
+    fn get_notifications(&self) -> &Notifications;
+    fn get_notifications_mut(&mut self) -> &mut Notifications;
+
+    fn gateways(&self) -> &[Gateway];
+    /// Index of the gateway the configuration is read from and sent to
+    fn active_gateway(&self) -> usize;
+    /// Index of the gateway highlighted on the home screen
+    fn selected_gateway(&self) -> usize;
+    fn select_gateway(&mut self, index: usize);
+    /// Make a gateway active, the entities of the previous one are kept until it is back
+    fn switch_gateway(&mut self, index: usize);
+
+    /// Compare the active gateway with the selected one, or the next one when it is active
+    fn open_compare(&mut self) -> bool;
+    /// Load the compared tab from both gateways
+    fn reload_compare(&mut self);
+    fn get_compare(&self) -> Option<&Compare>;
+    fn get_compare_mut(&mut self) -> Option<&mut Compare>;
+    fn compare_rows(&self) -> Vec<CompareRow>;
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum ActiveBlock {
     Empty,
-    Error,
     HelpMenu,
     Home,
     Tab,
     TabSelected,
     Dialog,
+    /// Deletion waiting for a confirmation
+    Confirm,
     Detailed,
     Editing,
+    Compare,
+    Notifications,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum RouteId {
     Home,
     Configuration,
+    /// Entities of two gateways side by side
+    Compare,
+    /// History of the notifications
+    Notifications,
 }
 
 const DEFAULT_ROUTE: Route = Route {
@@ -93,31 +141,36 @@ pub struct UiApp {
     pub config: Option<Config>,
     pub configuration_state: ConfigurationState,
     pub connectivity_test: bool,
-    io_tx: Sender<IoEvent>,
+    /// Not empty, the first one is active at startup
+    pub gateways: Vec<Gateway>,
+    active_gateway: usize,
+    selected_gateway: usize,
+    compare: Option<Compare>,
+    last_health_check: Option<Instant>,
     pub input: String,
     navigation_stack: Vec<Route>,
-    error_queue: Vec<Error>,
+    notifications: Notifications,
+    keymap: KeyMap,
+    /// Block under the help overlay
+    help_from: Option<ActiveBlock>,
     pub selected_configuration_tab: Option<usize>,
-    pub vault: Option<SecretsVault>,
 
     initialized: bool,
     pub force_exit: bool,
 }
 
 impl UiApp {
-    pub async fn new(io_tx: Sender<IoEvent>, mut config: Config, vault_key: &str) -> UiApp {
+    pub async fn new(gateways: Vec<Gateway>, mut config: Config) -> UiApp {
         config.init_logging();
-        let vault = match SecretsVault::new(vault_key) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("Can't decode secret vault : {}", e);
-                panic!("Can't decode secret vault : {}", e);
-            }
-        };
+        i18n::set_locale(config.locale);
+        let keymap = config.keymap.clone();
         UiApp {
             config: Some(config),
-            io_tx,
-            vault: Some(vault),
+            gateways,
+            active_gateway: 0,
+            selected_gateway: 0,
+            compare: None,
+            last_health_check: None,
             configuration_state: ConfigurationState::default(),
             connectivity_test: false,
             input: String::new(),
@@ -125,7 +178,80 @@ impl UiApp {
             selected_configuration_tab: None,
             initialized: false,
             force_exit: false,
-            error_queue: Vec::new(),
+            notifications: Notifications::default(),
+            keymap,
+            help_from: None,
+        }
+    }
+
+    fn gateway(&self) -> Result<&Gateway> {
+        self.gateways
+            .get(self.active_gateway)
+            .ok_or_else(|| anyhow::anyhow!(tr(Message::NoGateway)))
+    }
+
+    fn gateway_index(&self, addr: SocketAddr) -> Option<usize> {
+        self.gateways
+            .iter()
+            .position(|gateway| gateway.addr == addr)
+    }
+
+    /// The gateway accepted a create, update or delete event, its tab is reloaded once the
+    /// form sending it, if any, is done
+    fn entity_changed(&mut self, event: &IoEvent) {
+        if self
+            .configuration_state
+            .form()
+            .is_some_and(|form| form.awaits(event))
+        {
+            self.form_accepted();
+            return;
+        }
+        let Some(change) = Change::of(event) else {
+            return;
+        };
+        self.notifications.success(change.to_string());
+        if let Err(e) = self
+            .gateway()
+            .and_then(|gateway| gateway.try_dispatch(change.tab.get_all_event()))
+        {
+            self.notifications.error(e.to_string());
+        }
+    }
+
+    /// The gateway accepted an event of the form, the next one is sent or the form is closed
+    /// and its tab reloaded
+    fn form_accepted(&mut self) {
+        let Some(form) = self
+            .configuration_state
+            .form_mut()
+            .filter(|form| form.sending)
+        else {
+            return;
+        };
+        let tab = form.tab;
+        let event = match form.next_event() {
+            Some(event) => event,
+            None => {
+                self.notifications.success(tr_args(Message::Saved, &[&tab]));
+                self.configuration_state.close_form();
+                if matches!(
+                    self.get_current_route().active_block,
+                    ActiveBlock::Dialog | ActiveBlock::Editing
+                ) {
+                    self.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+                }
+                tab.get_all_event()
+            }
+        };
+        if let Err(e) = self
+            .gateway()
+            .and_then(|gateway| gateway.try_dispatch(event))
+        {
+            match self.configuration_state.form_mut() {
+                Some(form) => form.fail(e.to_string()),
+                None => self.notifications.error(e.to_string()),
+            }
         }
     }
 }
@@ -137,34 +263,29 @@ impl AppTrait<Config> for UiApp {
             return Ok(());
         }
         log::info!("Initilizing UiApp...");
-        self.io_tx.send(IoEvent::Ping).await?;
+        for gateway in &self.gateways {
+            gateway.dispatch(IoEvent::Ping).await?;
+        }
+        self.last_health_check = Some(Instant::now());
         log::info!("Ping sent...");
         self.initialized = true;
         Ok(())
     }
 
     async fn dispatch(&self, io_event: IoEvent) -> Result<(), anyhow::Error> {
-        self.io_tx.send(io_event).await?;
-        Ok(())
+        self.gateway()?.dispatch(io_event).await
     }
 
-    fn ask_secrets(master: &str) -> Result<()> {
+    fn ask_secrets(backend: &dyn SecretBackend) -> Result<()> {
         let mut secret = String::new();
         for s in SecretType::iterator() {
-            <UiApp as AppTrait<Config>>::ask_secret(master, &mut secret, *s);
+            <UiApp as AppTrait<Config>>::ask_secret(backend, &mut secret, *s);
         }
         print!("\x1B[2J\x1B[1;1H");
         Ok(())
     }
 
-    #[cfg(not(feature = "store"))]
-    #[allow(unused_variables)]
-    fn ask_secret(master: &str, s: &mut String, stype: SecretType) {
-        panic!("To create a vault store you must enable store features");
-    }
-
-    #[cfg(feature = "store")]
-    fn ask_secret(master: &str, s: &mut String, stype: SecretType) {
+    fn ask_secret(backend: &dyn SecretBackend, s: &mut String, stype: SecretType) {
         use std::io::{stdin, stdout, Write};
         println!("Pleaser enter {} API KEY", stype);
         let _ = stdout().flush();
@@ -172,10 +293,9 @@ impl AppTrait<Config> for UiApp {
             .read_line(s)
             .expect("Did not enter a correct string");
         s.pop();
-        SecretsVault::new(master)
-            .unwrap()
-            .create_secret(stype, s.to_owned())
-            .unwrap();
+        backend
+            .write_secret(stype, s)
+            .unwrap_or_else(|e| panic!("Can't store {stype} secret : {e}"));
         s.clear()
     }
 
@@ -187,8 +307,14 @@ impl AppTrait<Config> for UiApp {
         self.connectivity_test = connected;
     }
 
+    /// Vault of the active gateway
     fn vault(&self) -> Option<&SecretsVault> {
-        self.vault.as_ref()
+        self.gateways.get(self.active_gateway).map(|g| &g.vault)
+    }
+
+    /// Each gateway has its own API keys, they are never sent to another gateway
+    fn gateway_vault(&self, gateway: SocketAddr) -> Option<&SecretsVault> {
+        self.gateway_index(gateway).map(|i| &self.gateways[i].vault)
     }
 
     fn config(&self) -> &Config {
@@ -201,91 +327,161 @@ impl AppTrait<Config> for UiApp {
         res: ResponseContent<T>,
     ) {
         match event {
-            IoEvent::Ping => todo!(),
-            IoEvent::GetAllProfiles => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.profiles =
-                        serde_json::from_value::<Vec<ApplicationProfileEntity>>(
-                            serde_json::to_value(entity).unwrap(),
-                        )
-                        .unwrap();
+            // Ping results are given to `handle_gateway_ping`
+            IoEvent::Ping => log::debug!("Ping answered : {}", res.status),
+            IoEvent::GetAllProfiles
+            | IoEvent::GetAllBusinessApplications
+            | IoEvent::GetAllCertificates
+            | IoEvent::GetAllSags
+            | IoEvent::GetAllApiGatewayInfoEntity
+            | IoEvent::GetAllForwardProxyEntity
+            | IoEvent::GetAllApiClientCredentials => {
+                match self.configuration_state.load(&event, res.entity) {
+                    Ok(_) => self.configuration_state.refresh_view(),
+                    Err(e) => self.notifications.error(tr_args(
+                        Message::UnreadableResponse,
+                        &[&format!("{:?}", event), &e],
+                    )),
                 }
             }
-            IoEvent::GetAllBusinessApplications => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.business_applications =
-                        serde_json::from_value::<Vec<BusinessApplicationEntity>>(
-                            serde_json::to_value(entity).unwrap(),
-                        )
-                        .unwrap();
-                }
+            // Not sent by the UI, which reads whole lists
+            IoEvent::GetSag(..) | IoEvent::GetCertificate(_) | IoEvent::GetProfiles(..) => {
+                log::debug!("Ignoring the response to {:?}", event)
             }
-            IoEvent::GetAllCertificates => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.certificates =
-                        serde_json::from_value::<Vec<CertificateEntity>>(
-                            serde_json::to_value(entity).unwrap(),
-                        )
-                        .unwrap();
+            IoEvent::PostBusinessApplication(_)
+            | IoEvent::PostApiGatewayInfoEntity(_)
+            | IoEvent::PostForwardProxyEntity(_)
+            | IoEvent::PostProfile(_)
+            | IoEvent::PostCertificate(_)
+            | IoEvent::PostApiClientCredential(_)
+            | IoEvent::PostSag(_)
+            | IoEvent::DeleteApiGatewayInfoEntity(_)
+            | IoEvent::DeleteForwardProxyEntity(_)
+            | IoEvent::DeleteBusinessApplication(_)
+            | IoEvent::DeleteCertificate(_)
+            | IoEvent::DeleteSag(_)
+            | IoEvent::DeleteProfile(_)
+            | IoEvent::DeleteApiClientCredential(_)
+            | IoEvent::UpdateApiClientCredential(_)
+            | IoEvent::UpdateSag(_)
+            | IoEvent::UpdateProfile(_)
+            | IoEvent::UpdateCertificate(_)
+            | IoEvent::UpdateForwardProxyEntity(_)
+            | IoEvent::UpdateBusinessApplication(_) => self.entity_changed(&event),
+        }
+    }
+
+    /// Entities of an inactive gateway are kept in its own state, for the comparison and
+    /// until it is active again
+    fn handle_gateway_response<'a, T: Deserialize<'a> + Serialize>(
+        &mut self,
+        gateway: SocketAddr,
+        event: IoEvent,
+        res: ResponseContent<T>,
+    ) {
+        match self.gateway_index(gateway) {
+            Some(i) if i != self.active_gateway => {
+                let gateway = &mut self.gateways[i];
+                match gateway.state.load(&event, res.entity) {
+                    Ok(_) => gateway.state.refresh_view(),
+                    Err(e) => self.notifications.error(tr_args(
+                        Message::UnreadableGatewayResponse,
+                        &[&gateway.name, &format!("{:?}", event), &e],
+                    )),
                 }
             }
-            IoEvent::GetAllSags => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.sags = serde_json::from_value::<Vec<SagEntity>>(
-                        serde_json::to_value(entity).unwrap(),
-                    )
-                    .unwrap();
-                }
+            _ => self.handle_network_response(event, res),
+        }
+    }
+
+    fn handle_gateway_error(&mut self, gateway: SocketAddr, error: Error) {
+        match self.gateway_index(gateway) {
+            Some(i) if i != self.active_gateway => {
+                log::error!("Gateway {} : {}", self.gateways[i].name, error);
+                self.notifications.error(format!(
+                    "{} : {}",
+                    self.gateways[i].name,
+                    error.root_cause()
+                ));
+                self.gateways[i].health = Health::Down(error.to_string());
             }
-            IoEvent::GetAllApiGatewayInfoEntity => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.apiproxy =
-                        serde_json::from_value::<Vec<ApiGatewayInfoEntity>>(
-                            serde_json::to_value(entity).unwrap(),
-                        )
-                        .unwrap();
-                }
+            _ => {
+                self.set_connected(false);
+                self.handle_network_error(error);
             }
-            IoEvent::GetAllForwardProxyEntity => {
-                if let Some(entity) = res.entity {
-                    self.configuration_state.forwardproxy =
-                        serde_json::from_value::<Vec<ForwardProxyEntity>>(
-                            serde_json::to_value(entity).unwrap(),
-                        )
-                        .unwrap();
-                }
+        }
+    }
+
+    fn handle_gateway_ping(&mut self, gateway: SocketAddr, res: Result<u16, Error>) {
+        let index = self.gateway_index(gateway);
+        if let Some(i) = index {
+            let health = match &res {
+                Ok(status) => Health::Up(*status),
+                Err(e) => Health::Down(e.to_string()),
+            };
+            // Only changes are notified, gateways are pinged periodically
+            let name = &self.gateways[i].name;
+            match (&self.gateways[i].health, &health) {
+                (Health::Down(_), Health::Up(_)) => self
+                    .notifications
+                    .success(tr_args(Message::Reachable, &[name])),
+                (Health::Unknown | Health::Up(_), Health::Down(error)) => self
+                    .notifications
+                    .error(tr_args(Message::Unreachable, &[name, error])),
+                _ => {}
+            }
+            self.gateways[i].health = health;
+        }
+        if index.is_some_and(|i| i != self.active_gateway) {
+            return;
+        }
+        // Same as the default of `AppTrait`, for the active gateway
+        match res {
+            Ok(status) => {
+                self.set_connected(![200, 204].contains(&status));
+                log::info!(
+                    "Send ping result : {} -> connected : {}",
+                    status,
+                    self.is_connected()
+                );
+            }
+            Err(e) if index.is_some() => log::error!("{}", e),
+            Err(e) => self.handle_network_error(e),
+        }
+    }
+
+    /// Errors of the event awaited by the form are shown in the dialog, next to the field they
+    /// are about
+    fn handle_event_error(&mut self, gateway: SocketAddr, event: &IoEvent, error: Error) {
+        let active = self
+            .gateway_index(gateway)
+            .is_none_or(|i| i == self.active_gateway);
+        if active
+            && self
+                .configuration_state
+                .form()
+                .is_some_and(|form| form.awaits(event))
+        {
+            log::error!("Handling this error : {}", error);
+            self.set_connected(false);
+            if let Some(form) = self.configuration_state.form_mut() {
+                form.fail(error.to_string());
             }
-            IoEvent::PostCertificate(_) => {}
-            IoEvent::PostSag(_) => {}
-            //IoEvent::PostBusinessApplication => todo!(),
-            //IoEvent::PostCertificate => todo!(),
-            //IoEvent::PostSag => todo!(),
-            //IoEvent::PostProfile => todo!(),
-            //IoEvent::DeleteBusinessApplication(_e) => todo!(),
-            //IoEvent::DeleteCertificate(_e) => todo!(),
-            //IoEvent::DeleteSag(_e) => todo!(),
-            _ => todo!(),
+            return;
         }
+        self.handle_gateway_error(gateway, error);
     }
 
     fn handle_network_error(&mut self, error: Error) {
         log::error!("Handling this error : {}", error);
-        self.error_queue.push(error);
-        if <UiApp as UiAppTrait<Config>>::get_current_route(self).id != RouteId::Home {
-            <UiApp as UiAppTrait<Config>>::set_current_route_state(
-                self,
-                Some(ActiveBlock::Error),
-                None,
-            );
-        }
+        // The user stays where they are, the error is shown as a toast
+        self.notifications.error(error.root_cause().to_string());
     }
 
     async fn run(
         app: Arc<Mutex<UiApp>>,
         _notifier: Option<Arc<Notify>>,
     ) -> Result<(), anyhow::Error> {
-        use std::time::{Duration, Instant};
-
         use mgwconf_network::AppTrait;
 
         let mut stdout = stdout();
@@ -297,10 +493,19 @@ impl AppTrait<Config> for UiApp {
         let mut terminal = Terminal::new(backend)?;
         terminal.hide_cursor()?;
 
-        let tickrate = <UiApp as AppTrait<Config>>::config(&*app.lock().await).tickrate();
+        let (tickrate, exit_key) = {
+            let app = app.lock().await;
+            (
+                <UiApp as AppTrait<Config>>::config(&*app).tickrate(),
+                app.keymap.exit_key(),
+            )
+        };
 
         let tick_rate = Duration::from_millis(tickrate);
-        let mut events = Events::new(tickrate);
+        let mut events = Events::with_config(EventConfig {
+            exit_key,
+            tick_rate,
+        });
         let mut last_tick = Instant::now();
 
         'main: loop {
@@ -320,7 +525,10 @@ impl AppTrait<Config> for UiApp {
 
             match events.next().unwrap()? {
                 Event::Input(key) => {
-                    if key == Key::Esc
+                    if key == events.exit_key() {
+                        break 'main;
+                    }
+                    if app.keymap.matches(Action::Back, &key)
                         && (current_route.active_block == ActiveBlock::Empty
                             || current_route.active_block == ActiveBlock::Tab)
                     {
@@ -329,7 +537,7 @@ impl AppTrait<Config> for UiApp {
 
                     let current_active_block = current_route.active_block;
 
-                    if current_active_block == ActiveBlock::Dialog {
+                    if current_active_block == ActiveBlock::Editing {
                         handle_input::<UiApp, Config>(key, &mut *app);
                     } else {
                         handle_app::<UiApp, Config>(key, &mut *app).await
@@ -339,19 +547,6 @@ impl AppTrait<Config> for UiApp {
                     if <UiApp as UiAppTrait<Config>>::get_force_exit(&app) {
                         break 'main;
                     }
-                    if <UiApp as UiAppTrait<Config>>::get_current_route(&app).active_block
-                        == ActiveBlock::Error
-                    {
-                        std::thread::sleep(Duration::from_secs(2));
-                        <UiApp as UiAppTrait<Config>>::reset_navigation_stack(&mut app);
-                        <UiApp as UiAppTrait<Config>>::push_navigation_stack(
-                            &mut app,
-                            RouteId::Home,
-                            ActiveBlock::Home,
-                        );
-                        *<UiApp as UiAppTrait<Config>>::get_configuration_state_mut(&mut app) =
-                            ConfigurationState::default();
-                    }
                     <UiApp as UiAppTrait<Config>>::update_on_tick(&mut app);
                 }
             }
@@ -427,6 +622,19 @@ impl UiAppTrait<Config> for UiApp {
         if !self.configuration_state.waiting_for_load() {
             self.configuration_state.update_pan_len();
         }
+        if self.get_current_route().id == RouteId::Home
+            && self
+                .last_health_check
+                .is_some_and(|last| last.elapsed() >= HEALTH_CHECK_INTERVAL)
+        {
+            self.last_health_check = Some(Instant::now());
+            for gateway in &self.gateways {
+                // A gateway still busy with the previous ping is not pinged again
+                if let Err(e) = gateway.try_dispatch(IoEvent::Ping) {
+                    log::debug!("{}", e);
+                }
+            }
+        }
     }
 
     fn get_configuration_state(&self) -> &ConfigurationState {
@@ -453,7 +661,116 @@ impl UiAppTrait<Config> for UiApp {
         self.force_exit
     }
 
-    fn pop_error(&self) -> Option<&Error> {
-        self.error_queue.last()
+    fn keymap(&self) -> &KeyMap {
+        &self.keymap
+    }
+
+    fn show_help(&mut self) {
+        let active_block = self.get_current_route().active_block;
+        if active_block != ActiveBlock::HelpMenu {
+            self.help_from = Some(active_block);
+            self.set_current_route_state(Some(ActiveBlock::HelpMenu), None);
+        }
+    }
+
+    fn hide_help(&mut self) {
+        if let Some(active_block) = self.help_from.take() {
+            self.set_current_route_state(Some(active_block), None);
+        }
+    }
+
+    fn get_notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+
+    fn get_notifications_mut(&mut self) -> &mut Notifications {
+        &mut self.notifications
+    }
+
+    fn gateways(&self) -> &[Gateway] {
+        &self.gateways
+    }
+
+    fn active_gateway(&self) -> usize {
+        self.active_gateway
+    }
+
+    fn selected_gateway(&self) -> usize {
+        self.selected_gateway
+    }
+
+    fn select_gateway(&mut self, index: usize) {
+        if index < self.gateways.len() {
+            self.selected_gateway = index;
+        }
+    }
+
+    fn switch_gateway(&mut self, index: usize) {
+        if index == self.active_gateway || index >= self.gateways.len() {
+            return;
+        }
+        std::mem::swap(
+            &mut self.configuration_state,
+            &mut self.gateways[self.active_gateway].state,
+        );
+        std::mem::swap(
+            &mut self.configuration_state,
+            &mut self.gateways[index].state,
+        );
+        log::info!("Switching to gateway {}", self.gateways[index].name);
+        self.notifications.info(tr_args(
+            Message::ActiveGateway,
+            &[&self.gateways[index].name],
+        ));
+        self.active_gateway = index;
+        self.compare = None;
+        self.connectivity_test = false;
+        if let Err(e) = self.gateways[index].try_dispatch(IoEvent::Ping) {
+            self.handle_network_error(e);
+        }
+    }
+
+    fn open_compare(&mut self) -> bool {
+        let other = if self.selected_gateway != self.active_gateway {
+            Some(self.selected_gateway)
+        } else {
+            (0..self.gateways.len()).find(|i| *i != self.active_gateway)
+        };
+        let Some(other) = other else {
+            return false;
+        };
+        self.compare = Some(Compare::new(other));
+        self.reload_compare();
+        true
+    }
+
+    fn reload_compare(&mut self) {
+        let Some(compare) = &self.compare else {
+            return;
+        };
+        let event = compare.tab.get_all_event();
+        for i in [self.active_gateway, compare.other] {
+            if let Err(e) = self.gateways[i].try_dispatch(event.clone()) {
+                log::error!("{}", e);
+            }
+        }
+    }
+
+    fn get_compare(&self) -> Option<&Compare> {
+        self.compare.as_ref()
+    }
+
+    fn get_compare_mut(&mut self) -> Option<&mut Compare> {
+        self.compare.as_mut()
+    }
+
+    fn compare_rows(&self) -> Vec<CompareRow> {
+        match &self.compare {
+            Some(compare) => compare.rows(
+                self.configuration_state.entities(compare.tab),
+                self.gateways[compare.other].state.entities(compare.tab),
+            ),
+            None => Vec::new(),
+        }
     }
 }
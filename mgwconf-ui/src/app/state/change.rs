@@ -0,0 +1,71 @@
+use std::fmt;
+
+use mgwconf_network::event::IoEvent;
+use serde::Serialize;
+
+use super::TabId;
+use crate::i18n::{tr_args, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// Entity changed by a create, update or delete event accepted by the gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub tab: TabId,
+    pub action: Action,
+    /// Key fields of the entity, see `TabId::key`
+    pub key: String,
+}
+
+impl Change {
+    /// `None` for the events reading entities
+    pub fn of(event: &IoEvent) -> Option<Change> {
+        fn change<T: Serialize>(tab: TabId, action: Action, entity: &T) -> Option<Change> {
+            Some(Change {
+                tab,
+                action,
+                key: tab.key(&serde_json::to_value(entity).unwrap_or_default()),
+            })
+        }
+        use Action::*;
+        match event {
+            IoEvent::PostCertificate(e) => change(TabId::CERTIFICATE, Created, e),
+            IoEvent::PostSag(e) => change(TabId::SAG, Created, e),
+            IoEvent::PostBusinessApplication(e) => change(TabId::BUSINESSAPPLICATION, Created, e),
+            IoEvent::PostProfile(e) => change(TabId::PROFILE, Created, e),
+            IoEvent::PostApiGatewayInfoEntity(e) => change(TabId::APIPROXY, Created, e),
+            IoEvent::PostForwardProxyEntity(e) => change(TabId::FORWARDPROXY, Created, e),
+            IoEvent::PostApiClientCredential(e) => change(TabId::APICLIENTCREDENTIAL, Created, e),
+            IoEvent::UpdateCertificate(e) => change(TabId::CERTIFICATE, Updated, e),
+            IoEvent::UpdateSag(e) => change(TabId::SAG, Updated, e),
+            IoEvent::UpdateBusinessApplication(e) => change(TabId::BUSINESSAPPLICATION, Updated, e),
+            IoEvent::UpdateProfile(e) => change(TabId::PROFILE, Updated, e),
+            IoEvent::UpdateForwardProxyEntity(e) => change(TabId::FORWARDPROXY, Updated, e),
+            IoEvent::UpdateApiClientCredential(e) => change(TabId::APICLIENTCREDENTIAL, Updated, e),
+            IoEvent::DeleteCertificate(e) => change(TabId::CERTIFICATE, Deleted, e),
+            IoEvent::DeleteSag(e) => change(TabId::SAG, Deleted, e),
+            IoEvent::DeleteBusinessApplication(e) => change(TabId::BUSINESSAPPLICATION, Deleted, e),
+            IoEvent::DeleteProfile(e) => change(TabId::PROFILE, Deleted, e),
+            IoEvent::DeleteApiGatewayInfoEntity(e) => change(TabId::APIPROXY, Deleted, e),
+            IoEvent::DeleteForwardProxyEntity(e) => change(TabId::FORWARDPROXY, Deleted, e),
+            IoEvent::DeleteApiClientCredential(e) => change(TabId::APICLIENTCREDENTIAL, Deleted, e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.action {
+            Action::Created => Message::Created,
+            Action::Updated => Message::Updated,
+            Action::Deleted => Message::Deleted,
+        };
+        write!(f, "{}", tr_args(message, &[&self.tab, &self.key]))
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::TabId;
+
+/// How an entity of the active gateway differs from the compared one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    Same,
+    /// Fields with different values
+    Changed(Vec<String>),
+    OnlyLeft,
+    OnlyRight,
+}
+
+/// Entities of both gateways with the same key fields
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    pub key: String,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+    pub difference: Difference,
+}
+
+/// Entities of a tab of the active gateway, on the left, next to another gateway
+#[derive(Debug, Clone)]
+pub struct Compare {
+    /// Index of the compared gateway
+    pub other: usize,
+    pub tab: TabId,
+    pub selected: usize,
+    /// Entities which are the same on both gateways are hidden
+    pub only_differences: bool,
+}
+
+impl Compare {
+    pub fn new(other: usize) -> Compare {
+        Compare {
+            other,
+            tab: TabId::CERTIFICATE,
+            selected: 0,
+            only_differences: false,
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.tab = TabId::from((self.tab as usize + 1) % TabId::COUNT);
+        self.selected = 0;
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.tab = TabId::from((self.tab as usize + TabId::COUNT - 1) % TabId::COUNT);
+        self.selected = 0;
+    }
+
+    pub fn toggle_only_differences(&mut self) {
+        self.only_differences = !self.only_differences;
+        self.selected = 0;
+    }
+
+    /// Entities of both sides matched by their key fields, sorted by key
+    pub fn rows(&self, left: Vec<Value>, right: Vec<Value>) -> Vec<CompareRow> {
+        let mut rows: BTreeMap<String, (Option<Value>, Option<Value>)> = BTreeMap::new();
+        for entity in left {
+            let key = self.tab.key(&entity);
+            rows.entry(key).or_default().0 = Some(entity);
+        }
+        for entity in right {
+            let key = self.tab.key(&entity);
+            rows.entry(key).or_default().1 = Some(entity);
+        }
+        rows.into_iter()
+            .map(|(key, (left, right))| {
+                let difference = match (&left, &right) {
+                    (Some(left), Some(right)) => match changed(left, right) {
+                        fields if fields.is_empty() => Difference::Same,
+                        fields => Difference::Changed(fields),
+                    },
+                    (Some(_), None) => Difference::OnlyLeft,
+                    _ => Difference::OnlyRight,
+                };
+                CompareRow {
+                    key,
+                    left,
+                    right,
+                    difference,
+                }
+            })
+            .filter(|row| !self.only_differences || row.difference != Difference::Same)
+            .collect()
+    }
+}
+
+/// Top level fields whose values differ, a missing field is the same as null
+fn changed(left: &Value, right: &Value) -> Vec<String> {
+    let (Some(left), Some(right)) = (left.as_object(), right.as_object()) else {
+        return Vec::new();
+    };
+    let mut fields = left
+        .keys()
+        .chain(right.keys().filter(|key| !left.contains_key(*key)))
+        .filter(|key| {
+            left.get(*key).unwrap_or(&Value::Null) != right.get(*key).unwrap_or(&Value::Null)
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+    fields.sort();
+    fields
+}
@@ -1,6 +1,17 @@
-use mgwconf_network::mgw_configuration::{models::*, InnerEntityTrait};
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::{models::*, InnerEntityTrait},
+    model::certificate::CertificateDetails,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 
-use super::{State, TabId};
+use super::{
+    delete::{Deleted, Deletion},
+    form::Form,
+    list::{CertificateSummary, Chip, ListView, Row},
+    State, TabId,
+};
 
 #[derive(Debug)]
 pub struct ConfigurationState {
@@ -19,15 +30,22 @@ pub struct ConfigurationState {
     pub profiles: Vec<ApplicationProfileEntity>,
     pub apiproxy: Vec<ApiGatewayInfoEntity>,
     pub forwardproxy: Vec<ForwardProxyEntity>,
+    pub api_client_credentials: Vec<ApiCredentialsEntity>,
 
+    show_secrets: bool,
+    list: ListView,
     current_entity: Option<Box<dyn InnerEntityTrait>>,
+    form: Option<Form>,
+    deletion: Option<Deletion>,
+    /// Undo buffer, only the last deletion is kept
+    deleted: Option<Deleted>,
 }
 
 impl Default for ConfigurationState {
     fn default() -> Self {
         ConfigurationState {
             tab_id: 0,
-            tab_len: 6,
+            tab_len: TabId::COUNT,
             selected_tab: None,
             in_panel: false,
             sags: Vec::default(),
@@ -36,15 +54,263 @@ impl Default for ConfigurationState {
             profiles: Vec::default(),
             apiproxy: Vec::default(),
             forwardproxy: Vec::default(),
+            api_client_credentials: Vec::default(),
             pan_id: 0,
             pan_len: 0,
             waiting: false,
 
+            show_secrets: false,
+            list: ListView::default(),
             current_entity: None,
+            form: None,
+            deletion: None,
+            deleted: None,
         }
     }
 }
 
+impl ConfigurationState {
+    /// Show or hide secrets (e.g. consumer secrets) in the detailed view
+    pub fn toggle_secrets(&mut self) {
+        self.show_secrets = !self.show_secrets;
+    }
+
+    pub fn secrets_visible(&self) -> bool {
+        self.show_secrets
+    }
+
+    pub fn list(&self) -> &ListView {
+        &self.list
+    }
+
+    /// Entities of the selected tab shown by the list, in its order
+    pub fn visible<'a, T>(&self, entities: &'a [T]) -> Vec<&'a T> {
+        self.list
+            .rows()
+            .iter()
+            .filter_map(|i| entities.get(*i))
+            .collect()
+    }
+
+    /// Store the entities loaded by a `GetAll*` event, other events are ignored
+    pub fn load<T: Serialize>(
+        &mut self,
+        event: &IoEvent,
+        entity: Option<T>,
+    ) -> Result<(), serde_json::Error> {
+        fn entities<E: DeserializeOwned, T: Serialize>(
+            entity: Option<T>,
+            entities: &mut Vec<E>,
+        ) -> Result<(), serde_json::Error> {
+            if let Some(entity) = entity {
+                *entities = serde_json::from_value(serde_json::to_value(entity)?)?;
+            }
+            Ok(())
+        }
+        match event {
+            IoEvent::GetAllProfiles => entities(entity, &mut self.profiles),
+            IoEvent::GetAllBusinessApplications => {
+                entities(entity, &mut self.business_applications)
+            }
+            IoEvent::GetAllCertificates => entities(entity, &mut self.certificates),
+            IoEvent::GetAllSags => entities(entity, &mut self.sags),
+            IoEvent::GetAllApiGatewayInfoEntity => entities(entity, &mut self.apiproxy),
+            IoEvent::GetAllForwardProxyEntity => entities(entity, &mut self.forwardproxy),
+            IoEvent::GetAllApiClientCredentials => {
+                entities(entity, &mut self.api_client_credentials)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// JSON of the entities of a tab, in their loading order
+    pub fn entities(&self, tab: TabId) -> Vec<Value> {
+        fn values<T: Serialize>(entities: &[T]) -> Vec<Value> {
+            entities
+                .iter()
+                .map(|e| serde_json::to_value(e).unwrap_or_default())
+                .collect()
+        }
+        match tab {
+            TabId::CERTIFICATE => values(&self.certificates),
+            TabId::SAG => values(&self.sags),
+            TabId::BUSINESSAPPLICATION => values(&self.business_applications),
+            TabId::PROFILE => values(&self.profiles),
+            TabId::APIPROXY => values(&self.apiproxy),
+            TabId::FORWARDPROXY => values(&self.forwardproxy),
+            TabId::APICLIENTCREDENTIAL => values(&self.api_client_credentials),
+        }
+    }
+
+    /// Filter and sort the selected tab list again, after a load or a change of its view
+    pub fn refresh_view(&mut self) {
+        let tab = self.current_selected();
+        let mut entities = self
+            .entities(tab)
+            .into_iter()
+            .map(|fields| Row {
+                fields,
+                certificate: None,
+            })
+            .collect::<Vec<Row>>();
+        if tab == TabId::CERTIFICATE {
+            for (row, certificate) in entities.iter_mut().zip(self.certificates.iter()) {
+                row.certificate =
+                    CertificateDetails::try_from(certificate)
+                        .ok()
+                        .map(|d| CertificateSummary {
+                            days_left: d.days_left(),
+                            subject: d.subject,
+                            not_after: d.not_after,
+                        });
+            }
+        }
+        self.list.refresh(tab, entities);
+        self.pan_len = self.list.rows().len() + 1;
+        if self.pan_id >= self.pan_len {
+            self.pan_id = 0;
+        }
+        self.select_entity();
+    }
+
+    pub fn page_down(&mut self) {
+        self.move_to(self.pan_id + self.list.page());
+    }
+
+    pub fn page_up(&mut self) {
+        self.move_to(self.pan_id.saturating_sub(self.list.page()));
+    }
+
+    pub fn first(&mut self) {
+        self.move_to(0);
+    }
+
+    pub fn last(&mut self) {
+        self.move_to(self.pan_len.saturating_sub(1));
+    }
+
+    /// Select a row of the list, past the end selects the last one
+    fn move_to(&mut self, pan_id: usize) {
+        if self.in_panel && self.pan_len > 0 {
+            self.pan_id = pan_id.min(self.pan_len - 1);
+            self.select_entity();
+        }
+    }
+
+    /// Start typing a search, the input buffer holds it until Enter or Esc
+    pub fn start_search(&mut self) -> String {
+        self.list.searching = true;
+        self.list.search.clone()
+    }
+
+    pub fn set_search(&mut self, search: &str) {
+        self.list.search = search.to_owned();
+        self.refresh_view();
+    }
+
+    /// Stop typing the search, it is kept unless `clear`
+    pub fn end_search(&mut self, clear: bool) {
+        self.list.searching = false;
+        if clear {
+            self.set_search("");
+        }
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.list.searching
+    }
+
+    /// Toggle the chip of the selected tab with the given number, starting at 1
+    pub fn toggle_chip(&mut self, number: usize) {
+        let chip = number
+            .checked_sub(1)
+            .and_then(|i| Chip::for_tab(self.current_selected()).get(i));
+        if let Some(chip) = chip {
+            self.list.toggle_chip(*chip);
+            self.refresh_view();
+        }
+    }
+
+    pub fn next_sort_column(&mut self) {
+        self.list.next_column(self.current_selected());
+        self.refresh_view();
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.list.descending = !self.list.descending;
+        self.refresh_view();
+    }
+
+    /// Start the create dialog of the selected tab entity
+    pub fn open_form(&mut self) {
+        self.form = Some(Form::new(self.current_selected()));
+    }
+
+    /// Edit form of the entity shown in the detail view, certificates are not editable since the
+    /// gateway does not return their private key
+    pub fn open_edit_form(&mut self) {
+        if self.current_selected() == TabId::CERTIFICATE {
+            return;
+        }
+        if let Some(entity) = self.selected_entity() {
+            self.form = Some(Form::edit(self.current_selected(), &entity.to_string()));
+        }
+    }
+
+    /// Ask to confirm the deletion of the selected entity, `LIVE_CONFIRMATION` is typed on a
+    /// LIVE gateway
+    pub fn ask_delete(&mut self, live_gateway: bool) {
+        self.deletion = self
+            .selected_entity()
+            .and_then(|entity| Deletion::new(self.current_selected(), *entity, live_gateway));
+    }
+
+    pub fn deletion(&self) -> Option<&Deletion> {
+        self.deletion.as_ref()
+    }
+
+    pub fn deletion_mut(&mut self) -> Option<&mut Deletion> {
+        self.deletion.as_mut()
+    }
+
+    pub fn cancel_delete(&mut self) {
+        self.deletion = None;
+    }
+
+    /// Delete event of the confirmed deletion, the entity is kept for undo when it can be
+    /// created again
+    pub fn confirm_delete(&mut self) -> Option<IoEvent> {
+        if !self.deletion.as_ref()?.is_confirmed() {
+            return None;
+        }
+        let deletion = self.deletion.take()?;
+        let (event, deleted) = deletion.confirm();
+        self.deleted = deleted;
+        Some(event)
+    }
+
+    pub fn last_deleted(&self) -> Option<&Deleted> {
+        self.deleted.as_ref()
+    }
+
+    /// Event creating the last deleted entity again
+    pub fn undo_delete(&mut self) -> Option<IoEvent> {
+        Some(self.deleted.take()?.restore())
+    }
+
+    pub fn close_form(&mut self) {
+        self.form = None;
+    }
+
+    pub fn form(&self) -> Option<&Form> {
+        self.form.as_ref()
+    }
+
+    pub fn form_mut(&mut self) -> Option<&mut Form> {
+        self.form.as_mut()
+    }
+}
+
 impl State for ConfigurationState {
     fn next(&mut self) {
         if self.in_panel && self.pan_len > 0 {
@@ -85,26 +351,26 @@ impl State for ConfigurationState {
             self.current_entity = None;
             return;
         }
-        let entity: Option<Box<dyn InnerEntityTrait>> = match self.current_selected() {
-            TabId::CERTIFICATE => Some(Box::new(
-                self.certificates.get(self.pan_id - 1).unwrap().clone(),
-            )),
-            TabId::SAG => Some(Box::new(self.sags.get(self.pan_id - 1).unwrap().clone())),
-            TabId::BUSINESSAPPLICATION => Some(Box::new(
-                self.business_applications
-                    .get(self.pan_id - 1)
-                    .unwrap()
-                    .clone(),
-            )),
-            TabId::PROFILE => Some(Box::new(
-                self.profiles.get(self.pan_id - 1).unwrap().clone(),
-            )),
-            TabId::APIPROXY => Some(Box::new(
-                self.apiproxy.get(self.pan_id - 1).unwrap().clone(),
-            )),
-            TabId::FORWARDPROXY => Some(Box::new(
-                self.forwardproxy.get(self.pan_id - 1).unwrap().clone(),
-            )),
+        let Some(i) = self.list.rows().get(self.pan_id - 1).copied() else {
+            self.current_entity = None;
+            return;
+        };
+        fn boxed<T: InnerEntityTrait + Clone + 'static>(
+            entities: &[T],
+            i: usize,
+        ) -> Option<Box<dyn InnerEntityTrait>> {
+            entities
+                .get(i)
+                .map(|e| Box::new(e.clone()) as Box<dyn InnerEntityTrait>)
+        }
+        let entity = match self.current_selected() {
+            TabId::CERTIFICATE => boxed(&self.certificates, i),
+            TabId::SAG => boxed(&self.sags, i),
+            TabId::BUSINESSAPPLICATION => boxed(&self.business_applications, i),
+            TabId::PROFILE => boxed(&self.profiles, i),
+            TabId::APIPROXY => boxed(&self.apiproxy, i),
+            TabId::FORWARDPROXY => boxed(&self.forwardproxy, i),
+            TabId::APICLIENTCREDENTIAL => boxed(&self.api_client_credentials, i),
         };
         if let Some(e) = entity {
             self.current_entity = Some(e);
@@ -133,19 +399,13 @@ impl State for ConfigurationState {
         self.pan_id = 0;
         self.selected_tab = Some(self.tab_id);
         self.in_panel = true;
+        self.list = ListView::default();
+        self.refresh_view();
         self.update_pan_len();
     }
 
     fn update_pan_len(&mut self) {
-        match self.tab_id {
-            0 => self.pan_len = self.certificates.len() + 1,
-            1 => self.pan_len = self.sags.len() + 1,
-            2 => self.pan_len = self.business_applications.len() + 1,
-            3 => self.pan_len = self.profiles.len() + 1,
-            4 => self.pan_len = self.apiproxy.len() + 1,
-            5 => self.pan_len = self.forwardproxy.len() + 1,
-            _ => {}
-        }
+        self.pan_len = self.list.rows().len() + 1;
         if self.pan_len > 0 {
             self.waiting = false;
         }
@@ -0,0 +1,162 @@
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::{models::*, InnerEntityTrait},
+};
+use serde_json::Value;
+
+use super::TabId;
+
+/// Text to type before deleting an entity of a LIVE environment
+pub const LIVE_CONFIRMATION: &str = "LIVE";
+
+/// Deletion waiting for the user's confirmation
+#[derive(Debug, Clone)]
+pub struct Deletion {
+    pub tab: TabId,
+    /// Key fields of the entity, with their value
+    pub summary: Vec<(&'static str, String)>,
+    /// The entity or its gateway belongs to a LIVE environment, `LIVE_CONFIRMATION` must be
+    /// typed
+    pub live: bool,
+    pub typed: String,
+    /// Secret fields missing from the cached copy, the deletion cannot be undone when any
+    pub secrets_missing: Vec<&'static str>,
+    delete: IoEvent,
+    restore: IoEvent,
+}
+
+/// Last deleted entity, its cached copy is posted again on undo
+#[derive(Debug, Clone)]
+pub struct Deleted {
+    pub tab: TabId,
+    pub summary: String,
+    restore: IoEvent,
+}
+
+impl Deletion {
+    pub fn new(tab: TabId, entity: &dyn InnerEntityTrait, live_gateway: bool) -> Option<Deletion> {
+        let (delete, restore) = events(tab, entity)?;
+        let json = serde_json::from_str::<Value>(&entity.to_string()).unwrap_or_default();
+        let summary = tab
+            .key_fields()
+            .iter()
+            .map(|key| {
+                let value = match json.get(*key) {
+                    Some(Value::String(s)) => s.to_owned(),
+                    Some(value) => value.to_string(),
+                    None => String::new(),
+                };
+                (*key, value)
+            })
+            .collect();
+        let secrets_missing = tab
+            .secret_fields()
+            .iter()
+            .filter(|field| match json.get(**field) {
+                Some(Value::String(s)) => s.is_empty() || s.chars().all(|c| c == '*'),
+                _ => true,
+            })
+            .copied()
+            .collect();
+        Some(Deletion {
+            tab,
+            summary,
+            live: live_gateway
+                || json.get("environment").and_then(Value::as_str) == Some(LIVE_CONFIRMATION),
+            typed: String::new(),
+            secrets_missing,
+            delete,
+            restore,
+        })
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        !self.live || self.typed == LIVE_CONFIRMATION
+    }
+
+    /// Posting the cached copy again creates the same entity
+    pub fn is_undoable(&self) -> bool {
+        self.secrets_missing.is_empty()
+    }
+
+    /// Delete event to dispatch, and the entity to keep for undo when its secrets are known
+    pub fn confirm(self) -> (IoEvent, Option<Deleted>) {
+        if !self.is_undoable() {
+            return (self.delete, None);
+        }
+        let summary = self
+            .summary
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        (
+            self.delete,
+            Some(Deleted {
+                tab: self.tab,
+                summary,
+                restore: self.restore,
+            }),
+        )
+    }
+}
+
+impl Deleted {
+    /// Event creating the deleted entity again
+    pub fn restore(self) -> IoEvent {
+        self.restore
+    }
+}
+
+/// Delete and create events of the entity shown in a tab
+fn events(tab: TabId, entity: &dyn InnerEntityTrait) -> Option<(IoEvent, IoEvent)> {
+    let any = entity.as_any();
+    Some(match tab {
+        TabId::CERTIFICATE => {
+            let e = any.downcast_ref::<CertificateEntity>()?;
+            (
+                IoEvent::DeleteCertificate(e.clone()),
+                IoEvent::PostCertificate(e.clone()),
+            )
+        }
+        TabId::SAG => {
+            let e = any.downcast_ref::<SagEntity>()?;
+            (IoEvent::DeleteSag(e.clone()), IoEvent::PostSag(e.clone()))
+        }
+        TabId::BUSINESSAPPLICATION => {
+            let e = any.downcast_ref::<BusinessApplicationEntity>()?;
+            (
+                IoEvent::DeleteBusinessApplication(e.clone()),
+                IoEvent::PostBusinessApplication(e.clone()),
+            )
+        }
+        TabId::PROFILE => {
+            let e = any.downcast_ref::<ApplicationProfileEntity>()?;
+            (
+                IoEvent::DeleteProfile(e.clone()),
+                IoEvent::PostProfile(e.clone()),
+            )
+        }
+        TabId::APIPROXY => {
+            let e = any.downcast_ref::<ApiGatewayInfoEntity>()?;
+            (
+                IoEvent::DeleteApiGatewayInfoEntity(e.clone()),
+                IoEvent::PostApiGatewayInfoEntity(e.clone()),
+            )
+        }
+        TabId::FORWARDPROXY => {
+            let e = any.downcast_ref::<ForwardProxyEntity>()?;
+            (
+                IoEvent::DeleteForwardProxyEntity(e.clone()),
+                IoEvent::PostForwardProxyEntity(e.clone()),
+            )
+        }
+        TabId::APICLIENTCREDENTIAL => {
+            let e = any.downcast_ref::<ApiCredentialsEntity>()?;
+            (
+                IoEvent::DeleteApiClientCredential(e.clone()),
+                IoEvent::PostApiClientCredential(e.clone()),
+            )
+        }
+    })
+}
@@ -0,0 +1,664 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use mgwconf_network::{
+    event::IoEvent, mgw_configuration::models::*, model::certificate::CertificateSource,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::TabId;
+use crate::i18n::{tr, tr_args, Message};
+
+/// How a form field is edited and checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    /// Text shown masked
+    Secret,
+    Integer,
+    Boolean,
+    /// One of the given values, cycled with Left and Right
+    Choice(&'static [&'static str]),
+    /// Several values, such as user DNs
+    List,
+    /// Path chosen with the file picker
+    File,
+}
+
+#[derive(Debug, Clone)]
+pub struct FormField {
+    /// JSON name of the entity field
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub value: String,
+    /// Values of a list field
+    pub items: Vec<String>,
+    /// Selected value of a list field
+    pub item: usize,
+    pub error: Option<String>,
+}
+
+impl FormField {
+    fn new(name: &'static str, label: Message, kind: FieldKind, required: bool) -> Self {
+        let value = match kind {
+            FieldKind::Boolean => String::from("false"),
+            FieldKind::Choice(choices) => choices.first().copied().unwrap_or_default().to_owned(),
+            _ => String::new(),
+        };
+        FormField {
+            name,
+            label: tr(label),
+            kind,
+            required,
+            value,
+            items: Vec::new(),
+            item: 0,
+            error: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self.kind {
+            FieldKind::List => self.items.is_empty(),
+            _ => self.value.is_empty(),
+        }
+    }
+
+    /// Check the field alone, its error is kept to be shown next to it
+    pub fn validate(&mut self) -> bool {
+        self.error = if self.required && self.is_empty() {
+            Some(tr(Message::Required).to_owned())
+        } else if self.is_empty() {
+            None
+        } else {
+            match self.kind {
+                FieldKind::Integer => match self.value.parse::<i32>() {
+                    Ok(port) if self.name == "port" && !(1..=65535).contains(&port) => {
+                        Some(tr_args(Message::PortOutOfRange, &[&port]))
+                    }
+                    Ok(_) => None,
+                    Err(_) => Some(tr_args(Message::NotANumber, &[&self.value])),
+                },
+                FieldKind::List if self.name == "userDNs" => self
+                    .items
+                    .iter()
+                    .find(|dn| dn.split(',').any(|rdn| !rdn.contains('=')))
+                    .map(|dn| tr_args(Message::NotADistinguishedName, &[dn])),
+                FieldKind::File if !Path::new(&self.value).is_file() => {
+                    Some(tr_args(Message::NotAFile, &[&self.value]))
+                }
+                _ => None,
+            }
+        };
+        self.error.is_none()
+    }
+}
+
+/// Browses directories to choose a file, `..` comes first
+#[derive(Debug, Clone)]
+pub struct FilePicker {
+    pub dir: PathBuf,
+    pub entries: Vec<PathBuf>,
+    pub selected: usize,
+}
+
+impl FilePicker {
+    pub fn new(start: &Path) -> FilePicker {
+        let dir = if start.is_dir() {
+            start.to_path_buf()
+        } else {
+            start
+                .parent()
+                .filter(|p| p.is_dir())
+                .map(Path::to_path_buf)
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_else(|| PathBuf::from("."))
+        };
+        let mut picker = FilePicker {
+            dir,
+            entries: Vec::new(),
+            selected: 0,
+        };
+        picker.read();
+        picker
+    }
+
+    fn read(&mut self) {
+        self.dir = self.dir.canonicalize().unwrap_or_else(|_| self.dir.clone());
+        let mut entries = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| {
+                        !p.file_name()
+                            .map(|n| n.to_string_lossy().starts_with('.'))
+                            .unwrap_or(true)
+                    })
+                    .collect::<Vec<PathBuf>>()
+            })
+            .unwrap_or_default();
+        // Directories first, then files, by name
+        entries.sort_by_key(|p| (!p.is_dir(), p.file_name().map(|n| n.to_os_string())));
+        if let Some(parent) = self.dir.parent() {
+            entries.insert(0, parent.to_path_buf());
+        }
+        self.entries = entries;
+        self.selected = 0;
+    }
+
+    /// Entry as shown in the picker
+    pub fn label(&self, entry: &Path) -> String {
+        if Some(entry) == self.dir.parent() {
+            String::from("../")
+        } else {
+            let name = entry
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if entry.is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        }
+    }
+
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1) % self.entries.len();
+        }
+    }
+
+    pub fn back(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    /// A selected directory is entered, a selected file is returned
+    pub fn open(&mut self) -> Option<PathBuf> {
+        let entry = self.entries.get(self.selected)?.clone();
+        if entry.is_dir() {
+            self.dir = entry;
+            self.read();
+            None
+        } else {
+            Some(entry)
+        }
+    }
+
+    pub fn parent(&mut self) {
+        if let Some(parent) = self.dir.parent() {
+            self.dir = parent.to_path_buf();
+            self.read();
+        }
+    }
+}
+
+/// Create or edit dialog of the entity of a configuration tab
+#[derive(Debug, Clone)]
+pub struct Form {
+    pub tab: TabId,
+    pub fields: Vec<FormField>,
+    /// Selected field, `fields.len()` selects the submit button
+    pub selected: usize,
+    pub picker: Option<FilePicker>,
+    /// Why the entity cannot be built, or why the gateway refused it
+    pub error: Option<String>,
+    /// JSON of the edited entity, `None` when creating one
+    original: Option<Value>,
+    /// The diff is shown and waits for a confirmation
+    pub review: bool,
+    /// Events not sent yet, each one is sent once the previous one succeeded
+    queue: Vec<IoEvent>,
+    sent: usize,
+    /// Event sent last, waiting for the gateway's answer
+    in_flight: Option<IoEvent>,
+    pub sending: bool,
+}
+
+impl Form {
+    pub fn new(tab: TabId) -> Form {
+        use FieldKind::*;
+        let fields = match tab {
+            TabId::CERTIFICATE => vec![
+                FormField::new("alias", Message::FieldAlias, Text, true),
+                FormField::new("certificate", Message::FieldCertificateFile, File, true),
+                FormField::new("privateKey", Message::FieldPrivateKeyFile, File, false),
+                FormField::new("password", Message::FieldPkcs12Password, Secret, false),
+                FormField::new("force", Message::FieldForce, Boolean, false),
+            ],
+            TabId::SAG => vec![
+                FormField::new("hostname", Message::FieldHostname, Text, true),
+                FormField::new("port", Message::FieldPort, Integer, true),
+                FormField::new(
+                    "messagePartnerName",
+                    Message::FieldMessagePartner,
+                    Text,
+                    false,
+                ),
+                FormField::new("userDNs", Message::FieldUserDns, List, true),
+                FormField::new("lauKey", Message::FieldLauKey, Secret, false),
+                FormField::new("sslDN", Message::FieldSslDn, Text, false),
+                FormField::new("active", Message::FieldActive, Boolean, false),
+                FormField::new(
+                    "publicCertificateAlias",
+                    Message::FieldPublicCertificateAlias,
+                    Text,
+                    false,
+                ),
+            ],
+            TabId::BUSINESSAPPLICATION => vec![
+                FormField::new("applicationName", Message::FieldApplicationName, Text, true),
+                FormField::new("sharedSecret", Message::FieldSharedSecret, Secret, false),
+            ],
+            TabId::PROFILE => vec![
+                FormField::new("applicationName", Message::FieldApplicationName, Text, true),
+                FormField::new("profileName", Message::FieldProfileName, Text, true),
+                FormField::new("rbacScope", Message::FieldRbacScope, Text, false),
+                FormField::new("userDNs", Message::FieldUserDns, List, false),
+                FormField::new(
+                    "certificateAlias",
+                    Message::FieldCertificateAlias,
+                    Text,
+                    false,
+                ),
+            ],
+            TabId::APIPROXY => vec![
+                FormField::new(
+                    "environment",
+                    Message::FieldEnvironment,
+                    Choice(&["DEV", "SANDBOX", "TEST", "LIVE"]),
+                    true,
+                ),
+                FormField::new(
+                    "publicCertAlias",
+                    Message::FieldPublicCertificateAlias,
+                    Text,
+                    true,
+                ),
+            ],
+            TabId::FORWARDPROXY => vec![
+                FormField::new("hostname", Message::FieldHostname, Text, true),
+                FormField::new("port", Message::FieldPort, Integer, true),
+                FormField::new("user", Message::FieldUser, Text, false),
+                FormField::new("password", Message::FieldPassword, Secret, false),
+            ],
+            TabId::APICLIENTCREDENTIAL => vec![
+                FormField::new("applicationName", Message::FieldApplicationName, Text, true),
+                FormField::new("consumerKey", Message::FieldConsumerKey, Text, true),
+                FormField::new("consumerSecret", Message::FieldConsumerSecret, Secret, true),
+            ],
+        };
+        Form {
+            tab,
+            fields,
+            selected: 0,
+            picker: None,
+            error: None,
+            original: None,
+            review: false,
+            queue: Vec::new(),
+            sent: 0,
+            in_flight: None,
+            sending: false,
+        }
+    }
+
+    /// Form filled with an existing entity, given as the JSON of `InnerEntityTrait::to_string`
+    pub fn edit(tab: TabId, json: &str) -> Form {
+        let mut form = Form::new(tab);
+        let original = serde_json::from_str::<Value>(json).unwrap_or_default();
+        for field in form.fields.iter_mut() {
+            match (field.kind, original.get(field.name)) {
+                (_, None) | (_, Some(Value::Null)) => {}
+                (FieldKind::List, Some(Value::Array(items))) => {
+                    field.items = items
+                        .iter()
+                        .map(|item| item.as_str().map(str::to_owned).unwrap_or(item.to_string()))
+                        .collect();
+                }
+                (_, Some(Value::String(value))) => field.value = value.to_owned(),
+                (_, Some(value)) => field.value = value.to_string(),
+            }
+        }
+        form.original = Some(original);
+        form
+    }
+
+    pub fn is_edit(&self) -> bool {
+        self.original.is_some()
+    }
+
+    /// Fields changed by the edit, with their value before and after, secrets are not shown
+    pub fn diff(&self) -> Vec<(&'static str, String, String)> {
+        let (Some(original), Ok(values)) = (&self.original, self.values()) else {
+            return Vec::new();
+        };
+        let show = |value: Option<&Value>| match value {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.to_owned(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_owned).unwrap_or(item.to_string()))
+                .collect::<Vec<String>>()
+                .join(", "),
+            Some(value) => value.to_string(),
+        };
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let before = match (field.kind, original.get(field.name)) {
+                    // An absent flag is unset
+                    (FieldKind::Boolean, None) => String::from("false"),
+                    (_, value) => show(value),
+                };
+                let after = show(values.get(field.name));
+                match (before == after, field.kind) {
+                    (true, _) => None,
+                    (false, FieldKind::Secret) => Some((
+                        field.label,
+                        String::from("****"),
+                        tr(Message::SecretChanged).to_owned(),
+                    )),
+                    (false, _) => Some((field.label, before, after)),
+                }
+            })
+            .collect()
+    }
+
+    /// Send `events` in order, the first one is returned to be dispatched
+    pub fn start(&mut self, mut events: Vec<IoEvent>) -> Option<IoEvent> {
+        events.reverse();
+        self.queue = events;
+        self.sent = 0;
+        self.sending = true;
+        self.error = None;
+        self.next_event()
+    }
+
+    /// Event to send once the previous one succeeded, `None` when all were sent
+    pub fn next_event(&mut self) -> Option<IoEvent> {
+        let event = self.queue.pop();
+        if event.is_some() {
+            self.sent += 1;
+        }
+        self.in_flight = event.clone();
+        event
+    }
+
+    /// The gateway's answer to `event` is awaited by the form
+    pub fn awaits(&self, event: &IoEvent) -> bool {
+        self.sending && self.in_flight.as_ref() == Some(event)
+    }
+
+    /// The gateway refused an event, the error is shown next to the field it names
+    pub fn fail(&mut self, error: String) {
+        let applied = self.sent.saturating_sub(1);
+        self.sending = false;
+        self.review = false;
+        self.queue.clear();
+        self.in_flight = None;
+        let lowercase = error.to_lowercase();
+        let field = self.fields.iter().position(|f| {
+            lowercase.contains(&f.name.to_lowercase())
+                || lowercase.contains(&f.label.to_lowercase())
+        });
+        let error = if applied > 0 {
+            tr_args(Message::AppliedBeforeFailure, &[&applied, &error])
+        } else {
+            error
+        };
+        match field {
+            Some(i) => {
+                self.selected = i;
+                self.fields[i].error = Some(error);
+            }
+            None => self.error = Some(error),
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % (self.fields.len() + 1);
+    }
+
+    pub fn back(&mut self) {
+        self.selected = (self.selected + self.fields.len()) % (self.fields.len() + 1);
+    }
+
+    pub fn is_submit_selected(&self) -> bool {
+        self.selected == self.fields.len()
+    }
+
+    pub fn current(&self) -> Option<&FormField> {
+        self.fields.get(self.selected)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut FormField> {
+        self.fields.get_mut(self.selected)
+    }
+
+    /// Text put in the input buffer when editing starts, a list is edited one new value at a time
+    pub fn edit_value(&self) -> String {
+        match self.current() {
+            Some(field) if field.kind != FieldKind::List => field.value.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// Store the edited input in the selected field
+    pub fn commit(&mut self, input: &str) {
+        self.review = false;
+        if let Some(field) = self.current_mut() {
+            let input = input.trim();
+            match field.kind {
+                FieldKind::List if !input.is_empty() => {
+                    field.items.push(input.to_owned());
+                    field.item = field.items.len() - 1;
+                }
+                FieldKind::List => {}
+                _ => field.value = input.to_owned(),
+            }
+            field.validate();
+        }
+    }
+
+    /// Toggle a boolean, cycle a choice or select another value of a list
+    pub fn cycle(&mut self, forward: bool) {
+        self.review = false;
+        if let Some(field) = self.current_mut() {
+            match field.kind {
+                FieldKind::Boolean => {
+                    field.value = (field.value != "true").to_string();
+                }
+                FieldKind::Choice(choices) => {
+                    let i = choices.iter().position(|c| *c == field.value).unwrap_or(0);
+                    let i = if forward {
+                        (i + 1) % choices.len()
+                    } else {
+                        (i + choices.len() - 1) % choices.len()
+                    };
+                    field.value = choices[i].to_owned();
+                }
+                FieldKind::List if !field.items.is_empty() => {
+                    let len = field.items.len();
+                    field.item = if forward {
+                        (field.item + 1) % len
+                    } else {
+                        (field.item + len - 1) % len
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Remove the selected value of a list, or clear the field
+    pub fn remove(&mut self) {
+        self.review = false;
+        if let Some(field) = self.current_mut() {
+            match field.kind {
+                FieldKind::List if field.item < field.items.len() => {
+                    field.items.remove(field.item);
+                    field.item = field.item.min(field.items.len().saturating_sub(1));
+                }
+                FieldKind::Text | FieldKind::Secret | FieldKind::Integer | FieldKind::File => {
+                    field.value.clear()
+                }
+                _ => {}
+            }
+            field.validate();
+        }
+    }
+
+    pub fn open_picker(&mut self) {
+        if let Some(field) = self.current() {
+            self.picker = Some(FilePicker::new(Path::new(&field.value)));
+        }
+    }
+
+    /// Use the file selected in the picker, once a file is chosen the picker is closed
+    pub fn pick(&mut self) {
+        if let Some(path) = self.picker.as_mut().and_then(FilePicker::open) {
+            self.picker = None;
+            self.commit(&path.display().to_string());
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name && !f.value.is_empty())
+            .map(|f| f.value.as_str())
+    }
+
+    /// Events creating or updating the entity, every field is checked first
+    ///
+    /// Entities without update, or whose key changed, are deleted and created again
+    pub fn submit(&mut self) -> Result<Vec<IoEvent>, String> {
+        let invalid = self
+            .fields
+            .iter_mut()
+            .map(FormField::validate)
+            .filter(|valid| !valid)
+            .count();
+        let res = if invalid > 0 {
+            Err(tr_args(Message::FieldsToFix, &[&invalid]))
+        } else {
+            use IoEvent::*;
+            match self.tab {
+                // Certificates are not editable, their form only imports
+                TabId::CERTIFICATE => self.certificate().map(|e| vec![PostCertificate(e)]),
+                TabId::SAG => self
+                    .entity()
+                    .and_then(|e| self.operations(e, PostSag, Some(UpdateSag), DeleteSag)),
+                TabId::BUSINESSAPPLICATION => self.entity().and_then(|e| {
+                    self.operations(
+                        e,
+                        PostBusinessApplication,
+                        Some(UpdateBusinessApplication),
+                        DeleteBusinessApplication,
+                    )
+                }),
+                TabId::PROFILE => self.entity().and_then(|e| {
+                    self.operations(e, PostProfile, Some(UpdateProfile), DeleteProfile)
+                }),
+                TabId::APIPROXY => self.entity().and_then(|e| {
+                    self.operations(
+                        e,
+                        PostApiGatewayInfoEntity,
+                        None,
+                        DeleteApiGatewayInfoEntity,
+                    )
+                }),
+                TabId::FORWARDPROXY => self.entity().and_then(|e| {
+                    self.operations(
+                        e,
+                        PostForwardProxyEntity,
+                        Some(UpdateForwardProxyEntity),
+                        DeleteForwardProxyEntity,
+                    )
+                }),
+                TabId::APICLIENTCREDENTIAL => self.entity().and_then(|e| {
+                    self.operations(
+                        e,
+                        PostApiClientCredential,
+                        Some(UpdateApiClientCredential),
+                        DeleteApiClientCredential,
+                    )
+                }),
+            }
+        };
+        self.error = res.as_ref().err().cloned();
+        res
+    }
+
+    fn operations<E: DeserializeOwned>(
+        &self,
+        entity: E,
+        post: fn(E) -> IoEvent,
+        update: Option<fn(E) -> IoEvent>,
+        delete: fn(E) -> IoEvent,
+    ) -> Result<Vec<IoEvent>, String> {
+        let Some(original) = &self.original else {
+            return Ok(vec![post(entity)]);
+        };
+        let values = self.values()?;
+        let key_changed = self
+            .tab
+            .key_fields()
+            .iter()
+            .any(|key| original.get(*key) != values.get(*key));
+        let old = serde_json::from_value::<E>(original.clone()).map_err(|e| e.to_string())?;
+        Ok(match update {
+            Some(update) if !key_changed => vec![update(entity)],
+            // The old entity is only removed once the new one exists
+            _ if key_changed => vec![post(entity), delete(old)],
+            _ => vec![delete(old), post(entity)],
+        })
+    }
+
+    /// JSON fields of the form, empty optional fields are left out
+    fn values(&self) -> Result<Map<String, Value>, String> {
+        let mut fields = Map::new();
+        for field in self.fields.iter() {
+            let value = match field.kind {
+                FieldKind::List if field.items.is_empty() && !field.required => continue,
+                FieldKind::List => Value::from(field.items.clone()),
+                _ if field.value.is_empty() => continue,
+                FieldKind::Integer => Value::from(
+                    field
+                        .value
+                        .parse::<i64>()
+                        .map_err(|_| tr_args(Message::NotANumber, &[&field.value]))?,
+                ),
+                FieldKind::Boolean => Value::from(field.value == "true"),
+                _ => Value::from(field.value.clone()),
+            };
+            fields.insert(field.name.to_owned(), value);
+        }
+        Ok(fields)
+    }
+
+    fn entity<E: DeserializeOwned>(&self) -> Result<E, String> {
+        serde_json::from_value::<E>(Value::Object(self.values()?)).map_err(|e| e.to_string())
+    }
+
+    /// Certificate files are read and checked like `mgwc cert import` does
+    fn certificate(&self) -> Result<CertificateEntity, String> {
+        CertificateSource {
+            alias: self.value("alias").unwrap_or_default().to_owned(),
+            path: PathBuf::from(self.value("certificate").unwrap_or_default()),
+            private_key: self.value("privateKey").map(PathBuf::from),
+            password: self.value("password").map(str::to_owned),
+            force: self.value("force") == Some("true"),
+        }
+        .load()
+        .map_err(|e| e.to_string())
+    }
+}
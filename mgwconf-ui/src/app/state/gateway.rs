@@ -0,0 +1,80 @@
+use std::{fmt, net::SocketAddr, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use mgwconf_network::event::IoEvent;
+use mgwconf_vault::{SecretBackend, SecretsVault};
+use tokio::sync::mpsc::Sender;
+
+use super::configuration::ConfigurationState;
+use crate::i18n::{tr_args, Message};
+
+/// Health of a gateway, from its last ping
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Health {
+    #[default]
+    Unknown,
+    /// HTTP status of the health route
+    Up(u16),
+    Down(String),
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Health::Unknown => write!(f, "?"),
+            Health::Up(status) => write!(f, "{}", tr_args(Message::HealthUp, &[status])),
+            Health::Down(error) => write!(f, "{}", tr_args(Message::HealthDown, &[error])),
+        }
+    }
+}
+
+/// A gateway the UI can switch to, with its own network thread
+#[derive(Debug)]
+pub struct Gateway {
+    /// Context name, or address when it is given on the command line
+    pub name: String,
+    pub addr: SocketAddr,
+    pub health: Health,
+    /// Deletions are confirmed by typing `LIVE_CONFIRMATION`, whatever the entity
+    pub live: bool,
+    /// API keys of the gateway, read from the namespace of its context
+    pub vault: SecretsVault,
+    io_tx: Sender<IoEvent>,
+    /// Entities loaded from the gateway, swapped with the state of the app while it is active
+    pub state: ConfigurationState,
+}
+
+impl Gateway {
+    pub fn new(
+        name: String,
+        addr: SocketAddr,
+        live: bool,
+        backend: Arc<dyn SecretBackend>,
+        io_tx: Sender<IoEvent>,
+    ) -> Gateway {
+        Gateway {
+            name,
+            addr,
+            health: Health::default(),
+            live,
+            vault: SecretsVault::new(backend),
+            io_tx,
+            state: ConfigurationState::default(),
+        }
+    }
+
+    /// Fails when the network thread of the gateway has stopped
+    pub async fn dispatch(&self, event: IoEvent) -> Result<()> {
+        self.io_tx
+            .send(event)
+            .await
+            .map_err(|e| anyhow!(tr_args(Message::CannotSend, &[&self.name, &e])))
+    }
+
+    /// Queue an event without waiting, for the synchronous handlers
+    pub fn try_dispatch(&self, event: IoEvent) -> Result<()> {
+        self.io_tx
+            .try_send(event)
+            .map_err(|e| anyhow!(tr_args(Message::CannotSend, &[&self.name, &e])))
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    cmp::Ordering,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+use serde_json::Value;
+
+use super::TabId;
+use crate::i18n::{tr, Message};
+
+/// Sort column of certificates by expiry date, it is not an entity field
+pub const EXPIRY: &str = "expiry";
+
+/// Certificates expiring within this number of days are expiring soon
+const EXPIRING_SOON_DAYS: i64 = 30;
+
+/// Filters toggled with the number keys, shown as chips above the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chip {
+    Active,
+    Inactive,
+    ExpiringSoon,
+    Expired,
+    Live,
+}
+
+impl Chip {
+    /// Chips offered on a tab, in number key order
+    pub fn for_tab(tab: TabId) -> &'static [Chip] {
+        match tab {
+            TabId::SAG => &[Chip::Active, Chip::Inactive],
+            TabId::CERTIFICATE => &[Chip::ExpiringSoon, Chip::Expired],
+            TabId::APIPROXY => &[Chip::Live],
+            _ => &[],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Chip::Active => tr(Message::ChipActive),
+            Chip::Inactive => tr(Message::ChipInactive),
+            Chip::ExpiringSoon => tr(Message::ChipExpiringSoon),
+            Chip::Expired => tr(Message::ChipExpired),
+            // Environment name, as typed in the forms
+            Chip::Live => "LIVE",
+        }
+    }
+
+    fn matches(&self, row: &Row) -> bool {
+        match self {
+            Chip::Active => row.fields.get("active").and_then(Value::as_bool) == Some(true),
+            Chip::Inactive => row.fields.get("active").and_then(Value::as_bool) != Some(true),
+            Chip::ExpiringSoon => row
+                .days_left()
+                .is_some_and(|days| (0..=EXPIRING_SOON_DAYS).contains(&days)),
+            Chip::Expired => row.days_left().is_some_and(|days| days < 0),
+            Chip::Live => row.fields.get("environment").and_then(Value::as_str) == Some("LIVE"),
+        }
+    }
+}
+
+/// Certificate fields shown in the list, parsed once when the list is refreshed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateSummary {
+    pub subject: String,
+    pub not_after: String,
+    /// Days before the certificate expires, negative once expired
+    pub days_left: i64,
+}
+
+/// Entity of a list, as seen by the search, the chips and the sort
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub fields: Value,
+    /// Parsed certificate of the certificates tab
+    pub certificate: Option<CertificateSummary>,
+}
+
+impl Row {
+    fn days_left(&self) -> Option<i64> {
+        self.certificate.as_ref().map(|c| c.days_left)
+    }
+}
+
+/// Search, filter chips and sort column of the selected tab list
+#[derive(Debug, Default)]
+pub struct ListView {
+    /// Case insensitive text searched in the name fields and DNs
+    pub search: String,
+    /// The search is being typed in the input buffer
+    pub searching: bool,
+    pub chips: Vec<Chip>,
+    /// Index in `TabId::columns`
+    pub column: usize,
+    pub descending: bool,
+    /// Indexes of the shown entities, in the entity vector of the tab
+    rows: Vec<usize>,
+    /// Every entity of the tab, as given to the last refresh
+    entities: Vec<Row>,
+    /// First row in the viewport, updated when the table is drawn
+    offset: AtomicUsize,
+    /// Rows fitting in the viewport, the PgUp and PgDn step
+    page: AtomicUsize,
+}
+
+impl ListView {
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// Shown entities, in display order
+    pub fn visible_rows(&self) -> Vec<&Row> {
+        self.rows
+            .iter()
+            .filter_map(|i| self.entities.get(*i))
+            .collect()
+    }
+
+    /// First row to draw in a viewport of `height` rows, the selected row stays visible
+    pub fn scroll(&self, selected: usize, height: usize, total: usize) -> usize {
+        let height = height.max(1);
+        let mut offset = self.offset.load(Relaxed).min(total.saturating_sub(height));
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+        self.offset.store(offset, Relaxed);
+        self.page.store(height, Relaxed);
+        offset
+    }
+
+    pub fn page(&self) -> usize {
+        self.page.load(Relaxed).max(1)
+    }
+
+    pub fn toggle_chip(&mut self, chip: Chip) {
+        match self.chips.iter().position(|c| *c == chip) {
+            Some(i) => {
+                self.chips.remove(i);
+            }
+            None => self.chips.push(chip),
+        }
+    }
+
+    /// Sort by the next column of the tab, back to the first one after the last
+    pub fn next_column(&mut self, tab: TabId) {
+        self.column = (self.column + 1) % tab.columns().len();
+    }
+
+    pub fn column_name(&self, tab: TabId) -> &'static str {
+        tab.columns().get(self.column).copied().unwrap_or_default()
+    }
+
+    /// Keep the entities matching the search and every chip, sorted by the column
+    pub fn refresh(&mut self, tab: TabId, entities: Vec<Row>) {
+        let search = self.search.to_lowercase();
+        let mut rows = entities
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| search.is_empty() || Self::found(tab, row, &search))
+            .filter(|(_, row)| self.chips.iter().all(|chip| chip.matches(row)))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        let column = self.column_name(tab);
+        rows.sort_by(|a, b| {
+            let ordering = Self::compare(column, &entities[*a], &entities[*b]);
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        self.rows = rows;
+        self.entities = entities;
+    }
+
+    fn found(tab: TabId, row: &Row, search: &str) -> bool {
+        tab.search_fields()
+            .iter()
+            .filter_map(|field| row.fields.get(*field))
+            .any(|value| match value {
+                Value::String(s) => s.to_lowercase().contains(search),
+                Value::Array(items) => items.iter().any(|item| {
+                    item.as_str()
+                        .is_some_and(|s| s.to_lowercase().contains(search))
+                }),
+                value => value.to_string().contains(search),
+            })
+    }
+
+    /// Numbers are compared as numbers, missing values come last
+    fn compare(column: &str, a: &Row, b: &Row) -> Ordering {
+        if column == EXPIRY {
+            return a
+                .days_left()
+                .unwrap_or(i64::MAX)
+                .cmp(&b.days_left().unwrap_or(i64::MAX));
+        }
+        let (a, b) = (
+            Self::key(a.fields.get(column)),
+            Self::key(b.fields.get(column)),
+        );
+        a.0.cmp(&b.0)
+            .then(a.1.total_cmp(&b.1))
+            .then_with(|| a.2.cmp(&b.2))
+    }
+
+    /// Missing flag, false first, then number and lowercase text of a sorted value
+    fn key(value: Option<&Value>) -> (bool, f64, String) {
+        match value {
+            None | Some(Value::Null) => (true, 0.0, String::new()),
+            Some(Value::Number(n)) => (false, n.as_f64().unwrap_or_default(), String::new()),
+            Some(Value::String(s)) => (false, 0.0, s.to_lowercase()),
+            Some(value) => (false, 0.0, value.to_string()),
+        }
+    }
+}
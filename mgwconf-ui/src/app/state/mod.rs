@@ -1,9 +1,20 @@
 use std::fmt::{self};
 
-use mgwconf_network::mgw_configuration::InnerEntityTrait;
+use mgwconf_network::{event::IoEvent, mgw_configuration::InnerEntityTrait};
+use serde_json::Value;
 
+use crate::i18n::{tr, Message};
+
+pub mod change;
+pub mod compare;
 pub mod configuration;
+pub mod delete;
+pub mod form;
+pub mod gateway;
+pub mod list;
+pub mod notification;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TabId {
     CERTIFICATE = 0,
     SAG = 1,
@@ -11,6 +22,7 @@ pub enum TabId {
     PROFILE = 3,
     APIPROXY = 4,
     FORWARDPROXY = 5,
+    APICLIENTCREDENTIAL = 6,
 }
 
 impl From<usize> for TabId {
@@ -22,22 +34,115 @@ impl From<usize> for TabId {
             x if x == TabId::PROFILE as usize => TabId::PROFILE,
             x if x == TabId::APIPROXY as usize => TabId::APIPROXY,
             x if x == TabId::FORWARDPROXY as usize => TabId::FORWARDPROXY,
+            x if x == TabId::APICLIENTCREDENTIAL as usize => TabId::APICLIENTCREDENTIAL,
             _ => TabId::CERTIFICATE,
         }
     }
 }
 
-impl fmt::Display for TabId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl TabId {
+    /// Number of tabs
+    pub const COUNT: usize = 7;
+
+    /// JSON fields identifying an entity of the tab in the gateway API
+    pub fn key_fields(&self) -> &'static [&'static str] {
+        match self {
+            TabId::CERTIFICATE => &["alias"],
+            TabId::SAG | TabId::FORWARDPROXY => &["hostname", "port"],
+            TabId::BUSINESSAPPLICATION | TabId::APICLIENTCREDENTIAL => &["applicationName"],
+            TabId::PROFILE => &["applicationName", "profileName"],
+            TabId::APIPROXY => &["environment"],
+        }
+    }
+
+    /// Values of the key fields of an entity, joined with `:`
+    pub fn key(&self, entity: &Value) -> String {
+        self.key_fields()
+            .iter()
+            .map(|field| match entity.get(*field) {
+                Some(Value::String(s)) => s.to_owned(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(":")
+    }
+
+    /// JSON fields the list of the tab can be sorted by, see `list::EXPIRY`
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            TabId::CERTIFICATE => &["alias", list::EXPIRY],
+            TabId::SAG => &["hostname", "port", "messagePartnerName"],
+            TabId::BUSINESSAPPLICATION | TabId::APICLIENTCREDENTIAL => &["applicationName"],
+            TabId::PROFILE => &["profileName", "applicationName"],
+            TabId::APIPROXY => &["environment"],
+            TabId::FORWARDPROXY => &["hostname", "port"],
+        }
+    }
+
+    /// JSON fields searched with `/`, names and DNs
+    pub fn search_fields(&self) -> &'static [&'static str] {
         match self {
-            TabId::CERTIFICATE => write!(f, "Certificates"),
-            TabId::SAG => write!(f, "Sags"),
-            TabId::BUSINESSAPPLICATION => write!(f, "Business Applications"),
-            TabId::PROFILE => write!(f, "Profiles"),
-            TabId::APIPROXY => write!(f, "Api Proxy"),
-            TabId::FORWARDPROXY => write!(f, "Forward Proxy"),
+            TabId::CERTIFICATE => &["alias"],
+            TabId::SAG => &[
+                "hostname",
+                "messagePartnerName",
+                "userDNs",
+                "sslDN",
+                "publicCertificateAlias",
+            ],
+            TabId::BUSINESSAPPLICATION => &["applicationName"],
+            TabId::PROFILE => &[
+                "profileName",
+                "applicationName",
+                "userDNs",
+                "certificateAlias",
+            ],
+            TabId::APIPROXY => &["environment", "publicCertAlias"],
+            TabId::FORWARDPROXY => &["hostname", "user"],
+            TabId::APICLIENTCREDENTIAL => &["applicationName", "consumerKey"],
         }
     }
+
+    /// JSON fields holding secrets, the gateway may omit or mask them when listing
+    pub fn secret_fields(&self) -> &'static [&'static str] {
+        match self {
+            TabId::CERTIFICATE => &["privateKey"],
+            TabId::SAG => &["lauKey"],
+            TabId::BUSINESSAPPLICATION => &["sharedSecret"],
+            TabId::PROFILE | TabId::APIPROXY => &[],
+            TabId::FORWARDPROXY => &["password"],
+            TabId::APICLIENTCREDENTIAL => &["consumerSecret"],
+        }
+    }
+
+    /// Event loading every entity of the tab
+    pub fn get_all_event(&self) -> IoEvent {
+        match self {
+            TabId::CERTIFICATE => IoEvent::GetAllCertificates,
+            TabId::SAG => IoEvent::GetAllSags,
+            TabId::BUSINESSAPPLICATION => IoEvent::GetAllBusinessApplications,
+            TabId::PROFILE => IoEvent::GetAllProfiles,
+            TabId::APIPROXY => IoEvent::GetAllApiGatewayInfoEntity,
+            TabId::FORWARDPROXY => IoEvent::GetAllForwardProxyEntity,
+            TabId::APICLIENTCREDENTIAL => IoEvent::GetAllApiClientCredentials,
+        }
+    }
+}
+
+impl fmt::Display for TabId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            TabId::CERTIFICATE => Message::TabCertificates,
+            TabId::SAG => Message::TabSags,
+            TabId::BUSINESSAPPLICATION => Message::TabBusinessApplications,
+            TabId::PROFILE => Message::TabProfiles,
+            TabId::APIPROXY => Message::TabApiProxy,
+            TabId::FORWARDPROXY => Message::TabForwardProxy,
+            TabId::APICLIENTCREDENTIAL => Message::TabApiClientCredentials,
+        };
+        write!(f, "{}", tr(message))
+    }
 }
 
 pub trait State {
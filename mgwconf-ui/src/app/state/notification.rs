@@ -0,0 +1,98 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Oldest notifications are forgotten past this number
+const MAX_NOTIFICATIONS: usize = 200;
+
+/// A new notification is shown as a toast for this duration
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Success,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+    pub at: Instant,
+}
+
+impl Notification {
+    /// Time since the notification, as `12s` or `5m`
+    pub fn age(&self) -> String {
+        let secs = self.at.elapsed().as_secs();
+        match secs {
+            0..=59 => format!("{secs}s"),
+            60..=3599 => format!("{}m", secs / 60),
+            _ => format!("{}h", secs / 3600),
+        }
+    }
+}
+
+/// History of the notifications, newest first, and the selected one in its panel
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    pub selected: usize,
+}
+
+impl Notifications {
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        self.history.push_front(Notification {
+            level,
+            message: message.into(),
+            at: Instant::now(),
+        });
+        self.history.truncate(MAX_NOTIFICATIONS);
+        if self.selected > 0 {
+            // The selection stays on the same notification
+            self.selected = (self.selected + 1).min(self.history.len() - 1);
+        }
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Level::Info, message)
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Level::Success, message)
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Level::Error, message)
+    }
+
+    pub fn history(&self) -> &VecDeque<Notification> {
+        &self.history
+    }
+
+    /// Newest notification, while it is recent enough to be shown as a toast
+    pub fn toast(&self) -> Option<&Notification> {
+        self.history
+            .front()
+            .filter(|notification| notification.at.elapsed() < TOAST_DURATION)
+    }
+
+    pub fn next(&mut self) {
+        self.move_to(self.selected.saturating_add(1));
+    }
+
+    pub fn back(&mut self) {
+        self.move_to(self.selected.saturating_sub(1));
+    }
+
+    pub fn move_to(&mut self, index: usize) {
+        self.selected = index.min(self.history.len().saturating_sub(1));
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.selected = 0;
+    }
+}
@@ -0,0 +1,567 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::anyhow;
+use mgwconf_network::{
+    event::IoEvent,
+    mgw_configuration::{apis::ResponseContent, models::*},
+    AppTrait,
+};
+use mgwconf_vault::EnvBackend;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{channel, Receiver};
+
+use super::{
+    state::{
+        gateway::{Gateway, Health},
+        notification::{Level, Notification},
+        State, TabId,
+    },
+    ActiveBlock, RouteId, UiApp, UiAppTrait,
+};
+use crate::{
+    config::Config,
+    event::Key,
+    handler::handle_app,
+    i18n::{tr, tr_args, Message},
+};
+
+/// App connected to fake gateways, whose dispatched events are kept for the assertions
+struct Harness {
+    app: UiApp,
+    sent: Vec<Receiver<IoEvent>>,
+}
+
+impl Harness {
+    async fn new(gateways: usize) -> Harness {
+        let mut sent = Vec::new();
+        let gateways = (0..gateways)
+            .map(|i| {
+                let (io_tx, io_rx) = channel(100);
+                sent.push(io_rx);
+                Gateway::new(
+                    format!("gw{i}"),
+                    addr(i),
+                    false,
+                    Arc::new(EnvBackend),
+                    io_tx,
+                )
+            })
+            .collect();
+        let app = UiApp::new(gateways, Config::for_tests(addr(0))).await;
+        Harness { app, sent }
+    }
+
+    /// Response of the active gateway
+    fn respond(&mut self, event: IoEvent, entity: Option<Value>) {
+        self.app.handle_network_response(event, response(entity));
+    }
+
+    fn respond_from(&mut self, gateway: usize, event: IoEvent, entity: Option<Value>) {
+        self.app
+            .handle_gateway_response(addr(gateway), event, response(entity));
+    }
+
+    /// Events dispatched to a gateway since the last call, as debug strings
+    fn sent(&mut self, gateway: usize) -> Vec<String> {
+        let mut events = Vec::new();
+        while let Ok(event) = self.sent[gateway].try_recv() {
+            events.push(format!("{:?}", event));
+        }
+        events
+    }
+
+    fn last_notification(&self) -> Option<&Notification> {
+        self.app.get_notifications().history().front()
+    }
+}
+
+fn addr(i: usize) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 9003 + i as u16))
+}
+
+fn response(entity: Option<Value>) -> ResponseContent<Value> {
+    ResponseContent {
+        status: Default::default(),
+        content: String::new(),
+        entity,
+    }
+}
+
+fn debug(event: IoEvent) -> Vec<String> {
+    vec![format!("{:?}", event)]
+}
+
+/// A default entity of the tab, as the gateway would list it
+fn entity(tab: TabId) -> Value {
+    match tab {
+        TabId::CERTIFICATE => json!(CertificateEntity::default()),
+        TabId::SAG => json!(SagEntity::default()),
+        TabId::BUSINESSAPPLICATION => json!(BusinessApplicationEntity::default()),
+        TabId::PROFILE => json!(ApplicationProfileEntity::default()),
+        TabId::APIPROXY => json!(ApiGatewayInfoEntity::default()),
+        TabId::FORWARDPROXY => json!(ForwardProxyEntity::default()),
+        TabId::APICLIENTCREDENTIAL => json!(ApiCredentialsEntity::default()),
+    }
+}
+
+/// Every create, update and delete event, with its tab
+fn changes() -> Vec<(IoEvent, TabId)> {
+    vec![
+        (
+            IoEvent::PostCertificate(Default::default()),
+            TabId::CERTIFICATE,
+        ),
+        (IoEvent::PostSag(Default::default()), TabId::SAG),
+        (
+            IoEvent::PostBusinessApplication(Default::default()),
+            TabId::BUSINESSAPPLICATION,
+        ),
+        (IoEvent::PostProfile(Default::default()), TabId::PROFILE),
+        (
+            IoEvent::PostApiGatewayInfoEntity(Default::default()),
+            TabId::APIPROXY,
+        ),
+        (
+            IoEvent::PostForwardProxyEntity(Default::default()),
+            TabId::FORWARDPROXY,
+        ),
+        (
+            IoEvent::PostApiClientCredential(Default::default()),
+            TabId::APICLIENTCREDENTIAL,
+        ),
+        (
+            IoEvent::UpdateCertificate(Default::default()),
+            TabId::CERTIFICATE,
+        ),
+        (IoEvent::UpdateSag(Default::default()), TabId::SAG),
+        (
+            IoEvent::UpdateBusinessApplication(Default::default()),
+            TabId::BUSINESSAPPLICATION,
+        ),
+        (IoEvent::UpdateProfile(Default::default()), TabId::PROFILE),
+        (
+            IoEvent::UpdateForwardProxyEntity(Default::default()),
+            TabId::FORWARDPROXY,
+        ),
+        (
+            IoEvent::UpdateApiClientCredential(Default::default()),
+            TabId::APICLIENTCREDENTIAL,
+        ),
+        (
+            IoEvent::DeleteCertificate(Default::default()),
+            TabId::CERTIFICATE,
+        ),
+        (IoEvent::DeleteSag(Default::default()), TabId::SAG),
+        (
+            IoEvent::DeleteBusinessApplication(Default::default()),
+            TabId::BUSINESSAPPLICATION,
+        ),
+        (IoEvent::DeleteProfile(Default::default()), TabId::PROFILE),
+        (
+            IoEvent::DeleteApiGatewayInfoEntity(Default::default()),
+            TabId::APIPROXY,
+        ),
+        (
+            IoEvent::DeleteForwardProxyEntity(Default::default()),
+            TabId::FORWARDPROXY,
+        ),
+        (
+            IoEvent::DeleteApiClientCredential(Default::default()),
+            TabId::APICLIENTCREDENTIAL,
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn ping_response_is_ignored() {
+    let mut harness = Harness::new(1).await;
+    harness.respond(IoEvent::Ping, None);
+    assert!(harness.last_notification().is_none());
+    assert!(harness.sent(0).is_empty());
+}
+
+#[tokio::test]
+async fn lists_are_loaded_for_every_tab() {
+    let mut harness = Harness::new(1).await;
+    for tab in (0..TabId::COUNT).map(TabId::from) {
+        harness.respond(tab.get_all_event(), Some(json!([entity(tab)])));
+        assert_eq!(
+            harness.app.get_configuration_state().entities(tab).len(),
+            1,
+            "{tab}"
+        );
+    }
+    assert!(harness.last_notification().is_none());
+}
+
+#[tokio::test]
+async fn empty_list_response_keeps_the_entities() {
+    let mut harness = Harness::new(1).await;
+    harness.respond(IoEvent::GetAllSags, Some(json!([entity(TabId::SAG)])));
+    harness.respond(IoEvent::GetAllSags, None);
+    assert_eq!(harness.app.get_configuration_state().sags.len(), 1);
+}
+
+#[tokio::test]
+async fn unreadable_list_is_notified() {
+    let mut harness = Harness::new(1).await;
+    harness.respond(IoEvent::GetAllSags, Some(json!([entity(TabId::SAG)])));
+    harness.respond(IoEvent::GetAllSags, Some(json!({ "hostname": 42 })));
+    let notification = harness.last_notification().unwrap();
+    assert_eq!(notification.level, Level::Error);
+    assert!(notification.message.contains("GetAllSags"));
+    assert_eq!(harness.app.get_configuration_state().sags.len(), 1);
+    assert_eq!(harness.app.get_current_route().id, RouteId::Home);
+}
+
+#[tokio::test]
+async fn single_entity_responses_are_ignored() {
+    let mut harness = Harness::new(1).await;
+    for event in [
+        IoEvent::GetSag("host".to_owned(), 48002),
+        IoEvent::GetCertificate("alias".to_owned()),
+        IoEvent::GetProfiles("app".to_owned(), None),
+    ] {
+        harness.respond(event, Some(json!({})));
+    }
+    assert!(harness.last_notification().is_none());
+    assert!(harness.sent(0).is_empty());
+}
+
+#[tokio::test]
+async fn changes_are_notified_and_their_list_reloaded() {
+    let mut harness = Harness::new(1).await;
+    for (event, tab) in changes() {
+        let name = format!("{:?}", event);
+        harness.respond(event, None);
+        let notification = harness.last_notification().unwrap();
+        assert_eq!(notification.level, Level::Success, "{name}");
+        assert!(notification.message.starts_with(&tab.to_string()), "{name}");
+        assert_eq!(harness.sent(0), debug(tab.get_all_event()), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn form_events_are_sent_in_turn() {
+    let mut harness = Harness::new(1).await;
+    let state = harness.app.get_configuration_state_mut();
+    state.open_form();
+    let first = state.form_mut().unwrap().start(vec![
+        IoEvent::PostCertificate(Default::default()),
+        IoEvent::DeleteCertificate(Default::default()),
+    ]);
+
+    harness.respond(first.unwrap(), None);
+    assert_eq!(
+        harness.sent(0),
+        debug(IoEvent::DeleteCertificate(Default::default()))
+    );
+    assert!(harness.app.get_configuration_state().form().is_some());
+    assert!(harness.last_notification().is_none());
+
+    harness.respond(IoEvent::DeleteCertificate(Default::default()), None);
+    assert_eq!(harness.sent(0), debug(IoEvent::GetAllCertificates));
+    assert!(harness.app.get_configuration_state().form().is_none());
+    assert_eq!(
+        harness.last_notification().unwrap().message,
+        "Certificates saved"
+    );
+}
+
+#[tokio::test]
+async fn network_error_is_shown_in_the_sending_form() {
+    let mut harness = Harness::new(1).await;
+    let state = harness.app.get_configuration_state_mut();
+    state.open_form();
+    let event = state
+        .form_mut()
+        .unwrap()
+        .start(vec![IoEvent::PostCertificate(Default::default())])
+        .unwrap();
+
+    harness
+        .app
+        .handle_event_error(addr(0), &event, anyhow!("refused"));
+    let form = harness.app.get_configuration_state().form().unwrap();
+    assert!(!form.sending);
+    assert!(form.error.as_ref().unwrap().contains("refused"));
+    assert!(harness.last_notification().is_none());
+}
+
+#[tokio::test]
+async fn unrelated_error_is_not_shown_in_the_sending_form() {
+    let mut harness = Harness::new(1).await;
+    let state = harness.app.get_configuration_state_mut();
+    state.open_form();
+    state
+        .form_mut()
+        .unwrap()
+        .start(vec![IoEvent::PostCertificate(Default::default())]);
+
+    harness
+        .app
+        .handle_event_error(addr(0), &IoEvent::GetAllSags, anyhow!("refused"));
+    harness
+        .app
+        .handle_gateway_ping(addr(0), Err(anyhow!("unreachable")));
+    let form = harness.app.get_configuration_state().form().unwrap();
+    assert!(form.sending);
+    assert!(form.error.is_none());
+    let notifications = harness.app.get_notifications().history();
+    for message in ["refused", "unreachable"] {
+        assert!(
+            notifications.iter().any(|n| n.message.contains(message)),
+            "{message}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn network_error_is_notified_in_place() {
+    let mut harness = Harness::new(1).await;
+    harness
+        .app
+        .push_navigation_stack(RouteId::Configuration, ActiveBlock::Tab);
+    harness.app.handle_network_error(anyhow!("refused"));
+    let notification = harness.last_notification().unwrap();
+    assert_eq!(notification.level, Level::Error);
+    assert_eq!(notification.message, "refused");
+    let route = harness.app.get_current_route();
+    assert_eq!(route.id, RouteId::Configuration);
+    assert_eq!(route.active_block, ActiveBlock::Tab);
+}
+
+#[tokio::test]
+async fn inactive_gateway_lists_are_cached() {
+    let mut harness = Harness::new(2).await;
+    harness.respond_from(1, IoEvent::GetAllSags, Some(json!([entity(TabId::SAG)])));
+    assert!(harness.app.get_configuration_state().sags.is_empty());
+    assert_eq!(harness.app.gateways()[1].state.sags.len(), 1);
+
+    harness.app.switch_gateway(1);
+    assert_eq!(harness.app.get_configuration_state().sags.len(), 1);
+    assert_eq!(harness.sent(1), debug(IoEvent::Ping));
+}
+
+#[tokio::test]
+async fn inactive_gateway_error_is_notified() {
+    let mut harness = Harness::new(2).await;
+    harness.app.set_connected(true);
+    harness
+        .app
+        .handle_gateway_error(addr(1), anyhow!("timed out"));
+    assert!(matches!(harness.app.gateways()[1].health, Health::Down(_)));
+    assert_eq!(harness.last_notification().unwrap().level, Level::Error);
+    assert!(harness.app.is_connected());
+}
+
+#[tokio::test]
+async fn health_changes_are_notified() {
+    let mut harness = Harness::new(2).await;
+    harness
+        .app
+        .handle_gateway_ping(addr(1), Err(anyhow!("refused")));
+    assert_eq!(harness.last_notification().unwrap().level, Level::Error);
+
+    harness.app.handle_gateway_ping(addr(1), Ok(200));
+    assert_eq!(harness.app.gateways()[1].health, Health::Up(200));
+    assert_eq!(harness.last_notification().unwrap().level, Level::Success);
+
+    harness.app.handle_gateway_ping(addr(1), Ok(200));
+    assert_eq!(harness.app.get_notifications().history().len(), 2);
+}
+
+#[tokio::test]
+async fn credential_is_updated_from_its_detail_view() {
+    let mut harness = Harness::new(1).await;
+    harness.respond(
+        IoEvent::GetAllApiClientCredentials,
+        Some(json!([{
+            "applicationName": "app",
+            "consumerKey": "key",
+            "consumerSecret": "old",
+        }])),
+    );
+    let state = harness.app.get_configuration_state_mut();
+    while state.current_tab() != TabId::APICLIENTCREDENTIAL as usize {
+        state.next();
+    }
+    state.select_current();
+    state.next();
+    state.open_edit_form();
+    let form = state.form_mut().unwrap();
+    form.selected = form
+        .fields
+        .iter()
+        .position(|field| field.name == "consumerSecret")
+        .unwrap();
+    form.commit("new");
+
+    let events = form.submit().unwrap();
+    assert!(
+        matches!(
+            &events[..],
+            [IoEvent::UpdateApiClientCredential(credential)]
+                if credential.consumer_key == "key" && credential.consumer_secret == "new"
+        ),
+        "{events:?}"
+    );
+}
+
+#[tokio::test]
+async fn certificate_is_not_editable() {
+    let mut harness = Harness::new(1).await;
+    harness.respond(
+        IoEvent::GetAllCertificates,
+        Some(json!([entity(TabId::CERTIFICATE)])),
+    );
+    let state = harness.app.get_configuration_state_mut();
+    state.select_current();
+    state.next();
+    harness
+        .app
+        .push_navigation_stack(RouteId::Configuration, ActiveBlock::Detailed);
+
+    handle_app(Key::Char('e'), &mut harness.app).await;
+    assert!(harness.app.get_configuration_state().form().is_none());
+    assert_eq!(
+        harness.app.get_current_route().active_block,
+        ActiveBlock::Detailed
+    );
+    assert_eq!(
+        harness.last_notification().unwrap().message,
+        tr(Message::CertificateNotEditable)
+    );
+}
+
+#[tokio::test]
+async fn sag_delete_on_a_live_gateway_is_typed() {
+    let mut harness = Harness::new(1).await;
+    harness.app.gateways[0].live = true;
+    harness.respond(IoEvent::GetAllSags, Some(json!([entity(TabId::SAG)])));
+    let state = harness.app.get_configuration_state_mut();
+    while state.current_tab() != TabId::SAG as usize {
+        state.next();
+    }
+    state.select_current();
+    state.next();
+    harness
+        .app
+        .push_navigation_stack(RouteId::Configuration, ActiveBlock::TabSelected);
+
+    handle_app(Key::Delete, &mut harness.app).await;
+    assert!(
+        harness
+            .app
+            .get_configuration_state()
+            .deletion()
+            .unwrap()
+            .live
+    );
+    handle_app(Key::Char('y'), &mut harness.app).await;
+    assert!(harness.sent(0).is_empty());
+
+    handle_app(Key::Backspace, &mut harness.app).await;
+    for c in "LIVE".chars() {
+        handle_app(Key::Char(c), &mut harness.app).await;
+    }
+    handle_app(Key::Enter, &mut harness.app).await;
+    assert_eq!(
+        harness.sent(0),
+        debug(IoEvent::DeleteSag(
+            serde_json::from_value(entity(TabId::SAG)).unwrap()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn delete_without_its_secrets_cannot_be_undone() {
+    let mut harness = Harness::new(1).await;
+    let mut keyed = entity(TabId::SAG);
+    keyed["hostname"] = json!("keyed");
+    keyed["lauKey"] = json!("0123456789abcdef");
+    harness.respond(
+        IoEvent::GetAllSags,
+        Some(json!([entity(TabId::SAG), keyed.clone()])),
+    );
+    let state = harness.app.get_configuration_state_mut();
+    while state.current_tab() != TabId::SAG as usize {
+        state.next();
+    }
+    state.select_current();
+    state.next();
+    harness
+        .app
+        .push_navigation_stack(RouteId::Configuration, ActiveBlock::TabSelected);
+
+    handle_app(Key::Delete, &mut harness.app).await;
+    let deletion = harness.app.get_configuration_state().deletion().unwrap();
+    assert_eq!(deletion.secrets_missing, ["lauKey"]);
+    handle_app(Key::Char('y'), &mut harness.app).await;
+    assert!(harness
+        .app
+        .get_configuration_state()
+        .last_deleted()
+        .is_none());
+    harness.sent(0);
+
+    harness.app.get_configuration_state_mut().next();
+    handle_app(Key::Delete, &mut harness.app).await;
+    assert!(harness
+        .app
+        .get_configuration_state()
+        .deletion()
+        .unwrap()
+        .is_undoable());
+    handle_app(Key::Char('y'), &mut harness.app).await;
+    harness.sent(0);
+    handle_app(Key::Char('u'), &mut harness.app).await;
+    assert_eq!(
+        harness.sent(0),
+        debug(IoEvent::PostSag(serde_json::from_value(keyed).unwrap()))
+    );
+}
+
+#[tokio::test]
+async fn api_keys_are_read_from_the_vault_of_each_gateway() {
+    let harness = Harness::new(2).await;
+    let app = &harness.app;
+    for i in 0..2 {
+        assert!(std::ptr::eq(
+            app.gateway_vault(addr(i)).unwrap(),
+            &app.gateways[i].vault
+        ));
+    }
+    assert!(app.gateway_vault(addr(2)).is_none());
+}
+
+#[tokio::test]
+async fn stopped_network_thread_is_notified() {
+    let mut harness = Harness::new(1).await;
+    // The receiving end is dropped as when the network thread of the gateway exits
+    harness.sent.clear();
+    let message = tr_args(Message::CannotSend, &[&"gw0", &"channel closed"]);
+    let cannot_send = |notification: Option<&Notification>| {
+        notification.is_some_and(|n| n.level == Level::Error && n.message == message)
+    };
+
+    harness.app.set_connected(false);
+    handle_app(Key::Enter, &mut harness.app).await;
+    assert!(
+        cannot_send(harness.last_notification()),
+        "{:?}",
+        harness.last_notification()
+    );
+
+    harness.app.get_notifications_mut().clear();
+    harness
+        .app
+        .push_navigation_stack(RouteId::Configuration, ActiveBlock::Tab);
+    handle_app(Key::Enter, &mut harness.app).await;
+    assert!(
+        cannot_send(harness.last_notification()),
+        "{:?}",
+        harness.last_notification()
+    );
+    assert!(!harness.app.get_configuration_state().waiting_for_load());
+}
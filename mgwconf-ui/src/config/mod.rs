@@ -1,5 +1,9 @@
 use log::{debug, info};
-use mgwconf_network::{AppConfig, Identity};
+use mgwconf_network::{
+    context::{ConfigFile, GatewayContext},
+    AppConfig, Identity,
+};
+use mgwconf_vault::{SecretBackend, SecretSource, VaultLocation};
 use std::{
     any::Any,
     error::Error,
@@ -7,6 +11,8 @@ use std::{
     io::Read,
     net::{IpAddr, SocketAddr, ToSocketAddrs},
     path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 use tracing_subscriber::{
     prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter,
@@ -14,6 +20,8 @@ use tracing_subscriber::{
 
 use clap::{ArgMatches, Parser};
 
+use crate::{event::keymap::KeyMap, i18n::Locale};
+
 #[derive(Parser, Debug, Default, Clone)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
@@ -23,9 +31,27 @@ pub struct Args {
     /// create secret
     #[clap(long = "create_secret", action = clap::ArgAction::SetTrue, default_value = "false")]
     pub create_secret: bool,
-    /// pass vault key
-    #[clap(long = "key")]
+    /// pass vault key, prefer the MGWC_VAULT_KEY variable which is not shown by ps
+    #[clap(long = "key", env = "MGWC_VAULT_KEY", hide_env_values = true)]
     pub vault_key: Option<String>,
+    /// Where API keys are read from: prompt, store, env, keyring or command
+    #[clap(long = "secrets")]
+    pub secrets: Option<SecretSource>,
+    /// Command printing an API key, `{type}` and `{namespace}` are replaced
+    #[clap(long = "secret_command")]
+    pub secret_command: Option<String>,
+    /// Vault directory, `~/.local/share/mgwconf/vault` by default
+    #[clap(long = "vault_dir")]
+    pub vault_dir: Option<PathBuf>,
+    /// Vault namespace holding the API keys of the gateway, the context name by default
+    #[clap(long = "vault_namespace")]
+    pub vault_namespace: Option<String>,
+    /// Configuration file, `~/.config/mgwconf/config.toml` by default
+    #[clap(long = "config")]
+    pub config_file: Option<PathBuf>,
+    /// Gateway context of the configuration file, its current context by default
+    #[clap(long = "context")]
+    pub context: Option<String>,
     /// pass ca
     #[clap(long = "ca")]
     pub root_ca_path: Option<String>,
@@ -33,8 +59,23 @@ pub struct Args {
     pub remote_addr: Option<String>,
     #[clap(long = "identity")]
     pub identity: Option<String>,
+    /// Accept invalid gateway certificates
     #[clap(short = 'k', action = clap::ArgAction::SetTrue, default_value = "false")]
     pub unsecure: bool,
+    /// Verify gateway certificates even if the context is unsecure
+    #[clap(long = "secure", action = clap::ArgAction::SetTrue, default_value = "false", conflicts_with = "unsecure")]
+    pub secure: bool,
+}
+
+impl Args {
+    /// `-k` or `--secure` when given, the context decides otherwise
+    pub fn tls_unsecure(&self) -> Option<bool> {
+        match (self.unsecure, self.secure) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
 }
 
 impl From<ArgMatches> for Args {
@@ -58,15 +99,24 @@ pub struct Config {
     unsecure: bool,
 
     pub tick_rate: u64,
+    pub connect_timeout: Duration,
+    pub request_timeout: Option<Duration>,
+    pub vault_dir: Option<PathBuf>,
+    pub vault_namespace: Option<String>,
+    pub secrets: SecretSource,
+    pub secret_command: Option<String>,
+    pub keymap: KeyMap,
+    /// Deletions on the gateway are confirmed by typing LIVE
+    pub live: bool,
+    pub locale: Locale,
 }
 
 impl Config {
     pub fn init(args: &Args) -> Result<Config, Box<dyn Error>> {
-        let remote_addr = if let Some(ip) = &args.remote_addr {
-            ip.to_socket_addrs()
-                .expect("Unable to resolve domain")
-                .next()
-                .unwrap()
+        let config_file = ConfigFile::load(args.config_file.as_deref())?;
+        let context = config_file.context(args.context.as_deref())?;
+        let remote_addr = if let Some(ip) = args.remote_addr.as_ref().or(context.address.as_ref()) {
+            resolve(ip)?
         } else {
             "127.0.0.1:9003".parse().unwrap()
         };
@@ -74,27 +124,110 @@ impl Config {
             debug: args.debug,
             loaded: false,
             remote_addr,
-            identity: Self::read_pem(args)?,
+            identity: Self::read_pem(args, &context)?,
             root_ca_path: args
                 .root_ca_path
                 .clone()
+                .or_else(|| context.ca.as_ref().map(|ca| ca.display().to_string()))
                 .unwrap_or_else(|| "./CA.pem".to_owned()),
-            tick_rate: 160,
-            unsecure: args.unsecure,
+            tick_rate: context.tick_rate.unwrap_or(160),
+            connect_timeout: Duration::from_secs(context.connect_timeout.unwrap_or(15)),
+            request_timeout: context.request_timeout.map(Duration::from_secs),
+            vault_dir: args.vault_dir.clone().or_else(|| context.vault.clone()),
+            vault_namespace: args.vault_namespace.clone().or_else(|| {
+                config_file
+                    .context_name(args.context.as_deref())
+                    .map(str::to_owned)
+            }),
+            secrets: match (args.secrets, &context.secrets) {
+                (Some(secrets), _) => secrets,
+                (None, Some(secrets)) => secrets.parse()?,
+                (None, None) => SecretSource::default(),
+            },
+            secret_command: args
+                .secret_command
+                .clone()
+                .or_else(|| context.secret_command.clone()),
+            unsecure: args.tls_unsecure().unwrap_or(context.unsecure),
+            keymap: KeyMap::from_bindings(&config_file.keys)?,
+            live: context.live,
+            locale: match &config_file.locale {
+                Some(locale) => locale.parse()?,
+                None => Locale::from_env(),
+            },
         };
         info!("Config has been loadded successfully");
         debug!("Config values {:?}", config);
         Ok(config)
     }
 
-    fn read_pem(args: &Args) -> Result<Option<Identity>, std::io::Error> {
+    /// The gateway of `args` first, then every other context of the configuration file with
+    /// an address, named after their context. Each one reads its API keys from its own context
+    pub fn gateways(&self, args: &Args) -> Vec<(String, Config)> {
+        let config_file = ConfigFile::load(args.config_file.as_deref()).unwrap_or_default();
+        let current = match args.remote_addr {
+            Some(_) => None,
+            None => config_file.context_name(args.context.as_deref()),
+        };
+        let mut gateways = vec![(
+            current
+                .map(str::to_owned)
+                .unwrap_or_else(|| self.remote_addr.to_string()),
+            self.clone(),
+        )];
+        for (name, context) in &config_file.contexts {
+            let Some(address) = context.address.as_ref() else {
+                continue;
+            };
+            if Some(name.as_str()) == current {
+                continue;
+            }
+            match address
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+            {
+                // Already listed, as the gateway of the command line
+                Some(addr) if addr == self.remote_addr => continue,
+                Some(_) => {}
+                None => {
+                    log::error!("Gateway {} skipped, cannot resolve {}", name, address);
+                    continue;
+                }
+            }
+            // The namespace of the command line belongs to its gateway
+            let args = Args {
+                context: Some(name.clone()),
+                remote_addr: None,
+                vault_namespace: None,
+                ..args.clone()
+            };
+            match Config::init(&args) {
+                Ok(mut config) => {
+                    config.loaded = self.loaded;
+                    gateways.push((name.clone(), config));
+                }
+                Err(e) => log::error!("Gateway {} skipped : {}", name, e),
+            }
+        }
+        gateways
+    }
+
+    fn read_pem(args: &Args, context: &GatewayContext) -> Result<Option<Identity>, std::io::Error> {
+        let path = |default: &Option<PathBuf>, file: &str| {
+            args.identity
+                .as_ref()
+                .map(PathBuf::from)
+                .or_else(|| default.clone())
+                .unwrap_or_else(|| PathBuf::from(file))
+        };
         let mut buf_pub: Vec<u8> = Vec::new();
         let mut buf_priv: Vec<u8> = Vec::new();
-        match File::open(args.identity.as_ref().unwrap_or(&"./mgw.key".to_string())) {
+        match File::open(path(&context.identity_key, "./mgw.key")) {
             Ok(mut f) => f.read_to_end(&mut buf_priv)?,
             Err(_) => return Ok(None),
         };
-        match File::open(args.identity.as_ref().unwrap_or(&"./mgw.pub".to_string())) {
+        match File::open(path(&context.identity_cert, "./mgw.pub")) {
             Ok(mut f) => f.read_to_end(&mut buf_pub)?,
             Err(_) => return Ok(None),
         };
@@ -107,6 +240,19 @@ impl Config {
         }
     }
 
+    pub fn vault_location(&self) -> VaultLocation {
+        VaultLocation::new(self.vault_dir.clone(), self.vault_namespace.as_deref())
+    }
+
+    /// Backend of the API keys, `master` is only used by the store
+    pub fn secret_backend(&self, master: &str) -> anyhow::Result<Arc<dyn SecretBackend>> {
+        self.secrets.open(
+            master,
+            &self.vault_location(),
+            self.secret_command.as_deref(),
+        )
+    }
+
     #[allow(dead_code)]
     pub fn is_loaded(&self) -> bool {
         self.loaded
@@ -146,6 +292,31 @@ impl Config {
     }
 }
 
+#[cfg(test)]
+impl Config {
+    /// Gateway at `remote_addr`, without identity nor logging
+    pub(crate) fn for_tests(remote_addr: SocketAddr) -> Config {
+        Config {
+            debug: false,
+            loaded: true,
+            remote_addr,
+            identity: None,
+            root_ca_path: String::new(),
+            unsecure: false,
+            tick_rate: 160,
+            connect_timeout: Duration::from_secs(1),
+            request_timeout: None,
+            vault_dir: None,
+            vault_namespace: None,
+            secrets: SecretSource::default(),
+            secret_command: None,
+            keymap: KeyMap::default(),
+            live: false,
+            locale: Locale::En,
+        }
+    }
+}
+
 impl AppConfig for Config {
     fn remote_addr(&self) -> SocketAddr {
         self.remote_addr
@@ -167,6 +338,14 @@ impl AppConfig for Config {
         self.tick_rate
     }
 
+    fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -179,3 +358,12 @@ impl AppConfig for Config {
         self.unsecure
     }
 }
+
+/// First socket address of a `host:port` gateway address
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    address
+        .to_socket_addrs()
+        .map_err(|e| format!("Unable to resolve {address} : {e}"))?
+        .next()
+        .ok_or_else(|| format!("{address} does not resolve to any address"))
+}
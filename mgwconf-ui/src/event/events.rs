@@ -35,6 +35,7 @@ pub enum Event<I> {
 /// type is handled in its own thread and returned to a common `Receiver`
 pub struct Events {
     rx: mpsc::Receiver<Event<Key>>,
+    exit_key: Key,
     // Need to be kept around to prevent disposing the sender side.
     _tx: mpsc::Sender<Event<Key>>,
 }
@@ -67,7 +68,15 @@ impl Events {
             }
         });
 
-        Events { rx, _tx: tx }
+        Events {
+            rx,
+            exit_key: config.exit_key,
+            _tx: tx,
+        }
+    }
+
+    pub fn exit_key(&self) -> Key {
+        self.exit_key
     }
 }
 
@@ -1,5 +1,5 @@
 use crossterm::event;
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 /// Represents an key.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -120,6 +120,62 @@ impl fmt::Display for Key {
     }
 }
 
+/// Parse a key of the config file, as shown by `Display`: `j`, `<Ctrl+s>`, `<Down>`, `F1`...
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Key, Self::Err> {
+        let name = s
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .unwrap_or(s);
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+        let lower = name.to_lowercase();
+        let char_of = |rest: &str| {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ if rest.eq_ignore_ascii_case("space") => Ok(' '),
+                _ => Err(anyhow::anyhow!("Invalid key {}", s)),
+            }
+        };
+        if let Some(rest) = lower.strip_prefix("ctrl+") {
+            return char_of(&name[name.len() - rest.len()..]).map(Key::Ctrl);
+        }
+        if let Some(rest) = lower.strip_prefix("alt+") {
+            return char_of(&name[name.len() - rest.len()..]).map(Key::Alt);
+        }
+        if let Some(n) = lower
+            .strip_prefix('f')
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| *n <= 12)
+        {
+            return Ok(Key::from_f(n));
+        }
+        Ok(match lower.trim_end_matches(" arrow key") {
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "esc" => Key::Esc,
+            "space" => Key::Char(' '),
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "ins" | "insert" => Key::Ins,
+            "delete" | "del" => Key::Delete,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdn" => Key::PageDown,
+            _ => return Err(anyhow::anyhow!("Invalid key {}", s)),
+        })
+    }
+}
+
 impl From<event::KeyEvent> for Key {
     fn from(key_event: event::KeyEvent) -> Self {
         match key_event {
@@ -0,0 +1,333 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+use super::Key;
+use crate::i18n::{tr, Message};
+
+/// Where an action is available, the help overlay has a section for each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Context {
+    /// Every screen but the text inputs and the delete confirmation
+    Global,
+    /// Every list
+    Navigation,
+    Home,
+    List,
+    Detail,
+    Form,
+    Compare,
+    Notifications,
+}
+
+impl Context {
+    pub const ALL: [Context; 8] = [
+        Context::Global,
+        Context::Navigation,
+        Context::Home,
+        Context::List,
+        Context::Detail,
+        Context::Form,
+        Context::Compare,
+        Context::Notifications,
+    ];
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Context::Global => Message::ContextGlobal,
+            Context::Navigation => Message::ContextNavigation,
+            Context::Home => Message::ContextHome,
+            Context::List => Message::ContextList,
+            Context::Detail => Message::ContextDetail,
+            Context::Form => Message::ContextForm,
+            Context::Compare => Message::ContextCompare,
+            Context::Notifications => Message::ContextNotifications,
+        };
+        write!(f, "{}", tr(message))
+    }
+}
+
+/// Something done with a key, named in the `[keys]` table of the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Exit,
+    Back,
+    Help,
+    Notifications,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    Select,
+    Left,
+    Right,
+    SwitchPanel,
+    Compare,
+    Search,
+    NextSort,
+    ReverseSort,
+    Delete,
+    Undo,
+    Edit,
+    ToggleSecrets,
+    Submit,
+    Reload,
+    OnlyDifferences,
+    Clear,
+}
+
+impl Action {
+    pub const ALL: [Action; 26] = [
+        Action::Exit,
+        Action::Back,
+        Action::Help,
+        Action::Notifications,
+        Action::Down,
+        Action::Up,
+        Action::PageDown,
+        Action::PageUp,
+        Action::First,
+        Action::Last,
+        Action::Select,
+        Action::Left,
+        Action::Right,
+        Action::SwitchPanel,
+        Action::Compare,
+        Action::Search,
+        Action::NextSort,
+        Action::ReverseSort,
+        Action::Delete,
+        Action::Undo,
+        Action::Edit,
+        Action::ToggleSecrets,
+        Action::Submit,
+        Action::Reload,
+        Action::OnlyDifferences,
+        Action::Clear,
+    ];
+
+    /// Name in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Exit => "exit",
+            Action::Back => "back",
+            Action::Help => "help",
+            Action::Notifications => "notifications",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Select => "select",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::SwitchPanel => "switch-panel",
+            Action::Compare => "compare",
+            Action::Search => "search",
+            Action::NextSort => "next-sort",
+            Action::ReverseSort => "reverse-sort",
+            Action::Delete => "delete",
+            Action::Undo => "undo",
+            Action::Edit => "edit",
+            Action::ToggleSecrets => "toggle-secrets",
+            Action::Submit => "submit",
+            Action::Reload => "reload",
+            Action::OnlyDifferences => "only-differences",
+            Action::Clear => "clear",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        tr(match self {
+            Action::Exit => Message::ActionExit,
+            Action::Back => Message::ActionBack,
+            Action::Help => Message::ActionHelp,
+            Action::Notifications => Message::ActionNotifications,
+            Action::Down => Message::ActionDown,
+            Action::Up => Message::ActionUp,
+            Action::PageDown => Message::ActionPageDown,
+            Action::PageUp => Message::ActionPageUp,
+            Action::First => Message::ActionFirst,
+            Action::Last => Message::ActionLast,
+            Action::Select => Message::ActionSelect,
+            Action::Left => Message::ActionLeft,
+            Action::Right => Message::ActionRight,
+            Action::SwitchPanel => Message::ActionSwitchPanel,
+            Action::Compare => Message::ActionCompare,
+            Action::Search => Message::ActionSearch,
+            Action::NextSort => Message::ActionNextSort,
+            Action::ReverseSort => Message::ActionReverseSort,
+            Action::Delete => Message::ActionDelete,
+            Action::Undo => Message::ActionUndo,
+            Action::Edit => Message::ActionEdit,
+            Action::ToggleSecrets => Message::ActionToggleSecrets,
+            Action::Submit => Message::ActionSubmit,
+            Action::Reload => Message::ActionReload,
+            Action::OnlyDifferences => Message::ActionOnlyDifferences,
+            Action::Clear => Message::ActionClear,
+        })
+    }
+
+    pub fn context(&self) -> Context {
+        match self {
+            Action::Exit | Action::Back | Action::Help | Action::Notifications => Context::Global,
+            Action::Down
+            | Action::Up
+            | Action::PageDown
+            | Action::PageUp
+            | Action::First
+            | Action::Last
+            | Action::Select
+            | Action::Left
+            | Action::Right => Context::Navigation,
+            Action::Compare => Context::Home,
+            Action::SwitchPanel
+            | Action::Search
+            | Action::NextSort
+            | Action::ReverseSort
+            | Action::Delete
+            | Action::Undo => Context::List,
+            Action::Edit | Action::ToggleSecrets => Context::Detail,
+            Action::Submit => Context::Form,
+            Action::Reload | Action::OnlyDifferences => Context::Compare,
+            Action::Clear => Context::Notifications,
+        }
+    }
+
+    /// Keys of the action when the config file does not bind it, vim keys included
+    fn default_keys(&self) -> Vec<Key> {
+        match self {
+            Action::Exit => vec![Key::Ctrl('c')],
+            Action::Back => vec![Key::Esc],
+            Action::Help => vec![Key::Char('?')],
+            Action::Notifications => vec![Key::Char('N')],
+            Action::Down => vec![Key::Down, Key::Char('j')],
+            Action::Up => vec![Key::Up, Key::Char('k')],
+            Action::PageDown => vec![Key::PageDown],
+            Action::PageUp => vec![Key::PageUp],
+            Action::First => vec![Key::Home, Key::Char('g')],
+            Action::Last => vec![Key::End, Key::Char('G')],
+            Action::Select => vec![Key::Enter],
+            Action::Left => vec![Key::Left],
+            Action::Right => vec![Key::Right],
+            Action::SwitchPanel => vec![Key::Tab],
+            Action::Compare => vec![Key::Char('c')],
+            Action::Search => vec![Key::Char('/')],
+            Action::NextSort => vec![Key::Char('s')],
+            Action::ReverseSort => vec![Key::Char('S')],
+            Action::Delete => vec![Key::Delete],
+            Action::Undo => vec![Key::Char('u')],
+            Action::Edit => vec![Key::Char('e')],
+            Action::ToggleSecrets => vec![Key::Char('s')],
+            Action::Submit => vec![Key::Ctrl('s')],
+            Action::Reload => vec![Key::Char('r')],
+            Action::OnlyDifferences => vec![Key::Char('d')],
+            Action::Clear => vec![Key::Char('c')],
+        }
+    }
+
+    /// Actions of two contexts may be available on the same screen
+    fn overlaps(&self, other: &Action) -> bool {
+        let shared = [Context::Global, Context::Navigation];
+        self.context() == other.context()
+            || shared.contains(&self.context())
+            || shared.contains(&other.context())
+    }
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown action {}, known actions : {}",
+                    s,
+                    Action::ALL.map(|action| action.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Keys bound to each action
+///
+/// ```toml
+/// [keys]
+/// down = ["<Down>", "j"]
+/// exit = ["<Ctrl+q>"]
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Default keys, replaced by the ones of the config file, a key cannot be bound to two
+    /// actions of the same screen
+    pub fn from_bindings(bindings: &BTreeMap<String, Vec<String>>) -> Result<KeyMap> {
+        let mut keymap = KeyMap::default();
+        for (name, keys) in bindings {
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<Key>>>()?;
+            keymap.bindings.insert(name.parse()?, keys);
+        }
+        for (i, a) in Action::ALL.iter().enumerate() {
+            for b in Action::ALL.iter().skip(i + 1).filter(|b| a.overlaps(b)) {
+                if let Some(key) = keymap.keys(*a).iter().find(|k| keymap.keys(*b).contains(k)) {
+                    return Err(anyhow!(
+                        "Key {} is bound to both {} and {}",
+                        key,
+                        a.name(),
+                        b.name()
+                    ));
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn matches(&self, action: Action, key: &Key) -> bool {
+        self.keys(action).contains(key)
+    }
+
+    /// Action of the key among the ones of the given contexts
+    pub fn action(&self, contexts: &[Context], key: &Key) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|action| contexts.contains(&action.context()))
+            .find(|action| self.matches(*action, key))
+    }
+
+    /// Exit key given to the event loop, the first one bound
+    pub fn exit_key(&self) -> Key {
+        self.keys(Action::Exit)
+            .first()
+            .copied()
+            .unwrap_or(Key::Ctrl('c'))
+    }
+}
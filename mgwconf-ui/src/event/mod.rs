@@ -1,7 +1,8 @@
 mod events;
 mod key;
+pub mod keymap;
 
 pub use self::{
-    events::{Event, Events},
+    events::{Event, EventConfig, Events},
     key::Key,
 };
@@ -0,0 +1,35 @@
+use crate::app::UiAppTrait;
+use crate::event::{
+    keymap::{Action, Context},
+    Key,
+};
+use mgwconf_network::AppConfig;
+
+pub async fn handler<A, C>(key: Key, app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let len = app.compare_rows().len();
+    let action = app
+        .keymap()
+        .action(&[Context::Navigation, Context::Compare], &key);
+    let Some(compare) = app.get_compare_mut() else {
+        return;
+    };
+    match action {
+        Some(Action::Down) if compare.selected + 1 < len => compare.selected += 1,
+        Some(Action::Up) => compare.selected = compare.selected.saturating_sub(1),
+        Some(Action::OnlyDifferences) => compare.toggle_only_differences(),
+        Some(Action::Right) => {
+            compare.next_tab();
+            app.reload_compare();
+        }
+        Some(Action::Left) => {
+            compare.previous_tab();
+            app.reload_compare();
+        }
+        Some(Action::Reload) => app.reload_compare(),
+        _ => {}
+    }
+}
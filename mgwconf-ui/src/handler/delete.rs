@@ -0,0 +1,66 @@
+use mgwconf_network::AppConfig;
+
+use crate::app::{
+    state::{delete::LIVE_CONFIRMATION, State},
+    UiAppTrait,
+};
+use crate::event::Key;
+use crate::ui::prelude::ActiveBlock;
+
+/// Keys of the delete confirmation, `LIVE_CONFIRMATION` is typed for LIVE entities
+pub async fn handler<A, C>(key: Key, app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(deletion) = app.get_configuration_state_mut().deletion_mut() else {
+        app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+        return;
+    };
+    match key {
+        Key::Enter if deletion.is_confirmed() => confirm(app).await,
+        Key::Char('y') if !deletion.live => confirm(app).await,
+        Key::Char('n') if !deletion.live => {
+            app.get_configuration_state_mut().cancel_delete();
+            app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+        }
+        Key::Char(c) if deletion.live && deletion.typed.len() < LIVE_CONFIRMATION.len() => {
+            deletion.typed.push(c)
+        }
+        Key::Backspace if deletion.live => {
+            deletion.typed.pop();
+        }
+        _ => {}
+    }
+}
+
+async fn confirm<A, C>(app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+    let Some(event) = app.get_configuration_state_mut().confirm_delete() else {
+        return;
+    };
+    // The list is reloaded once the gateway accepted it
+    if let Err(e) = app.dispatch(event).await {
+        app.get_notifications_mut().error(e.to_string());
+    }
+    app.get_configuration_state_mut().reload();
+}
+
+/// Create the last deleted entity again from its cached copy
+pub async fn undo<A, C>(app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(event) = app.get_configuration_state_mut().undo_delete() else {
+        return;
+    };
+    if let Err(e) = app.dispatch(event).await {
+        app.get_notifications_mut().error(e.to_string());
+    }
+    app.get_configuration_state_mut().reload();
+}
@@ -0,0 +1,90 @@
+use mgwconf_network::AppConfig;
+
+use crate::app::{state::form::FieldKind, UiAppTrait};
+use crate::event::{
+    keymap::{Action, Context},
+    Key,
+};
+use crate::i18n::{tr, Message};
+use crate::ui::prelude::ActiveBlock;
+
+/// Keys of the create and edit dialog, the input buffer is handled by `handle_input` while editing
+pub async fn handler<A, C>(key: Key, app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let action = app
+        .keymap()
+        .action(&[Context::Navigation, Context::Form], &key);
+    let Some(form) = app.get_configuration_state_mut().form_mut() else {
+        app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+        return;
+    };
+    if let Some(picker) = form.picker.as_mut() {
+        match (action, key) {
+            (Some(Action::Down), _) => picker.next(),
+            (Some(Action::Up), _) => picker.back(),
+            (Some(Action::Left), _) | (_, Key::Backspace) => picker.parent(),
+            (Some(Action::Select | Action::Right), _) => form.pick(),
+            _ => {}
+        }
+        return;
+    }
+    match (action, key) {
+        (Some(Action::Down), _) | (_, Key::Tab) => form.next(),
+        (Some(Action::Up), _) => form.back(),
+        (Some(Action::Left), _) => form.cycle(false),
+        (Some(Action::Right), _) | (_, Key::Char(' ')) => form.cycle(true),
+        (_, Key::Delete | Key::Backspace) => form.remove(),
+        (Some(Action::Submit), _) => submit(app).await,
+        (Some(Action::Select), _) if form.is_submit_selected() => submit(app).await,
+        (Some(Action::Select), _) => match form.current().map(|f| f.kind) {
+            Some(FieldKind::Boolean) | Some(FieldKind::Choice(_)) => form.cycle(true),
+            Some(FieldKind::File) => form.open_picker(),
+            Some(_) => {
+                let value = form.edit_value();
+                *app.get_user_input_mut() = value;
+                app.set_current_route_state(Some(ActiveBlock::Editing), None);
+            }
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+/// Send the entity, the dialog stays open with its errors when the entity is invalid
+///
+/// An edit shows its diff first and is sent on the next submit. The dialog closes once
+/// the gateway accepted every event, see `UiApp::handle_network_response`
+async fn submit<A, C>(app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(form) = app.get_configuration_state_mut().form_mut() else {
+        return;
+    };
+    if form.sending {
+        return;
+    }
+    let Ok(events) = form.submit() else {
+        return;
+    };
+    if form.is_edit() && !form.review {
+        if form.diff().is_empty() {
+            form.error = Some(tr(Message::NothingChanged).to_owned());
+        } else {
+            form.review = true;
+        }
+        return;
+    }
+    let Some(event) = form.start(events) else {
+        return;
+    };
+    if let Err(e) = app.dispatch(event).await {
+        if let Some(form) = app.get_configuration_state_mut().form_mut() {
+            form.fail(e.to_string());
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::app::{RouteId, UiAppTrait};
-use crate::event::Key;
+use crate::event::{
+    keymap::{Action, Context},
+    Key,
+};
 use crate::ui::prelude::ActiveBlock;
 use mgwconf_network::{event::IoEvent, AppConfig};
 
@@ -20,12 +23,25 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    match key {
-        k if k == &Key::Enter && app.get_current_route().id != RouteId::Configuration => {
+    match app
+        .keymap()
+        .action(&[Context::Navigation, Context::Home], key)
+    {
+        Some(Action::Down) => app.select_gateway(app.selected_gateway() + 1),
+        Some(Action::Up) => app.select_gateway(app.selected_gateway().saturating_sub(1)),
+        Some(Action::Select) if app.selected_gateway() != app.active_gateway() => {
+            app.switch_gateway(app.selected_gateway());
+        }
+        Some(Action::Compare) if app.open_compare() => {
+            app.push_navigation_stack(RouteId::Compare, ActiveBlock::Compare);
+        }
+        Some(Action::Select) if app.get_current_route().id != RouteId::Configuration => {
             if app.is_connected() {
                 app.push_navigation_stack(RouteId::Configuration, ActiveBlock::Tab);
             } else {
-                app.dispatch(IoEvent::Ping).await.unwrap();
+                if let Err(e) = app.dispatch(IoEvent::Ping).await {
+                    app.get_notifications_mut().error(e.to_string());
+                }
             }
         }
         _ => {}
@@ -37,7 +53,7 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    if *key == Key::Esc {
+    if app.keymap().matches(Action::Back, key) {
         app.force_exit();
     }
 }
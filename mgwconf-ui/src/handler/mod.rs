@@ -1,19 +1,43 @@
+mod compare;
 mod configuration;
+mod delete;
+mod form;
 mod home;
+mod notification;
 
 use mgwconf_network::AppConfig;
 
 use crate::app::{ActiveBlock, RouteId, UiAppTrait};
-use crate::event::Key;
+use crate::event::{
+    keymap::{Action, Context},
+    Key,
+};
+
+/// Distinguished names and paths are longer than most values
+const MAX_INPUT_LEN: usize = 256;
 
 pub async fn handle_app<A, C>(key: Key, app: &mut A)
 where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    match key {
-        Key::Esc => {
-            handle_escape(app);
+    let active_block = app.get_current_route().active_block;
+    let action = app
+        .keymap()
+        .action(&[Context::Global], &key)
+        // Other keys are typed in the LIVE confirmation
+        .filter(|action| active_block != ActiveBlock::Confirm || *action == Action::Back);
+    if active_block == ActiveBlock::HelpMenu {
+        if matches!(action, Some(Action::Back | Action::Help)) {
+            app.hide_help();
+        }
+        return;
+    }
+    match action {
+        Some(Action::Back) => handle_escape(app),
+        Some(Action::Help) => app.show_help(),
+        Some(Action::Notifications) => {
+            app.push_navigation_stack(RouteId::Notifications, ActiveBlock::Notifications);
         }
         _ => handle_route(key, app).await,
     }
@@ -28,6 +52,8 @@ where
     match current_route.id {
         RouteId::Home => home::handler(key, app).await,
         RouteId::Configuration => configuration::handler(key, app).await,
+        RouteId::Compare => compare::handler(key, app).await,
+        RouteId::Notifications => notification::handler(key, app),
     }
 }
 
@@ -41,33 +67,49 @@ where
             app.get_user_input_mut().clear();
             handle_escape(app);
         }
-        Key::Enter => {
+        Key::Enter if app.get_configuration_state().is_searching() => {
             app.get_user_input_mut().clear();
+            app.get_configuration_state_mut().end_search(false);
+            app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
         }
-        Key::Backspace => {
-            if !app.get_user_input().is_empty() {
-                app.get_user_input_mut().pop();
+        Key::Enter => {
+            let input = std::mem::take(app.get_user_input_mut());
+            if let Some(form) = app.get_configuration_state_mut().form_mut() {
+                form.commit(&input);
             }
+            app.set_current_route_state(Some(ActiveBlock::Dialog), None);
         }
-        Key::Char(c) => {
-            if app.get_user_input_mut().len() < 48 {
-                app.get_user_input_mut().push(c);
-            }
+        Key::Backspace if !app.get_user_input().is_empty() => {
+            app.get_user_input_mut().pop();
+            search_input(app);
+        }
+        Key::Char(c) if app.get_user_input().len() < MAX_INPUT_LEN => {
+            app.get_user_input_mut().push(c);
+            search_input(app);
         }
         _ => {}
     }
 }
 
+/// The list is filtered while the search is typed
+fn search_input<A, C>(app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    if app.get_configuration_state().is_searching() {
+        let search = app.get_user_input().to_owned();
+        app.get_configuration_state_mut().set_search(&search);
+    }
+}
+
 fn handle_escape<A, C>(app: &mut A)
 where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
     match app.get_current_route().active_block {
-        ActiveBlock::Error => {
-            app.pop_navigation_stack();
-        }
-        ActiveBlock::Home => {
+        ActiveBlock::Home | ActiveBlock::Compare | ActiveBlock::Notifications => {
             app.pop_navigation_stack();
         }
         ActiveBlock::TabSelected => {
@@ -77,7 +119,24 @@ where
         ActiveBlock::Detailed => {
             app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
         }
-        ActiveBlock::Dialog => app.reset_navigation_stack(),
+        ActiveBlock::Dialog => match app.get_configuration_state_mut().form_mut() {
+            Some(form) if form.picker.is_some() => form.picker = None,
+            _ => {
+                app.get_configuration_state_mut().close_form();
+                app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+            }
+        },
+        ActiveBlock::Editing if app.get_configuration_state().is_searching() => {
+            app.get_configuration_state_mut().end_search(true);
+            app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+        }
+        ActiveBlock::Editing => {
+            app.set_current_route_state(Some(ActiveBlock::Dialog), None);
+        }
+        ActiveBlock::Confirm => {
+            app.get_configuration_state_mut().cancel_delete();
+            app.set_current_route_state(Some(ActiveBlock::TabSelected), None);
+        }
         _ => {
             app.set_current_route_state(Some(ActiveBlock::Empty), None);
         }
@@ -0,0 +1,30 @@
+use crate::app::UiAppTrait;
+use crate::event::{
+    keymap::{Action, Context},
+    Key,
+};
+use mgwconf_network::AppConfig;
+
+/// Rows moved by PgUp and PgDn in the history
+const PAGE: usize = 10;
+
+pub fn handler<A, C>(key: Key, app: &mut A)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let action = app
+        .keymap()
+        .action(&[Context::Navigation, Context::Notifications], &key);
+    let notifications = app.get_notifications_mut();
+    match action {
+        Some(Action::Down) => notifications.next(),
+        Some(Action::Up) => notifications.back(),
+        Some(Action::PageDown) => notifications.move_to(notifications.selected + PAGE),
+        Some(Action::PageUp) => notifications.move_to(notifications.selected.saturating_sub(PAGE)),
+        Some(Action::First) => notifications.move_to(0),
+        Some(Action::Last) => notifications.move_to(usize::MAX),
+        Some(Action::Clear) => notifications.clear(),
+        _ => {}
+    }
+}
@@ -0,0 +1,213 @@
+use super::Message::{self, *};
+
+pub(super) fn text(message: Message) -> &'static str {
+    match message {
+        // Tabs
+        TabCertificates => "Certificates",
+        TabSags => "SAGs",
+        TabBusinessApplications => "Business Applications",
+        TabProfiles => "Profiles",
+        TabApiProxy => "Api Proxy",
+        TabForwardProxy => "Forward Proxy",
+        TabApiClientCredentials => "Api Client Credentials",
+        // Home
+        Home => "Home",
+        HomeSummary => "User : {}\nRemote IP : {}\nRemote Port : {}\nConnectivity : {}",
+        HomeUseGateway => "Press {} to use this gateway",
+        HomeRefresh => "Press {} to refresh",
+        HomeContinue => "Press {} to continue",
+        Gateways => "Gateways",
+        GatewaysHint => "Gateways - {}/{} : select, {} : compare with the active gateway",
+        ColumnName => "Name",
+        ColumnAddress => "Address",
+        ColumnHealth => "Health",
+        HealthUp => "UP ({})",
+        HealthDown => "DOWN : {}",
+        // Gateways
+        NoGateway => "No gateway configured",
+        CannotSend => "Cannot send to gateway {} : {}",
+        ActiveGateway => "{} is the active gateway",
+        Reachable => "{} is reachable",
+        Unreachable => "{} is unreachable : {}",
+        UnreadableResponse => "Unreadable response to {} : {}",
+        UnreadableGatewayResponse => "Unreadable response of {} to {} : {}",
+        // Comparison
+        CompareTitle => "{} : {} / {} - {}/{} : tab, {} : {}, {} : reload",
+        CompareShowAll => "show all",
+        CompareOnlyDifferences => "only differences",
+        CompareSame => "Same on both gateways",
+        CompareChanged => "Differences : {}",
+        CompareMissingFrom => "Missing from {}",
+        CompareMissing => "(missing)",
+        CompareEmpty => "No entity",
+        // Entity lists
+        UndoDelete => "{} - {}: undo delete of {} {}",
+        Sort => "Sort: {} {} ({}/{})",
+        Ascending => "asc",
+        Descending => "desc",
+        Searching => "Search: {}_",
+        Search => "Search: {} ({})",
+        SearchHint => "Search ({})",
+        ChipActive => "active",
+        ChipInactive => "inactive",
+        ChipExpiringSoon => "expiring soon",
+        ChipExpired => "expired",
+        Yes => "yes",
+        No => "no",
+        AddCertificate => "Add Certificate",
+        AddSag => "Add SAG",
+        AddBusinessApplication => "Add Business Application",
+        AddProfile => "Add Profile",
+        AddApiProxy => "Add Api Proxy",
+        AddForwardProxy => "Add Forward Proxy",
+        AddApiClientCredential => "Add Api Client Credential",
+        ColumnHost => "Host",
+        ColumnPort => "Port",
+        ColumnMessagePartner => "MP name",
+        ColumnActive => "Active",
+        ColumnCertificateAlias => "Certificate alias",
+        ColumnAlias => "Alias",
+        ColumnSubject => "Subject",
+        ColumnExpiry => "Expiry",
+        ColumnApplication => "Application",
+        ColumnSharedSecret => "Shared secret",
+        ColumnProfile => "Profile",
+        ColumnScope => "Scope",
+        ColumnDns => "DNs",
+        ColumnEnvironment => "Environment",
+        ColumnUser => "User",
+        ColumnConsumerKey => "Consumer key",
+        // Entity details
+        SagDetail => {
+            "
+Hostname : {}
+Port : {}
+LAU KEY : {}
+SSL DN : {}
+Message Partner : {}
+User DNs : {}
+"
+        }
+        CertificateDetail => {
+            "
+Alias : {}
+Subject : {}
+Issuer : {}
+SANs : {}
+Not Before : {}
+Not After : {} ({})
+Key : {} {} bits
+SHA-256 Fingerprint : {}
+"
+        }
+        CertificateError => {
+            "
+Alias : {}
+Error : {}
+CertificateX509 : {}
+"
+        }
+        Expired => "EXPIRED",
+        DaysLeft => "{} days left",
+        // Changes
+        Created => "{} created : {}",
+        Updated => "{} updated : {}",
+        Deleted => "{} deleted : {}",
+        Saved => "{} saved",
+        // Create and edit dialog
+        FormNew => "New {}",
+        FormEdit => "Edit {}",
+        CertificateNotEditable => "Certificates cannot be edited, the gateway does not return their private key, import them again instead",
+        FormChanges => "Changes :",
+        FormSending => "[ Sending... ]",
+        FormCreate => "[ Create ]",
+        FormReview => "[ Review changes ]",
+        FormConfirm => "[ Confirm ]",
+        FormRequired => "* required",
+        FormHint => "{}/{} move, {} edit, {}/{} choose, Del remove, {} save, {} cancel",
+        FormAddItem => "(Enter to add)",
+        FormBrowse => "(Enter to browse)",
+        SecretChanged => "**** (changed)",
+        NothingChanged => "Nothing changed",
+        Required => "Required",
+        PortOutOfRange => "Port {} is out of range",
+        NotANumber => "{} is not a number",
+        NotADistinguishedName => "{} is not a distinguished name",
+        NotAFile => "{} is not a file",
+        FieldsToFix => "{} field(s) to fix",
+        AppliedBeforeFailure => "{} operation(s) applied before the failure : {}",
+        FieldAlias => "Alias",
+        FieldCertificateFile => "Certificate file",
+        FieldPrivateKeyFile => "Private key file",
+        FieldPkcs12Password => "PKCS#12 password",
+        FieldForce => "Import if expired",
+        FieldHostname => "Hostname",
+        FieldPort => "Port",
+        FieldMessagePartner => "Message partner",
+        FieldUserDns => "User DNs",
+        FieldLauKey => "LAU key",
+        FieldSslDn => "SSL DN",
+        FieldActive => "Active",
+        FieldPublicCertificateAlias => "Public certificate alias",
+        FieldApplicationName => "Application name",
+        FieldSharedSecret => "Shared secret",
+        FieldProfileName => "Profile name",
+        FieldRbacScope => "RBAC scope",
+        FieldCertificateAlias => "Certificate alias",
+        FieldEnvironment => "Environment",
+        FieldUser => "User",
+        FieldPassword => "Password",
+        FieldConsumerKey => "Consumer key",
+        FieldConsumerSecret => "Consumer secret",
+        // Delete confirmation
+        DeleteFrom => "Delete from {}",
+        DeleteLive => "{} environment, type {} to delete it",
+        DeleteNoUndo => "Cannot be undone, the gateway does not return {}",
+        DeleteLiveHint => "Enter delete, Esc cancel",
+        DeleteHint => "y/Enter delete, n/Esc cancel",
+        // Notifications
+        NotificationsTitle => "Notifications - {}: clear, {}: back",
+        StatusHelp => "{}: help",
+        StatusNotifications => "{}: notifications ({}, {} errors)",
+        // Help
+        HelpTitle => "Help - {} to close",
+        ContextGlobal => "Global",
+        ContextNavigation => "Navigation",
+        ContextHome => "Home",
+        ContextList => "Entity list",
+        ContextDetail => "Entity detail",
+        ContextForm => "Create and edit dialog",
+        ContextCompare => "Gateway comparison",
+        ContextNotifications => "Notifications",
+        ActionExit => "Quit the application",
+        ActionBack => "Go back, quit from the tab list",
+        ActionHelp => "Show this help",
+        ActionNotifications => "Show the notification history",
+        ActionDown => "Next item",
+        ActionUp => "Previous item",
+        ActionPageDown => "Next page",
+        ActionPageUp => "Previous page",
+        ActionFirst => "First item",
+        ActionLast => "Last item",
+        ActionSelect => "Open, or create from the first row of a list",
+        ActionLeft => "Previous value, tab or parent directory",
+        ActionRight => "Next value, tab or picked file",
+        ActionSwitchPanel => "Move between the tabs and the list",
+        ActionCompare => "Compare the selected gateway with the active one",
+        ActionSearch => "Search the list",
+        ActionNextSort => "Sort by the next column",
+        ActionReverseSort => "Reverse the sort order",
+        ActionDelete => "Delete the selected entity",
+        ActionUndo => "Create the last deleted entity again",
+        ActionEdit => "Edit the entity",
+        ActionToggleSecrets => "Show or hide the secrets",
+        ActionSubmit => "Send the entity",
+        ActionReload => "Load both gateways again",
+        ActionOnlyDifferences => "Show only the differences",
+        ActionClear => "Clear the history",
+        KeyToggleChip => "Toggle a filter chip",
+        KeyNextField => "Next field",
+        KeyNextValue => "Next value",
+        KeyClearField => "Clear the field",
+    }
+}
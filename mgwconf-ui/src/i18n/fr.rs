@@ -0,0 +1,215 @@
+use super::Message::{self, *};
+
+pub(super) fn text(message: Message) -> &'static str {
+    match message {
+        // Tabs
+        TabCertificates => "Certificats",
+        TabSags => "SAG",
+        TabBusinessApplications => "Applications métier",
+        TabProfiles => "Profils",
+        TabApiProxy => "Proxy API",
+        TabForwardProxy => "Proxy sortant",
+        TabApiClientCredentials => "Identifiants des clients API",
+        // Home
+        Home => "Accueil",
+        HomeSummary => "Utilisateur : {}\nIP distante : {}\nPort distant : {}\nConnectivité : {}",
+        HomeUseGateway => "Appuyer sur {} pour utiliser cette passerelle",
+        HomeRefresh => "Appuyer sur {} pour rafraîchir",
+        HomeContinue => "Appuyer sur {} pour continuer",
+        Gateways => "Passerelles",
+        GatewaysHint => "Passerelles - {}/{} : choisir, {} : comparer avec la passerelle active",
+        ColumnName => "Nom",
+        ColumnAddress => "Adresse",
+        ColumnHealth => "Santé",
+        HealthUp => "OK ({})",
+        HealthDown => "HS : {}",
+        // Gateways
+        NoGateway => "Aucune passerelle configurée",
+        CannotSend => "Envoi impossible à la passerelle {} : {}",
+        ActiveGateway => "{} est la passerelle active",
+        Reachable => "{} est joignable",
+        Unreachable => "{} est injoignable : {}",
+        UnreadableResponse => "Réponse illisible à {} : {}",
+        UnreadableGatewayResponse => "Réponse illisible de {} à {} : {}",
+        // Comparison
+        CompareTitle => "{} : {} / {} - {}/{} : onglet, {} : {}, {} : recharger",
+        CompareShowAll => "tout afficher",
+        CompareOnlyDifferences => "différences seulement",
+        CompareSame => "Identique sur les deux passerelles",
+        CompareChanged => "Différences : {}",
+        CompareMissingFrom => "Absent de {}",
+        CompareMissing => "(absent)",
+        CompareEmpty => "Aucune entité",
+        // Entity lists
+        UndoDelete => "{} - {} : annuler la suppression de {} {}",
+        Sort => "Tri : {} {} ({}/{})",
+        Ascending => "croissant",
+        Descending => "décroissant",
+        Searching => "Recherche : {}_",
+        Search => "Recherche : {} ({})",
+        SearchHint => "Recherche ({})",
+        ChipActive => "actif",
+        ChipInactive => "inactif",
+        ChipExpiringSoon => "expire bientôt",
+        ChipExpired => "expiré",
+        Yes => "oui",
+        No => "non",
+        AddCertificate => "Ajouter un certificat",
+        AddSag => "Ajouter une SAG",
+        AddBusinessApplication => "Ajouter une application métier",
+        AddProfile => "Ajouter un profil",
+        AddApiProxy => "Ajouter un proxy API",
+        AddForwardProxy => "Ajouter un proxy sortant",
+        AddApiClientCredential => "Ajouter un identifiant de client API",
+        ColumnHost => "Hôte",
+        ColumnPort => "Port",
+        ColumnMessagePartner => "Partenaire",
+        ColumnActive => "Active",
+        ColumnCertificateAlias => "Alias du certificat",
+        ColumnAlias => "Alias",
+        ColumnSubject => "Sujet",
+        ColumnExpiry => "Expiration",
+        ColumnApplication => "Application",
+        ColumnSharedSecret => "Secret partagé",
+        ColumnProfile => "Profil",
+        ColumnScope => "Portée",
+        ColumnDns => "DN",
+        ColumnEnvironment => "Environnement",
+        ColumnUser => "Utilisateur",
+        ColumnConsumerKey => "Clé client",
+        // Entity details
+        SagDetail => {
+            "
+Nom d'hôte : {}
+Port : {}
+Clé LAU : {}
+DN SSL : {}
+Partenaire : {}
+DN des utilisateurs : {}
+"
+        }
+        CertificateDetail => {
+            "
+Alias : {}
+Sujet : {}
+Émetteur : {}
+SAN : {}
+Valide à partir du : {}
+Valide jusqu'au : {} ({})
+Clé : {} {} bits
+Empreinte SHA-256 : {}
+"
+        }
+        CertificateError => {
+            "
+Alias : {}
+Erreur : {}
+CertificateX509 : {}
+"
+        }
+        Expired => "EXPIRÉ",
+        DaysLeft => "{} jours restants",
+        // Changes
+        Created => "{} : création de {}",
+        Updated => "{} : modification de {}",
+        Deleted => "{} : suppression de {}",
+        Saved => "{} : modifications enregistrées",
+        // Create and edit dialog
+        FormNew => "Création : {}",
+        FormEdit => "Modification : {}",
+        CertificateNotEditable => "Les certificats ne sont pas modifiables, la passerelle ne renvoie pas leur clé privée, les importer à nouveau",
+        FormChanges => "Modifications :",
+        FormSending => "[ Envoi... ]",
+        FormCreate => "[ Créer ]",
+        FormReview => "[ Vérifier les modifications ]",
+        FormConfirm => "[ Confirmer ]",
+        FormRequired => "* obligatoire",
+        FormHint => {
+            "{}/{} déplacer, {} modifier, {}/{} choisir, Suppr effacer, {} enregistrer, {} annuler"
+        }
+        FormAddItem => "(Entrée pour ajouter)",
+        FormBrowse => "(Entrée pour parcourir)",
+        SecretChanged => "**** (modifié)",
+        NothingChanged => "Aucune modification",
+        Required => "Obligatoire",
+        PortOutOfRange => "Le port {} est hors limites",
+        NotANumber => "{} n'est pas un nombre",
+        NotADistinguishedName => "{} n'est pas un nom distinctif",
+        NotAFile => "{} n'est pas un fichier",
+        FieldsToFix => "{} champ(s) à corriger",
+        AppliedBeforeFailure => "{} opération(s) appliquée(s) avant l'échec : {}",
+        FieldAlias => "Alias",
+        FieldCertificateFile => "Fichier du certificat",
+        FieldPrivateKeyFile => "Fichier de la clé privée",
+        FieldPkcs12Password => "Mot de passe PKCS#12",
+        FieldForce => "Importer même expiré",
+        FieldHostname => "Nom d'hôte",
+        FieldPort => "Port",
+        FieldMessagePartner => "Partenaire",
+        FieldUserDns => "DN des utilisateurs",
+        FieldLauKey => "Clé LAU",
+        FieldSslDn => "DN SSL",
+        FieldActive => "Active",
+        FieldPublicCertificateAlias => "Alias du certificat public",
+        FieldApplicationName => "Nom de l'application",
+        FieldSharedSecret => "Secret partagé",
+        FieldProfileName => "Nom du profil",
+        FieldRbacScope => "Portée RBAC",
+        FieldCertificateAlias => "Alias du certificat",
+        FieldEnvironment => "Environnement",
+        FieldUser => "Utilisateur",
+        FieldPassword => "Mot de passe",
+        FieldConsumerKey => "Clé client",
+        FieldConsumerSecret => "Secret client",
+        // Delete confirmation
+        DeleteFrom => "Suppression : {}",
+        DeleteLive => "Environnement {}, taper {} pour supprimer",
+        DeleteNoUndo => "Irréversible, la passerelle ne renvoie pas {}",
+        DeleteLiveHint => "Entrée supprimer, Échap annuler",
+        DeleteHint => "y/Entrée supprimer, n/Échap annuler",
+        // Notifications
+        NotificationsTitle => "Notifications - {} : effacer, {} : retour",
+        StatusHelp => "{} : aide",
+        StatusNotifications => "{} : notifications ({}, {} erreurs)",
+        // Help
+        HelpTitle => "Aide - {} pour fermer",
+        ContextGlobal => "Général",
+        ContextNavigation => "Navigation",
+        ContextHome => "Accueil",
+        ContextList => "Liste des entités",
+        ContextDetail => "Détail d'une entité",
+        ContextForm => "Création et modification",
+        ContextCompare => "Comparaison des passerelles",
+        ContextNotifications => "Notifications",
+        ActionExit => "Quitter l'application",
+        ActionBack => "Revenir en arrière, quitter depuis la liste des onglets",
+        ActionHelp => "Afficher cette aide",
+        ActionNotifications => "Afficher l'historique des notifications",
+        ActionDown => "Élément suivant",
+        ActionUp => "Élément précédent",
+        ActionPageDown => "Page suivante",
+        ActionPageUp => "Page précédente",
+        ActionFirst => "Premier élément",
+        ActionLast => "Dernier élément",
+        ActionSelect => "Ouvrir, ou créer depuis la première ligne d'une liste",
+        ActionLeft => "Valeur, onglet précédent ou répertoire parent",
+        ActionRight => "Valeur, onglet suivant ou fichier choisi",
+        ActionSwitchPanel => "Passer des onglets à la liste",
+        ActionCompare => "Comparer la passerelle choisie avec la passerelle active",
+        ActionSearch => "Rechercher dans la liste",
+        ActionNextSort => "Trier selon la colonne suivante",
+        ActionReverseSort => "Inverser l'ordre du tri",
+        ActionDelete => "Supprimer l'entité choisie",
+        ActionUndo => "Créer à nouveau la dernière entité supprimée",
+        ActionEdit => "Modifier l'entité",
+        ActionToggleSecrets => "Afficher ou masquer les secrets",
+        ActionSubmit => "Envoyer l'entité",
+        ActionReload => "Recharger les deux passerelles",
+        ActionOnlyDifferences => "N'afficher que les différences",
+        ActionClear => "Effacer l'historique",
+        KeyToggleChip => "Activer ou désactiver un filtre",
+        KeyNextField => "Champ suivant",
+        KeyNextValue => "Valeur suivante",
+        KeyClearField => "Effacer le champ",
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    env,
+    fmt::{self, Write},
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering::Relaxed},
+};
+
+use anyhow::{anyhow, Result};
+
+mod en;
+mod fr;
+#[cfg(test)]
+mod tests;
+
+/// Language of the UI, from the `locale` of the config file or from `LANG`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Fr];
+
+    /// Locale of `LC_ALL`, `LC_MESSAGES` or `LANG`, English when none is supported
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Text of the message in this locale
+    pub fn text(&self, message: Message) -> &'static str {
+        match self {
+            Locale::En => en::text(message),
+            Locale::Fr => fr::text(message),
+        }
+    }
+
+    /// Text of the message in this locale, each `{}` is replaced by the next argument
+    pub fn format(&self, message: Message, args: &[&dyn fmt::Display]) -> String {
+        let mut parts = self.text(message).split("{}");
+        let mut text = parts.next().unwrap_or_default().to_owned();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                let _ = write!(text, "{}", arg);
+            }
+            text.push_str(part);
+        }
+        text
+    }
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    /// Language of a locale name such as `fr`, `fr-FR` or `fr_FR.UTF-8`
+    fn from_str(s: &str) -> Result<Locale> {
+        let language = s
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.to_string() == language)
+            .ok_or_else(|| anyhow!("Unsupported locale {}, supported locales : en, fr", s))
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::En => write!(f, "en"),
+            Locale::Fr => write!(f, "fr"),
+        }
+    }
+}
+
+/// Locale of the messages, process wide as they are also built by the state and the network
+/// threads
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Relaxed) {
+        1 => Locale::Fr,
+        _ => Locale::En,
+    }
+}
+
+/// Text of the message in the UI locale
+pub fn tr(message: Message) -> &'static str {
+    locale().text(message)
+}
+
+/// Text of the message in the UI locale, each `{}` is replaced by the next argument
+pub fn tr_args(message: Message, args: &[&dyn fmt::Display]) -> String {
+    locale().format(message, args)
+}
+
+macro_rules! messages {
+    ($($name:ident,)*) => {
+        /// User facing text of the UI, each catalog gives its translation
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Message {
+            $($name,)*
+        }
+
+        impl Message {
+            pub const ALL: &'static [Message] = &[$(Message::$name,)*];
+        }
+    };
+}
+
+messages! {
+    // Tabs
+    TabCertificates,
+    TabSags,
+    TabBusinessApplications,
+    TabProfiles,
+    TabApiProxy,
+    TabForwardProxy,
+    TabApiClientCredentials,
+    // Home
+    Home,
+    HomeSummary,
+    HomeUseGateway,
+    HomeRefresh,
+    HomeContinue,
+    Gateways,
+    GatewaysHint,
+    ColumnName,
+    ColumnAddress,
+    ColumnHealth,
+    HealthUp,
+    HealthDown,
+    // Gateways
+    NoGateway,
+    CannotSend,
+    ActiveGateway,
+    Reachable,
+    Unreachable,
+    UnreadableResponse,
+    UnreadableGatewayResponse,
+    // Comparison
+    CompareTitle,
+    CompareShowAll,
+    CompareOnlyDifferences,
+    CompareSame,
+    CompareChanged,
+    CompareMissingFrom,
+    CompareMissing,
+    CompareEmpty,
+    // Entity lists
+    UndoDelete,
+    Sort,
+    Ascending,
+    Descending,
+    Searching,
+    Search,
+    SearchHint,
+    ChipActive,
+    ChipInactive,
+    ChipExpiringSoon,
+    ChipExpired,
+    Yes,
+    No,
+    AddCertificate,
+    AddSag,
+    AddBusinessApplication,
+    AddProfile,
+    AddApiProxy,
+    AddForwardProxy,
+    AddApiClientCredential,
+    ColumnHost,
+    ColumnPort,
+    ColumnMessagePartner,
+    ColumnActive,
+    ColumnCertificateAlias,
+    ColumnAlias,
+    ColumnSubject,
+    ColumnExpiry,
+    ColumnApplication,
+    ColumnSharedSecret,
+    ColumnProfile,
+    ColumnScope,
+    ColumnDns,
+    ColumnEnvironment,
+    ColumnUser,
+    ColumnConsumerKey,
+    // Entity details
+    SagDetail,
+    CertificateDetail,
+    CertificateError,
+    Expired,
+    DaysLeft,
+    // Changes
+    Created,
+    Updated,
+    Deleted,
+    Saved,
+    // Create and edit dialog
+    FormNew,
+    FormEdit,
+    CertificateNotEditable,
+    FormChanges,
+    FormSending,
+    FormCreate,
+    FormReview,
+    FormConfirm,
+    FormRequired,
+    FormHint,
+    FormAddItem,
+    FormBrowse,
+    SecretChanged,
+    NothingChanged,
+    Required,
+    PortOutOfRange,
+    NotANumber,
+    NotADistinguishedName,
+    NotAFile,
+    FieldsToFix,
+    AppliedBeforeFailure,
+    FieldAlias,
+    FieldCertificateFile,
+    FieldPrivateKeyFile,
+    FieldPkcs12Password,
+    FieldForce,
+    FieldHostname,
+    FieldPort,
+    FieldMessagePartner,
+    FieldUserDns,
+    FieldLauKey,
+    FieldSslDn,
+    FieldActive,
+    FieldPublicCertificateAlias,
+    FieldApplicationName,
+    FieldSharedSecret,
+    FieldProfileName,
+    FieldRbacScope,
+    FieldCertificateAlias,
+    FieldEnvironment,
+    FieldUser,
+    FieldPassword,
+    FieldConsumerKey,
+    FieldConsumerSecret,
+    // Delete confirmation
+    DeleteFrom,
+    DeleteLive,
+    DeleteNoUndo,
+    DeleteLiveHint,
+    DeleteHint,
+    // Notifications
+    NotificationsTitle,
+    StatusHelp,
+    StatusNotifications,
+    // Help
+    HelpTitle,
+    ContextGlobal,
+    ContextNavigation,
+    ContextHome,
+    ContextList,
+    ContextDetail,
+    ContextForm,
+    ContextCompare,
+    ContextNotifications,
+    ActionExit,
+    ActionBack,
+    ActionHelp,
+    ActionNotifications,
+    ActionDown,
+    ActionUp,
+    ActionPageDown,
+    ActionPageUp,
+    ActionFirst,
+    ActionLast,
+    ActionSelect,
+    ActionLeft,
+    ActionRight,
+    ActionSwitchPanel,
+    ActionCompare,
+    ActionSearch,
+    ActionNextSort,
+    ActionReverseSort,
+    ActionDelete,
+    ActionUndo,
+    ActionEdit,
+    ActionToggleSecrets,
+    ActionSubmit,
+    ActionReload,
+    ActionOnlyDifferences,
+    ActionClear,
+    KeyToggleChip,
+    KeyNextField,
+    KeyNextValue,
+    KeyClearField,
+}
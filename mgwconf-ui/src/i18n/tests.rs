@@ -0,0 +1,41 @@
+use super::{Locale, Message};
+
+#[test]
+fn catalogs_have_the_same_arguments() {
+    for message in Message::ALL {
+        let en = Locale::En.text(*message);
+        for locale in Locale::ALL {
+            let text = locale.text(*message);
+            assert!(!text.is_empty(), "{message:?} in {locale}");
+            assert_eq!(
+                text.matches("{}").count(),
+                en.matches("{}").count(),
+                "{message:?} in {locale}"
+            );
+        }
+    }
+}
+
+#[test]
+fn arguments_are_filled_in_order() {
+    assert_eq!(
+        Locale::Fr.format(Message::Unreachable, &[&"prod", &"refused"]),
+        "prod est injoignable : refused"
+    );
+    assert_eq!(Locale::En.format(Message::DaysLeft, &[&12]), "12 days left");
+}
+
+#[test]
+fn locale_names_are_parsed() {
+    for (name, locale) in [
+        ("en", Locale::En),
+        ("FR", Locale::Fr),
+        ("fr-BE", Locale::Fr),
+        ("fr_FR.UTF-8", Locale::Fr),
+        ("en_US.UTF-8@euro", Locale::En),
+    ] {
+        assert_eq!(name.parse::<Locale>().unwrap(), locale, "{name}");
+    }
+    assert!("C".parse::<Locale>().is_err());
+    assert!("de_DE".parse::<Locale>().is_err());
+}
@@ -2,5 +2,6 @@ pub mod app;
 pub mod config;
 pub mod event;
 pub mod handler;
+pub mod i18n;
 mod model;
 pub mod ui;
@@ -1,48 +1,59 @@
 use mgwconf_network::mgw_configuration::models::*;
+use mgwconf_network::model::certificate::CertificateDetails;
 pub use std::fmt::Write;
 
+use crate::i18n::{tr, tr_args, Message};
 use crate::ui::fmt::FmtModel;
 
 impl FmtModel for SagEntity {
     fn to_string(&self) -> String {
-        let mut model = String::new();
-        write!(
-            model,
-            r#"
-Hostname : {}
-Port : {}
-LAU KEY : {}
-SSL DN : {}
-Message Partner : {}
-User DNs : {}
-            "#,
-            self.hostname,
-            self.port,
-            self.lau_key.as_ref().unwrap_or(&"NULL".to_owned()),
-            self.ssl_dn.as_ref().unwrap_or(&"NULL".to_owned()),
-            self.message_partner_name
-                .as_ref()
-                .unwrap_or(&"NULL".to_owned()),
-            self.user_dns.join(" ")
+        let null = "NULL".to_owned();
+        tr_args(
+            Message::SagDetail,
+            &[
+                &self.hostname,
+                &self.port,
+                self.lau_key.as_ref().unwrap_or(&null),
+                self.ssl_dn.as_ref().unwrap_or(&null),
+                self.message_partner_name.as_ref().unwrap_or(&null),
+                &self.user_dns.join(" "),
+            ],
         )
-        .unwrap();
-        model
     }
 }
 
 impl FmtModel for CertificateEntity {
     fn to_string(&self) -> String {
-        let mut model = String::new();
-        write!(
-            model,
-            r#"
-Alias : {}
-CertificateX509 : {}
-        "#,
-            self.alias, self.certificate_x509
-        )
-        .unwrap();
-        model
+        match CertificateDetails::try_from(self) {
+            Ok(details) => tr_args(
+                Message::CertificateDetail,
+                &[
+                    &self.alias,
+                    &details.subject,
+                    &details.issuer,
+                    &details.sans.join(" "),
+                    &details.not_before,
+                    &details.not_after,
+                    &expiry(details.days_left()),
+                    &details.key_type,
+                    &details.key_bits,
+                    &details.sha256_fingerprint,
+                ],
+            ),
+            Err(e) => tr_args(
+                Message::CertificateError,
+                &[&self.alias, &e, &self.certificate_x509],
+            ),
+        }
+    }
+}
+
+/// Days before the certificate expires, or that it expired
+pub fn expiry(days_left: i64) -> String {
+    if days_left < 0 {
+        tr(Message::Expired).to_owned()
+    } else {
+        tr_args(Message::DaysLeft, &[&days_left])
     }
 }
 
@@ -0,0 +1,109 @@
+use mgwconf_network::AppConfig;
+use ratatui::{
+    style::Modifier,
+    widgets::{Cell, Row, Table, TableState},
+};
+
+use crate::app::state::compare::{CompareRow, Difference};
+use crate::event::keymap::Action;
+use crate::i18n::{tr, tr_args, Message};
+use crate::ui::help::first_key;
+use crate::ui::prelude::*;
+
+/// Entities of the active gateway on the left and of the compared one on the right, on
+/// aligned rows
+pub fn draw_compare<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(compare) = app.get_compare() else {
+        return;
+    };
+    let name = |i: usize| {
+        app.gateways()
+            .get(i)
+            .map(|gateway| gateway.name.to_owned())
+            .unwrap_or_default()
+    };
+    let (left, right) = (name(app.active_gateway()), name(compare.other));
+    let rows = app.compare_rows();
+
+    let block = Block::default()
+        .title(Span::styled(
+            tr_args(
+                Message::CompareTitle,
+                &[
+                    &compare.tab,
+                    &left,
+                    &right,
+                    &first_key(app, Action::Left),
+                    &first_key(app, Action::Right),
+                    &first_key(app, Action::OnlyDifferences),
+                    &tr(if compare.only_differences {
+                        Message::CompareShowAll
+                    } else {
+                        Message::CompareOnlyDifferences
+                    }),
+                    &first_key(app, Action::Reload),
+                ],
+            ),
+            Style::default(),
+        ))
+        .borders(Borders::ALL);
+    f.render_widget(block, layout_chunk);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(2)].as_ref())
+        .margin(1)
+        .split(layout_chunk);
+    let sides = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(chunks[0]);
+    let selected = (!rows.is_empty()).then(|| compare.selected.min(rows.len() - 1));
+    draw_side(f, sides[0], &left, &rows, selected, |row| &row.left);
+    draw_side(f, sides[1], &right, &rows, selected, |row| &row.right);
+
+    let detail = match selected.map(|i| &rows[i].difference) {
+        Some(Difference::Same) => tr(Message::CompareSame).to_owned(),
+        Some(Difference::Changed(fields)) => {
+            tr_args(Message::CompareChanged, &[&fields.join(", ")])
+        }
+        Some(Difference::OnlyLeft) => tr_args(Message::CompareMissingFrom, &[&right]),
+        Some(Difference::OnlyRight) => tr_args(Message::CompareMissingFrom, &[&left]),
+        None => tr(Message::CompareEmpty).to_owned(),
+    };
+    f.render_widget(
+        Paragraph::new(detail).style(Style::default().fg(Color::Reset)),
+        chunks[1],
+    );
+}
+
+fn draw_side(
+    f: &mut Frame,
+    layout_chunk: Rect,
+    title: &str,
+    rows: &[CompareRow],
+    selected: Option<usize>,
+    side: impl Fn(&CompareRow) -> &Option<serde_json::Value>,
+) {
+    let mut state = TableState::default();
+    state.select(selected);
+    let table = Table::new(
+        rows.iter().map(|row| {
+            let (text, color) = match (side(row), &row.difference) {
+                (None, _) => (tr(Message::CompareMissing).to_owned(), Color::Red),
+                (Some(_), Difference::Same) => (row.key.to_owned(), Color::Reset),
+                (Some(_), Difference::Changed(_)) => (row.key.to_owned(), Color::Yellow),
+                (Some(_), _) => (row.key.to_owned(), Color::Red),
+            };
+            Row::new([Cell::from(text)]).style(Style::default().fg(color))
+        }),
+        [Constraint::Percentage(100)],
+    )
+    .block(Block::default().title(Span::styled(title, get_color((true, false)))))
+    .row_highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    f.render_stateful_widget(table, layout_chunk, &mut state);
+}
@@ -1,29 +1,32 @@
-use mgwconf_network::mgw_configuration::InnerEntityTrait;
+use mgwconf_network::mgw_configuration::{
+    models::{ApiCredentialsEntity, CertificateEntity},
+    InnerEntityTrait,
+};
+use mgwconf_network::model::Masked;
 use mgwconf_network::AppConfig;
 use ratatui::widgets::Wrap;
 
-use crate::app::state::State;
+use crate::app::state::{list::Chip, State, TabId};
+use crate::event::keymap::Action;
+use crate::i18n::{tr, tr_args, Message};
+use crate::model::prelude::expiry;
+use crate::ui::fmt::FmtModel;
+use crate::ui::help::first_key;
 use crate::ui::prelude::*;
 
-pub const CONFIGURATION_USER_TAB: [&str; 6] = [
-    "Certificates",
-    "SAGs",
-    "Business Application",
-    "Profiles",
-    "Api Proxy",
-    "Forward Proxy",
-];
-
 pub fn draw_configuration_user_block<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
 where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
+    let tabs = (0..TabId::COUNT)
+        .map(|i| TabId::from(i).to_string())
+        .collect::<Vec<String>>();
     draw_selectable_list(
         f,
         layout_chunk,
         "",
-        &CONFIGURATION_USER_TAB,
+        &tabs,
         (true, true),
         Some(app.get_configuration_state().current_tab()),
         Borders::ALL,
@@ -35,11 +38,20 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
+    let title = match app.get_configuration_state().last_deleted() {
+        Some(deleted) => tr_args(
+            Message::UndoDelete,
+            &[
+                &app.get_configuration_state().current_selected(),
+                &first_key(app, Action::Undo),
+                &deleted.tab,
+                &deleted.summary,
+            ],
+        ),
+        None => app.get_configuration_state().current_selected().to_string(),
+    };
     let configuration = Block::default()
-        .title(Span::styled(
-            app.get_configuration_state().current_selected().to_string(),
-            Style::default(),
-        ))
+        .title(Span::styled(title, Style::default()))
         .borders(Borders::ALL)
         .title_alignment(Alignment::Left);
     f.render_widget(configuration, layout_chunk);
@@ -51,31 +63,93 @@ where
         3 => draw_configuration_profiles(f, app, area),
         4 => draw_configuration_api_proxies(f, app, area),
         5 => draw_configuration_forward_proxies(f, app, area),
+        6 => draw_configuration_api_client_credentials(f, app, area),
         _ => {}
     }
 }
 
-pub fn draw_error<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+/// Sort column, search and filter chips of the list, with their keys
+fn list_title<A, C>(app: &A) -> String
 where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let configuration = Block::default()
-        .title(Span::styled(
-            app.get_configuration_state().current_selected().to_string(),
-            Style::default(),
-        ))
-        .borders(Borders::ALL)
-        .title_alignment(Alignment::Left);
-    f.render_widget(configuration, layout_chunk);
-    let area = centered_rect(97, 50, layout_chunk);
-    let error = app.pop_error();
-    let paragraph = Paragraph::new(error.unwrap().root_cause().to_string())
-        .style(Style::default().bg(Color::Reset).fg(Color::Red))
-        .block(Block::default())
-        .alignment(Alignment::Left)
-        .wrap(Wrap { trim: true });
-    f.render_widget(paragraph, area);
+    let state = app.get_configuration_state();
+    let tab = state.current_selected();
+    let list = state.list();
+    let mut title = tr_args(
+        Message::Sort,
+        &[
+            &list.column_name(tab),
+            &tr(if list.descending {
+                Message::Descending
+            } else {
+                Message::Ascending
+            }),
+            &first_key(app, Action::NextSort),
+            &first_key(app, Action::ReverseSort),
+        ],
+    );
+    let search = first_key(app, Action::Search);
+    let search = if list.searching {
+        tr_args(Message::Searching, &[&list.search])
+    } else if !list.search.is_empty() {
+        tr_args(Message::Search, &[&list.search, &search])
+    } else {
+        tr_args(Message::SearchHint, &[&search])
+    };
+    title.push_str(&format!(" | {}", search));
+    for (i, chip) in Chip::for_tab(tab).iter().enumerate() {
+        let on = if list.chips.contains(chip) { "x" } else { " " };
+        title.push_str(&format!(" | [{}] {} {}", on, i + 1, chip.label()));
+    }
+    title
+}
+
+/// Table of the visible entities of the selected tab, only the rows in the viewport are built
+///
+/// The first row creates a new entity
+fn draw_entity_table<A, C, T, F>(
+    f: &mut Frame,
+    app: &A,
+    layout_chunk: Rect,
+    add: &str,
+    header: &[&str],
+    entities: Vec<&T>,
+    cells: F,
+) where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+    F: Fn(&T) -> Vec<String>,
+{
+    let state = app.get_configuration_state();
+    // Title and header lines
+    let height = layout_chunk.height.saturating_sub(2) as usize;
+    let total = entities.len() + 1;
+    let selected = state.current_pan().min(total - 1);
+    let offset = state.list().scroll(selected, height, total);
+    let rows = (offset..total.min(offset + height.max(1)))
+        .map(|i| match i {
+            0 => vec![add.to_owned()],
+            i => cells(entities[i - 1]),
+        })
+        .collect::<Vec<Vec<String>>>();
+    draw_selectable_table(
+        f,
+        layout_chunk,
+        &list_title(app),
+        header,
+        rows,
+        (true, true),
+        Some(selected - offset),
+    );
+}
+
+fn yes_no(value: Option<bool>) -> String {
+    match value {
+        Some(true) => tr(Message::Yes).to_owned(),
+        _ => tr(Message::No).to_owned(),
+    }
 }
 
 pub fn draw_configuration_sags<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
@@ -83,22 +157,29 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let sags = &app.get_configuration_state().sags;
-    let mut sags_str = Vec::new();
-    sags_str.push("Add SAG".to_owned());
-    sags_str.extend(
-        sags.iter()
-            .map(|s| s.hostname.to_owned())
-            .collect::<Vec<String>>(),
-    );
-    draw_selectable_list(
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &sags_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddSag),
+        &[
+            tr(Message::ColumnHost),
+            tr(Message::ColumnPort),
+            tr(Message::ColumnMessagePartner),
+            tr(Message::ColumnActive),
+            tr(Message::ColumnCertificateAlias),
+        ],
+        state.visible(&state.sags),
+        |s| {
+            vec![
+                s.hostname.to_owned(),
+                s.port.to_string(),
+                s.message_partner_name.clone().unwrap_or_default(),
+                yes_no(s.active),
+                s.public_certificate_alias.clone().unwrap_or_default(),
+            ]
+        },
     );
 }
 
@@ -107,23 +188,33 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let certificates = &app.get_configuration_state().certificates;
-    let mut certificates_str = Vec::new();
-    certificates_str.push("Add Certificate".to_owned());
-    certificates_str.extend(
-        certificates
-            .iter()
-            .map(|s| s.alias.to_owned())
-            .collect::<Vec<String>>(),
-    );
-    draw_selectable_list(
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &certificates_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddCertificate),
+        &[
+            tr(Message::ColumnAlias),
+            tr(Message::ColumnSubject),
+            tr(Message::ColumnExpiry),
+        ],
+        state.list().visible_rows(),
+        |row| {
+            let alias = row.fields["alias"].as_str().unwrap_or_default().to_owned();
+            match &row.certificate {
+                Some(certificate) => vec![
+                    alias,
+                    certificate.subject.to_owned(),
+                    format!(
+                        "{} ({})",
+                        certificate.not_after,
+                        expiry(certificate.days_left)
+                    ),
+                ],
+                None => vec![alias],
+            }
+        },
     );
 }
 
@@ -132,23 +223,23 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let business_applications = &app.get_configuration_state().business_applications;
-    let mut business_applications_str = Vec::new();
-    business_applications_str.push("Add Business Application".to_owned());
-    business_applications_str.extend(
-        business_applications
-            .iter()
-            .map(|s| s.application_name.to_owned())
-            .collect::<Vec<String>>(),
-    );
-    draw_selectable_list(
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &business_applications_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddBusinessApplication),
+        &[
+            tr(Message::ColumnApplication),
+            tr(Message::ColumnSharedSecret),
+        ],
+        state.visible(&state.business_applications),
+        |s| {
+            vec![
+                s.application_name.to_owned(),
+                yes_no(s.shared_secret.as_ref().map(|_| true)),
+            ]
+        },
     );
 }
 
@@ -157,23 +248,31 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let profiles = &app.get_configuration_state().profiles;
-    let mut profiles_str = Vec::new();
-    profiles_str.push("Add Profile".to_owned());
-    profiles_str.extend(
-        profiles
-            .iter()
-            .map(|s| s.profile_name.to_owned())
-            .collect::<Vec<String>>(),
-    );
-    draw_selectable_list(
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &profiles_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddProfile),
+        &[
+            tr(Message::ColumnApplication),
+            tr(Message::ColumnProfile),
+            tr(Message::ColumnScope),
+            tr(Message::ColumnDns),
+        ],
+        state.visible(&state.profiles),
+        |s| {
+            vec![
+                s.application_name.to_owned(),
+                s.profile_name.to_owned(),
+                s.rbac_scope.clone().unwrap_or_default(),
+                s.user_dns
+                    .as_ref()
+                    .map(Vec::len)
+                    .unwrap_or_default()
+                    .to_string(),
+            ]
+        },
     );
 }
 
@@ -182,27 +281,18 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let api_proxies = &app.get_configuration_state().apiproxy;
-    let mut api_proxies_str = Vec::new();
-    api_proxies_str.push("Add Api Proxy".to_owned());
-    api_proxies_str.extend(
-        api_proxies
-            .iter()
-            .map(
-                |s: &mgwconf_network::mgw_configuration::models::ApiGatewayInfoEntity| {
-                    s.environment.to_string()
-                },
-            )
-            .collect::<Vec<String>>(),
-    );
-    draw_selectable_list(
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &api_proxies_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddApiProxy),
+        &[
+            tr(Message::ColumnEnvironment),
+            tr(Message::ColumnCertificateAlias),
+        ],
+        state.visible(&state.apiproxy),
+        |s| vec![s.environment.to_string(), s.public_cert_alias.to_owned()],
     );
 }
 
@@ -211,27 +301,45 @@ where
     A: UiAppTrait<C>,
     C: AppConfig,
 {
-    let forward_proxies = &app.get_configuration_state().forwardproxy;
-    let mut forward_proxies_str = Vec::new();
-    forward_proxies_str.push("Add Forward Proxy".to_owned());
-    forward_proxies_str.extend(
-        forward_proxies
-            .iter()
-            .map(
-                |s: &mgwconf_network::mgw_configuration::models::ForwardProxyEntity| {
-                    s.hostname.to_owned()
-                },
-            )
-            .collect::<Vec<String>>(),
+    let state = app.get_configuration_state();
+    draw_entity_table(
+        f,
+        app,
+        layout_chunk,
+        tr(Message::AddForwardProxy),
+        &[
+            tr(Message::ColumnHost),
+            tr(Message::ColumnPort),
+            tr(Message::ColumnUser),
+        ],
+        state.visible(&state.forwardproxy),
+        |s| {
+            vec![
+                s.hostname.to_owned(),
+                s.port.to_string(),
+                s.user.clone().unwrap_or_default(),
+            ]
+        },
     );
-    draw_selectable_list(
+}
+
+pub fn draw_configuration_api_client_credentials<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let state = app.get_configuration_state();
+    draw_entity_table(
         f,
+        app,
         layout_chunk,
-        "",
-        &forward_proxies_str,
-        (true, true),
-        Some(app.get_configuration_state().current_pan()),
-        Borders::NONE,
+        tr(Message::AddApiClientCredential),
+        &[
+            tr(Message::ColumnApplication),
+            tr(Message::ColumnConsumerKey),
+        ],
+        state.visible(&state.api_client_credentials),
+        |s| vec![s.application_name.to_owned(), s.consumer_key.to_owned()],
     );
 }
 
@@ -251,7 +359,17 @@ where
         .title_alignment(Alignment::Left);
     f.render_widget(configuration, layout_chunk);
     let area = centered_rect(100, 95, layout_chunk);
-    let paragraph = Paragraph::new(entity.to_string())
+    let content = if let Some(certificate) = entity.as_any().downcast_ref::<CertificateEntity>() {
+        FmtModel::to_string(certificate)
+    } else {
+        match entity.as_any().downcast_ref::<ApiCredentialsEntity>() {
+            Some(credentials) if !app.get_configuration_state().secrets_visible() => {
+                credentials.masked().to_string()
+            }
+            _ => entity.to_string(),
+        }
+    };
+    let paragraph = Paragraph::new(content)
         .style(Style::default().bg(Color::Reset).fg(Color::White))
         .wrap(Wrap { trim: false });
     f.render_widget(paragraph, area);
@@ -0,0 +1,80 @@
+use mgwconf_network::AppConfig;
+use ratatui::{
+    style::Modifier,
+    text::Line,
+    widgets::{Clear, Wrap},
+};
+
+use crate::app::state::delete::LIVE_CONFIRMATION;
+use crate::i18n::{tr, tr_args, Message};
+use crate::ui::prelude::*;
+
+pub fn draw_delete_confirm<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(deletion) = app.get_configuration_state().deletion() else {
+        return;
+    };
+    let area = centered_rect(50, 40, layout_chunk);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            tr_args(Message::DeleteFrom, &[&deletion.tab]),
+            Style::default().fg(Color::Red),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let mut lines = vec![Line::from("")];
+    for (name, value) in deletion.summary.iter() {
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {} : ", name)),
+            Span::styled(
+                value.to_owned(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    if !deletion.is_undoable() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}",
+                tr_args(
+                    Message::DeleteNoUndo,
+                    &[&deletion.secrets_missing.join(", ")]
+                )
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(""));
+    }
+    if deletion.live {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}",
+                tr_args(
+                    Message::DeleteLive,
+                    &[&LIVE_CONFIRMATION, &LIVE_CONFIRMATION]
+                )
+            ),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Line::from(format!("  > {}_", deletion.typed)));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr(Message::DeleteLiveHint)),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr(Message::DeleteHint)),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
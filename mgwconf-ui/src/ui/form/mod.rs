@@ -0,0 +1,195 @@
+use mgwconf_network::AppConfig;
+use ratatui::{
+    style::Modifier,
+    text::Line,
+    widgets::{Clear, Wrap},
+};
+
+use crate::app::state::form::{FieldKind, FilePicker, FormField};
+use crate::event::keymap::Action;
+use crate::i18n::{tr, tr_args, Message};
+use crate::ui::help::first_key;
+use crate::ui::prelude::*;
+
+pub fn draw_form<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(form) = app.get_configuration_state().form() else {
+        return;
+    };
+    let editing = app.get_current_route().active_block == ActiveBlock::Editing;
+    let area = centered_rect(70, 80, layout_chunk);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            tr_args(
+                if form.is_edit() {
+                    Message::FormEdit
+                } else {
+                    Message::FormNew
+                },
+                &[&form.tab],
+            ),
+            get_color((true, true)),
+        ))
+        .borders(Borders::ALL)
+        .border_style(get_color((true, true)));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let mut lines = Vec::new();
+    for (i, field) in form.fields.iter().enumerate() {
+        let selected = i == form.selected;
+        let value = if selected && editing {
+            field_input(field, app.get_user_input())
+        } else {
+            field_value(field, selected)
+        };
+        let style = if selected {
+            get_color((true, true)).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{} {}{} : ",
+                    if selected { ">" } else { " " },
+                    field.label,
+                    if field.required { " *" } else { "" }
+                ),
+                style,
+            ),
+            Span::raw(value),
+        ]));
+        if let Some(error) = &field.error {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", error),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    lines.push(Line::from(""));
+    if form.review {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr(Message::FormChanges)),
+            get_color((true, true)),
+        )));
+        for (label, before, after) in form.diff() {
+            lines.push(Line::from(vec![
+                Span::raw(format!("    {} : ", label)),
+                Span::styled(before, Style::default().fg(Color::Red)),
+                Span::raw(" -> "),
+                Span::styled(after, Style::default().fg(Color::Green)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+    let button = match (form.is_edit(), form.review, form.sending) {
+        (_, _, true) => Message::FormSending,
+        (false, _, _) => Message::FormCreate,
+        (true, false, _) => Message::FormReview,
+        (true, true, _) => Message::FormConfirm,
+    };
+    lines.push(Line::from(Span::styled(
+        format!("  {}", tr(button)),
+        if form.is_submit_selected() {
+            get_color((true, true)).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default()
+        },
+    )));
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", error),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("  {}", tr(Message::FormRequired)),
+        Style::default().fg(Color::Gray),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {}",
+            tr_args(
+                Message::FormHint,
+                &[
+                    &first_key(app, Action::Up),
+                    &first_key(app, Action::Down),
+                    &first_key(app, Action::Select),
+                    &first_key(app, Action::Left),
+                    &first_key(app, Action::Right),
+                    &first_key(app, Action::Submit),
+                    &first_key(app, Action::Back),
+                ]
+            )
+        ),
+        Style::default().fg(Color::Gray),
+    )));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+
+    if let Some(picker) = &form.picker {
+        draw_file_picker(f, picker, centered_rect(80, 80, area));
+    }
+}
+
+/// Value of a field being edited, the caret follows the input buffer
+fn field_input(field: &FormField, input: &str) -> String {
+    let input = match field.kind {
+        FieldKind::Secret => "*".repeat(input.chars().count()),
+        _ => input.to_owned(),
+    };
+    match field.kind {
+        FieldKind::List if !field.items.is_empty() => {
+            format!("{} | {}_", field.items.join(" | "), input)
+        }
+        _ => format!("{}_", input),
+    }
+}
+
+fn field_value(field: &FormField, selected: bool) -> String {
+    match field.kind {
+        FieldKind::Secret => "*".repeat(field.value.chars().count()),
+        FieldKind::Boolean if field.value == "true" => String::from("[x]"),
+        FieldKind::Boolean => String::from("[ ]"),
+        FieldKind::Choice(_) => format!("< {} >", field.value),
+        FieldKind::List if field.items.is_empty() => tr(Message::FormAddItem).to_owned(),
+        FieldKind::List => field
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                if selected && i == field.item {
+                    format!("[{}]", item)
+                } else {
+                    item.to_owned()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" | "),
+        FieldKind::File if field.value.is_empty() => tr(Message::FormBrowse).to_owned(),
+        _ => field.value.to_owned(),
+    }
+}
+
+fn draw_file_picker(f: &mut Frame, picker: &FilePicker, area: Rect) {
+    f.render_widget(Clear, area);
+    let entries = picker
+        .entries
+        .iter()
+        .map(|entry| picker.label(entry))
+        .collect::<Vec<String>>();
+    draw_selectable_list(
+        f,
+        area,
+        &picker.dir.display().to_string(),
+        &entries,
+        (true, true),
+        Some(picker.selected),
+        Borders::ALL,
+    );
+}
@@ -0,0 +1,81 @@
+use mgwconf_network::AppConfig;
+use ratatui::{
+    style::Modifier,
+    widgets::{Cell, Clear, Row, Table},
+};
+
+use crate::event::keymap::{Action, Context};
+use crate::i18n::{tr, tr_args, Message};
+use crate::ui::prelude::*;
+
+/// Keys handled outside of the keymap, with the context they are shown in
+const FIXED_KEYS: [(Context, &str, Message); 4] = [
+    (Context::List, "1-9", Message::KeyToggleChip),
+    (Context::Form, "<Tab>", Message::KeyNextField),
+    (Context::Form, "<Space>", Message::KeyNextValue),
+    (
+        Context::Form,
+        "<Delete>, <Backspace>",
+        Message::KeyClearField,
+    ),
+];
+
+/// Keys of every action by context, from the keymap
+pub fn draw_help<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let keymap = app.keymap();
+    let mut rows = Vec::new();
+    for context in Context::ALL {
+        rows.push(
+            Row::new([Cell::from(context.to_string())])
+                .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+        );
+        for action in Action::ALL
+            .into_iter()
+            .filter(|action| action.context() == context)
+        {
+            let keys = keymap
+                .keys(action)
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            rows.push(Row::new([keys, action.description().to_owned()]));
+        }
+        for (_, keys, description) in FIXED_KEYS.iter().filter(|(c, _, _)| *c == context) {
+            rows.push(Row::new([*keys, tr(*description)]));
+        }
+    }
+
+    let area = centered_rect(70, 90, layout_chunk);
+    f.render_widget(Clear, area);
+    let table = Table::new(rows, [Constraint::Length(28), Constraint::Min(10)])
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    tr_args(Message::HelpTitle, &[&first_key(app, Action::Help)]),
+                    get_color((true, false)),
+                ))
+                .borders(Borders::ALL)
+                .border_style(get_color((true, false))),
+        )
+        .column_spacing(2)
+        .style(Style::default().fg(Color::Reset));
+    f.render_widget(table, area);
+}
+
+/// Key shown in the hints, the first one bound to the action
+pub fn first_key<A, C>(app: &A, action: Action) -> String
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    app.keymap()
+        .keys(action)
+        .first()
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
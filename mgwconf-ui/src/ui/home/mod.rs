@@ -1,5 +1,8 @@
 use mgwconf_network::AppConfig;
 
+use crate::event::keymap::Action;
+use crate::i18n::{tr, tr_args, Message};
+use crate::ui::help::first_key;
 use crate::ui::prelude::*;
 
 pub fn draw_home<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
@@ -9,27 +12,40 @@ where
 {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(100)].as_ref())
+        .constraints([Constraint::Length(8), Constraint::Min(3)].as_ref())
         .horizontal_margin(2)
         .vertical_margin(2)
         .split(layout_chunk);
 
     let welcome = Block::default()
-        .title(Span::styled("Home", Style::default()))
+        .title(Span::styled(tr(Message::Home), Style::default()))
         .title_alignment(Alignment::Left)
         .borders(Borders::ALL);
     let current_user = whoami::username();
+    let remote_addr = app
+        .gateways()
+        .get(app.active_gateway())
+        .map(|gateway| gateway.addr)
+        .unwrap_or_else(|| app.config().remote_addr());
+    let hint = if app.selected_gateway() != app.active_gateway() {
+        Message::HomeUseGateway
+    } else if !app.is_connected() {
+        Message::HomeRefresh
+    } else {
+        Message::HomeContinue
+    };
     let paragraph = Paragraph::new(format!(
-        "Utilisateur : {}\nRemote IP : {}\nRemote Port : {}\nConnectivity : {}\n\n\n{}",
-        current_user,
-        app.config().remote_ip(),
-        app.config().remote_port(),
-        if app.is_connected() { "OK" } else { "KO" },
-        if !app.is_connected() {
-            "Appuyer sur Entrer pour rafraichir"
-        } else {
-            "Appuyer sur Entrer pour continuer"
-        }
+        "{}\n\n{}",
+        tr_args(
+            Message::HomeSummary,
+            &[
+                &current_user,
+                &remote_addr.ip(),
+                &remote_addr.port(),
+                &if app.is_connected() { "OK" } else { "KO" },
+            ]
+        ),
+        tr_args(hint, &[&first_key(app, Action::Select)])
     ))
     .style(Style::default().bg(Color::Reset).fg(Color::White))
     .block(Block::default())
@@ -37,4 +53,52 @@ where
 
     f.render_widget(welcome, layout_chunk);
     f.render_widget(paragraph, chunks[0]);
+    draw_gateways(f, app, chunks[1]);
+}
+
+/// Configured gateways with their health, the active one is marked with `*`
+fn draw_gateways<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let rows = app
+        .gateways()
+        .iter()
+        .enumerate()
+        .map(|(i, gateway)| {
+            vec![
+                if i == app.active_gateway() { "*" } else { "" }.to_owned(),
+                gateway.name.to_owned(),
+                gateway.addr.to_string(),
+                gateway.health.to_string(),
+            ]
+        })
+        .collect();
+    let title = if app.gateways().len() > 1 {
+        tr_args(
+            Message::GatewaysHint,
+            &[
+                &first_key(app, Action::Up),
+                &first_key(app, Action::Down),
+                &first_key(app, Action::Compare),
+            ],
+        )
+    } else {
+        tr(Message::Gateways).to_owned()
+    };
+    draw_selectable_table(
+        f,
+        layout_chunk,
+        &title,
+        &[
+            "",
+            tr(Message::ColumnName),
+            tr(Message::ColumnAddress),
+            tr(Message::ColumnHealth),
+        ],
+        rows,
+        (true, false),
+        Some(app.selected_gateway()),
+    );
 }
@@ -5,15 +5,25 @@ use crate::app::UiAppTrait;
 use crate::ui::prelude::*;
 
 use crate::ui::{
+    compare::draw_compare,
     configuration::{draw_configuration, draw_configuration_user_block},
+    confirm::draw_delete_confirm,
+    form::draw_form,
+    help::draw_help,
     home::draw_home,
+    notification::{draw_notifications, draw_status, draw_toast},
 };
 
-use self::configuration::{draw_detailed_entity, draw_error};
+use self::configuration::draw_detailed_entity;
 
+pub mod compare;
 pub mod configuration;
+pub mod confirm;
 pub mod fmt;
+pub mod form;
+pub mod help;
 pub mod home;
+pub mod notification;
 pub mod prelude;
 pub mod utils;
 
@@ -30,9 +40,20 @@ where
 
     if app.get_current_route().id == RouteId::Home {
         draw_home(f, app, parent_layout[0]);
+    } else if app.get_current_route().id == RouteId::Compare {
+        draw_compare(f, app, parent_layout[0]);
+    } else if app.get_current_route().id == RouteId::Notifications {
+        draw_notifications(f, app, parent_layout[0]);
     } else {
         draw_routes(f, app, parent_layout[0]);
     }
+    if app.get_current_route().active_block == ActiveBlock::HelpMenu {
+        draw_help(f, app, parent_layout[0]);
+    }
+    draw_status(f, app, parent_layout[1]);
+    if app.get_current_route().id != RouteId::Notifications {
+        draw_toast(f, app, parent_layout[0]);
+    }
 }
 
 pub fn draw_routes<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
@@ -51,11 +72,6 @@ where
         (RouteId::Home, _) => {
             draw_home(f, app, chunks[1]);
         }
-        (RouteId::Configuration, ActiveBlock::Error) => {
-            if app.get_configuration_state().is_tab_selected() {
-                draw_error(f, app, layout_chunk);
-            }
-        }
         (RouteId::Configuration, ActiveBlock::Detailed) => {
             if app.get_configuration_state().is_tab_selected()
                 && app.get_configuration_state().selected_entity().is_some()
@@ -63,11 +79,25 @@ where
                 draw_detailed_entity(f, app, layout_chunk);
             }
         }
-        (RouteId::Configuration, ActiveBlock::TabSelected) => {
+        (RouteId::Configuration, ActiveBlock::TabSelected | ActiveBlock::HelpMenu) => {
+            if app.get_configuration_state().is_tab_selected() {
+                draw_configuration(f, app, chunks[1]);
+            }
+            draw_configuration_user_block(f, app, chunks[0]);
+        }
+        (RouteId::Configuration, ActiveBlock::Dialog | ActiveBlock::Editing) => {
+            if app.get_configuration_state().is_tab_selected() {
+                draw_configuration(f, app, chunks[1]);
+            }
+            draw_configuration_user_block(f, app, chunks[0]);
+            draw_form(f, app, layout_chunk);
+        }
+        (RouteId::Configuration, ActiveBlock::Confirm) => {
             if app.get_configuration_state().is_tab_selected() {
                 draw_configuration(f, app, chunks[1]);
             }
             draw_configuration_user_block(f, app, chunks[0]);
+            draw_delete_confirm(f, app, layout_chunk);
         }
         _ => {
             draw_configuration_user_block(f, app, chunks[0]);
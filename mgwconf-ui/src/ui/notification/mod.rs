@@ -0,0 +1,119 @@
+use mgwconf_network::AppConfig;
+use ratatui::{
+    style::Modifier,
+    widgets::{Clear, List, ListItem, ListState, Wrap},
+};
+
+use crate::app::state::notification::{Level, Notification};
+use crate::event::keymap::Action;
+use crate::i18n::{tr_args, Message};
+use crate::ui::help::first_key;
+use crate::ui::prelude::*;
+
+/// Widest toast, in columns
+const TOAST_WIDTH: u16 = 60;
+
+fn color(level: Level) -> Color {
+    match level {
+        Level::Info => Color::Reset,
+        Level::Success => Color::Green,
+        Level::Error => Color::Red,
+    }
+}
+
+fn line(notification: &Notification) -> String {
+    format!("{:>4}  {}", notification.age(), notification.message)
+}
+
+/// History of the notifications, newest first
+pub fn draw_notifications<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let notifications = app.get_notifications();
+    let items = notifications
+        .history()
+        .iter()
+        .map(|notification| {
+            ListItem::new(line(notification)).style(Style::default().fg(color(notification.level)))
+        })
+        .collect::<Vec<ListItem>>();
+    let mut state = ListState::default();
+    state.select((!items.is_empty()).then_some(notifications.selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    tr_args(
+                        Message::NotificationsTitle,
+                        &[
+                            &first_key(app, Action::Clear),
+                            &first_key(app, Action::Back),
+                        ],
+                    ),
+                    Style::default(),
+                ))
+                .borders(Borders::ALL),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    f.render_stateful_widget(list, layout_chunk, &mut state);
+}
+
+/// Last notification in the top right corner, for a few seconds
+pub fn draw_toast<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let Some(notification) = app.get_notifications().toast() else {
+        return;
+    };
+    let width = TOAST_WIDTH.min(layout_chunk.width);
+    let area = Rect {
+        x: layout_chunk.x + layout_chunk.width - width,
+        y: layout_chunk.y,
+        width,
+        height: 4.min(layout_chunk.height),
+    };
+    let style = Style::default().fg(color(notification.level));
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(notification.message.as_str())
+            .style(style)
+            .block(Block::default().borders(Borders::ALL).border_style(style))
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+/// Bottom line, with the keys of the help and of the notification history
+pub fn draw_status<A, C>(f: &mut Frame, app: &A, layout_chunk: Rect)
+where
+    A: UiAppTrait<C>,
+    C: AppConfig,
+{
+    let mut status = tr_args(Message::StatusHelp, &[&first_key(app, Action::Help)]);
+    let history = app.get_notifications().history();
+    if !history.is_empty() && app.get_current_route().id != RouteId::Notifications {
+        let errors = history
+            .iter()
+            .filter(|notification| notification.level == Level::Error)
+            .count();
+        status.push_str(&format!(
+            " | {}",
+            tr_args(
+                Message::StatusNotifications,
+                &[
+                    &first_key(app, Action::Notifications),
+                    &history.len(),
+                    &errors,
+                ],
+            )
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(status).style(Style::default().fg(Color::DarkGray)),
+        layout_chunk,
+    );
+}
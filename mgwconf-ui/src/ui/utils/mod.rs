@@ -2,12 +2,15 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::Span,
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, Cell, List, ListItem, ListState, Row, Table, TableState},
     Frame,
 };
 
 pub const SMALL_TERMINAL_HEIGHT: u16 = 45;
 
+/// Widest column of a table, longer values are cut by the terminal
+const MAX_COLUMN_WIDTH: usize = 48;
+
 pub fn draw_selectable_list<S>(
     f: &mut Frame,
     layout_chunk: Rect,
@@ -39,6 +42,58 @@ pub fn draw_selectable_list<S>(
     f.render_stateful_widget(list, layout_chunk, &mut state);
 }
 
+/// Borderless table of the rows in the viewport, `selected_index` is relative to the first
+/// given row
+///
+/// Columns are as wide as their widest shown value, the last one takes the remaining space
+pub fn draw_selectable_table(
+    f: &mut Frame,
+    layout_chunk: Rect,
+    title: &str,
+    header: &[&str],
+    rows: Vec<Vec<String>>,
+    highlight_state: (bool, bool),
+    selected_index: Option<usize>,
+) {
+    let mut state = TableState::default();
+    state.select(selected_index);
+
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let width = rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(name.chars().count()))
+                .max()
+                .unwrap_or_default()
+                .min(MAX_COLUMN_WIDTH);
+            if i + 1 == header.len() {
+                Constraint::Min(width as u16)
+            } else {
+                Constraint::Length(width as u16)
+            }
+        })
+        .collect::<Vec<Constraint>>();
+
+    let table = Table::new(
+        rows.into_iter()
+            .map(|row| Row::new(row.into_iter().map(Cell::from))),
+        widths,
+    )
+    .header(
+        Row::new(header.iter().map(|name| Cell::from(*name)))
+            .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+    )
+    .column_spacing(2)
+    .block(Block::default().title(Span::styled(title, get_color(highlight_state))))
+    .style(Style::default().fg(Color::Reset))
+    .row_highlight_style(get_color(highlight_state).add_modifier(Modifier::BOLD | Modifier::DIM));
+    f.render_stateful_widget(table, layout_chunk, &mut state);
+}
+
 /// helper function to get the color based on active or hovered
 ///
 /// * _ (bool, bool) : (is_active, is_hovered)
@@ -0,0 +1,346 @@
+use std::{fmt::Debug, process::Command, sync::Arc};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{prompt::PromptBackend, SecretType, VaultLocation};
+
+/// Where the API keys of a gateway are read from
+pub trait SecretBackend: Debug + Send + Sync {
+    /// Name shown in logs and errors
+    fn name(&self) -> &'static str;
+
+    fn read_secret(&self, stype: SecretType) -> Result<String>;
+
+    /// Store a secret, used by `--create_secret`
+    fn write_secret(&self, _stype: SecretType, _value: &str) -> Result<()> {
+        bail!("The {} secret backend is read only", self.name())
+    }
+}
+
+/// Secret backends selectable at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretSource {
+    /// Ask every API key on stdin
+    Prompt,
+    /// Files encrypted with the master key
+    Store,
+    /// `MGWC_<TYPE>_API_KEY` environment variables
+    Env,
+    /// Keyring of the operating system
+    Keyring,
+    /// Standard output of an external command
+    Command,
+}
+
+impl Default for SecretSource {
+    /// The encrypted store when built with it, as previous versions did
+    fn default() -> Self {
+        if cfg!(feature = "store") {
+            SecretSource::Store
+        } else {
+            SecretSource::Prompt
+        }
+    }
+}
+
+impl std::fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretSource::Prompt => write!(f, "prompt"),
+            SecretSource::Store => write!(f, "store"),
+            SecretSource::Env => write!(f, "env"),
+            SecretSource::Keyring => write!(f, "keyring"),
+            SecretSource::Command => write!(f, "command"),
+        }
+    }
+}
+
+impl std::str::FromStr for SecretSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            SecretSource::Prompt,
+            SecretSource::Store,
+            SecretSource::Env,
+            SecretSource::Keyring,
+            SecretSource::Command,
+        ]
+        .into_iter()
+        .find(|source| source.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| {
+            format!("{s} is not a secret backend, expected prompt, store, env, keyring or command")
+        })
+    }
+}
+
+impl SecretSource {
+    /// Only the encrypted store is opened with the master key
+    pub fn needs_master_key(&self) -> bool {
+        matches!(self, SecretSource::Store)
+    }
+
+    /// `command` is the command line of the command backend, see `CommandBackend`
+    pub fn open(
+        &self,
+        master: &str,
+        location: &VaultLocation,
+        command: Option<&str>,
+    ) -> Result<Arc<dyn SecretBackend>> {
+        Ok(match self {
+            SecretSource::Prompt => Arc::new(PromptBackend),
+            #[cfg(feature = "store")]
+            SecretSource::Store => Arc::new(crate::FileStore::new(master, location.clone())?),
+            #[cfg(not(feature = "store"))]
+            SecretSource::Store => {
+                let _ = master;
+                bail!("The store secret backend needs the store feature")
+            }
+            SecretSource::Env => Arc::new(EnvBackend),
+            #[cfg(feature = "keyring")]
+            SecretSource::Keyring => Arc::new(KeyringBackend::new(location)),
+            #[cfg(not(feature = "keyring"))]
+            SecretSource::Keyring => bail!("The keyring secret backend needs the keyring feature"),
+            SecretSource::Command => Arc::new(CommandBackend::new(
+                command.ok_or_else(|| anyhow!("The command secret backend needs a command"))?,
+                location,
+            )),
+        })
+    }
+}
+
+/// Reads `MGWC_CONFIGURATION_API_KEY`, `MGWC_MONITORING_API_KEY`, `MGWC_MANAGEMENT_API_KEY`
+/// and `MGWC_ENCRYPT_API_KEY`, meant for CI jobs
+#[derive(Debug, Clone, Default)]
+pub struct EnvBackend;
+
+impl EnvBackend {
+    pub fn variable(stype: SecretType) -> String {
+        format!("MGWC_{}_API_KEY", stype.to_string().to_uppercase())
+    }
+}
+
+impl SecretBackend for EnvBackend {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn read_secret(&self, stype: SecretType) -> Result<String> {
+        match std::env::var(Self::variable(stype)) {
+            Ok(value) if !value.is_empty() => Ok(value),
+            Ok(_) => bail!("{} is empty", Self::variable(stype)),
+            Err(e) => bail!("{} : {}", Self::variable(stype), e),
+        }
+    }
+}
+
+/// Runs a command and reads the secret from the first line of its standard output
+///
+/// `{type}` and `{namespace}` are replaced in the command line, such as
+/// `pass show mgwconf/{namespace}/{type}`, they are also given as `MGWC_SECRET_TYPE` and
+/// `MGWC_VAULT_NAMESPACE` environment variables
+#[derive(Debug, Clone)]
+pub struct CommandBackend {
+    command: String,
+    namespace: String,
+}
+
+impl CommandBackend {
+    pub fn new(command: &str, location: &VaultLocation) -> CommandBackend {
+        CommandBackend {
+            command: command.to_owned(),
+            namespace: location.namespace().unwrap_or("default").to_owned(),
+        }
+    }
+
+    fn command(&self, stype: SecretType) -> Command {
+        let stype = stype.to_string().to_lowercase();
+        let line = self
+            .command
+            .replace("{type}", &stype)
+            .replace("{namespace}", &self.namespace);
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        command
+            .arg(line)
+            .env("MGWC_SECRET_TYPE", stype)
+            .env("MGWC_VAULT_NAMESPACE", &self.namespace);
+        command
+    }
+}
+
+impl SecretBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn read_secret(&self, stype: SecretType) -> Result<String> {
+        let output = self
+            .command(stype)
+            .output()
+            .map_err(|e| anyhow!("Cannot run {} : {}", self.command, e))?;
+        if !output.status.success() {
+            bail!(
+                "{} exited with {} : {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stdout = String::from_utf8(output.stdout)?;
+        match stdout.lines().next() {
+            Some(value) if !value.is_empty() => Ok(value.to_owned()),
+            _ => bail!("{} printed no {} secret", self.command, stype),
+        }
+    }
+}
+
+/// Secret Service on Linux, Keychain on macOS and Credential Manager on Windows
+///
+/// Secrets are stored under the `mgwconf` service, as `<namespace>/<type>` entries
+#[cfg(feature = "keyring")]
+#[derive(Debug, Clone)]
+pub struct KeyringBackend {
+    namespace: String,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringBackend {
+    const SERVICE: &'static str = "mgwconf";
+
+    pub fn new(location: &VaultLocation) -> KeyringBackend {
+        KeyringBackend {
+            namespace: location.namespace().unwrap_or("default").to_owned(),
+        }
+    }
+
+    /// The keyring client blocks on its own runtime, it must not run on a tokio worker
+    fn with_entry<T: Send>(
+        &self,
+        stype: SecretType,
+        f: impl FnOnce(&keyring::Entry) -> keyring::Result<T> + Send,
+    ) -> Result<T> {
+        let user = format!("{}/{}", self.namespace, stype.to_string().to_lowercase());
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| keyring::Entry::new(Self::SERVICE, &user).and_then(|e| f(&e)))
+                .join()
+                .map_err(|_| anyhow!("Keyring access to {} panicked", user))?
+                .map_err(|e| anyhow!("Keyring entry {} : {}", user, e))
+        })
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl SecretBackend for KeyringBackend {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn read_secret(&self, stype: SecretType) -> Result<String> {
+        self.with_entry(stype, |entry| entry.get_password())
+    }
+
+    fn write_secret(&self, stype: SecretType, value: &str) -> Result<()> {
+        self.with_entry(stype, |entry| entry.set_password(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_source_from_str() {
+        for source in [
+            SecretSource::Prompt,
+            SecretSource::Store,
+            SecretSource::Env,
+            SecretSource::Keyring,
+            SecretSource::Command,
+        ] {
+            assert_eq!(source.to_string().parse::<SecretSource>(), Ok(source));
+        }
+        assert_eq!("ENV".parse::<SecretSource>(), Ok(SecretSource::Env));
+        assert_eq!(
+            "vault".parse::<SecretSource>(),
+            Err(
+                "vault is not a secret backend, expected prompt, store, env, keyring or command"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn env_backend() {
+        // The only test reading these variables, they are not shared with other tests
+        let variable = EnvBackend::variable(SecretType::Monitoring);
+        assert_eq!(variable, "MGWC_MONITORING_API_KEY");
+        std::env::set_var(&variable, "monitoring-key");
+        assert_eq!(
+            EnvBackend.read_secret(SecretType::Monitoring).unwrap(),
+            "monitoring-key"
+        );
+        std::env::set_var(&variable, "");
+        assert_eq!(
+            EnvBackend
+                .read_secret(SecretType::Monitoring)
+                .unwrap_err()
+                .to_string(),
+            "MGWC_MONITORING_API_KEY is empty"
+        );
+        std::env::remove_var(&variable);
+        assert!(EnvBackend.read_secret(SecretType::Monitoring).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_backend_substitutes_type_and_namespace() {
+        let location = VaultLocation::new(None, Some("mgw:9003"));
+        let backend = CommandBackend::new(
+            "echo {type}@{namespace} $MGWC_SECRET_TYPE; echo second line",
+            &location,
+        );
+        assert_eq!(
+            backend.read_secret(SecretType::Encrypt).unwrap(),
+            "encrypt@mgw_9003 encrypt"
+        );
+        let backend = CommandBackend::new("echo {namespace}", &VaultLocation::new(None, None));
+        assert_eq!(
+            backend.read_secret(SecretType::Configuration).unwrap(),
+            "default"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_backend_fails_on_non_zero_exit() {
+        let backend = CommandBackend::new(
+            "echo partial; echo locked >&2; exit 3",
+            &VaultLocation::default(),
+        );
+        let error = backend
+            .read_secret(SecretType::Configuration)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "echo partial; echo locked >&2; exit 3 exited with exit status: 3 : locked"
+        );
+        let backend = CommandBackend::new("true", &VaultLocation::default());
+        assert_eq!(
+            backend
+                .read_secret(SecretType::Configuration)
+                .unwrap_err()
+                .to_string(),
+            "true printed no Configuration secret"
+        );
+    }
+}
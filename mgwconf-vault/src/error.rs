@@ -12,6 +12,10 @@ pub enum VaultError {
     FromHexError(hex::FromHexError),
     Argon2Error(argon2::Error),
     MasterPasswordVerifyError,
+    /// The file is not a vault file
+    Corrupted(String),
+    /// The file was written by a newer version
+    UnsupportedVersion(u8),
 }
 
 impl From<argon2::Error> for VaultError {
@@ -70,6 +74,10 @@ impl fmt::Display for VaultError {
                 "Master password verify",
                 String::from("Can't verify master password"),
             ),
+            VaultError::Corrupted(e) => ("format", e.to_owned()),
+            VaultError::UnsupportedVersion(v) => {
+                ("format", format!("Unsupported vault format version {}", v))
+            }
         };
         write!(f, "error in {}: {} (This is usually caused by a wrong master key being entered or the vault file cannot be read)", module, e)
     }
@@ -86,6 +94,7 @@ impl error::Error for VaultError {
             VaultError::FromHexError(e) => e,
             VaultError::PadError(_) => return None,
             VaultError::MasterPasswordVerifyError => return None,
+            VaultError::Corrupted(_) | VaultError::UnsupportedVersion(_) => return None,
         })
     }
 }
@@ -0,0 +1,260 @@
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::{Config, Variant, Version};
+use rand::Rng;
+
+use crate::{error::VaultError, SecretType};
+
+/// Leading bytes of the vault files written since the format version 2
+const MAGIC: &[u8; 4] = b"MGWV";
+pub(crate) const VERSION: u8 = 2;
+pub(crate) const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+/// Argon2id parameters deriving the key of a vault file, stored in its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory in KiB
+    pub mem_cost: u32,
+    pub time_cost: u32,
+    pub lanes: u32,
+}
+
+impl Default for KdfParams {
+    /// RFC 9106 parameters for memory constrained environments
+    fn default() -> Self {
+        let config = Config::rfc9106_low_mem();
+        KdfParams {
+            mem_cost: config.mem_cost,
+            time_cost: config.time_cost,
+            lanes: config.lanes,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Parameters of the files written before the format version 2
+    pub(crate) fn legacy() -> KdfParams {
+        let config = Config::owasp5();
+        KdfParams {
+            mem_cost: config.mem_cost,
+            time_cost: config.time_cost,
+            lanes: config.lanes,
+        }
+    }
+
+    pub(crate) fn derive(&self, master: &str, salt: &[u8]) -> Result<[u8; 32], VaultError> {
+        // Parameters come from a file, they must not exhaust the memory of the workstation
+        if self.mem_cost > 4 * 1024 * 1024 || self.time_cost > 64 || self.lanes > 64 {
+            return Err(VaultError::Corrupted(format!(
+                "Argon2 parameters {:?} are out of bounds",
+                self
+            )));
+        }
+        let config = Config {
+            hash_length: 32,
+            lanes: self.lanes,
+            mem_cost: self.mem_cost,
+            time_cost: self.time_cost,
+            variant: Variant::Argon2id,
+            version: Version::Version13,
+            ..Default::default()
+        };
+        let mut hash = argon2::hash_raw(master.as_bytes(), salt, &config)?;
+        let key = hash[..32]
+            .try_into()
+            .map_err(|_| VaultError::Corrupted(String::from("Derived key is too short")))?;
+        zeroize::Zeroize::zeroize(&mut hash);
+        Ok(key)
+    }
+}
+
+/// A secret encrypted with AES-256-GCM
+///
+/// Layout: magic, version, Argon2 memory, time and lanes as little endian u32, salt, nonce,
+/// then the ciphertext and its tag. The header and the secret type are authenticated
+#[derive(Debug, Clone)]
+pub(crate) struct VaultFile {
+    pub params: KdfParams,
+    pub salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+impl VaultFile {
+    pub fn seal(
+        key: &[u8; 32],
+        params: KdfParams,
+        salt: [u8; SALT_LEN],
+        stype: SecretType,
+        secret: &[u8],
+    ) -> Result<VaultFile, VaultError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill(&mut nonce);
+        let mut file = VaultFile {
+            params,
+            salt,
+            nonce,
+            ciphertext: Vec::new(),
+        };
+        let aad = file.aad(stype);
+        file.ciphertext = Aes256Gcm::new(key.into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| VaultError::Corrupted(String::from("Encryption failed")))?;
+        Ok(file)
+    }
+
+    /// A wrong key, a modified file or a file of another secret type are not told apart
+    pub fn open(&self, key: &[u8; 32], stype: SecretType) -> Result<Vec<u8>, VaultError> {
+        Aes256Gcm::new(key.into())
+            .decrypt(
+                Nonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.aad(stype),
+                },
+            )
+            .map_err(|_| VaultError::MasterPasswordVerifyError)
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&self.params.mem_cost.to_le_bytes());
+        header.extend_from_slice(&self.params.time_cost.to_le_bytes());
+        header.extend_from_slice(&self.params.lanes.to_le_bytes());
+        header.extend_from_slice(&self.salt);
+        header.extend_from_slice(&self.nonce);
+        header
+    }
+
+    fn aad(&self, stype: SecretType) -> Vec<u8> {
+        [self.header(), stype.to_string().into_bytes()].concat()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        [self.header(), self.ciphertext.clone()].concat()
+    }
+
+    /// `None` for a file written before the format version 2
+    pub fn decode(buf: &[u8]) -> Result<Option<VaultFile>, VaultError> {
+        if !buf.starts_with(MAGIC) {
+            return Ok(None);
+        }
+        if buf.len() < HEADER_LEN {
+            return Err(VaultError::Corrupted(String::from("Header is truncated")));
+        }
+        let version = buf[MAGIC.len()];
+        if version != VERSION {
+            return Err(VaultError::UnsupportedVersion(version));
+        }
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap_or_default())
+        };
+        let params_start = MAGIC.len() + 1;
+        let salt_start = params_start + 12;
+        let nonce_start = salt_start + SALT_LEN;
+        Ok(Some(VaultFile {
+            params: KdfParams {
+                mem_cost: u32_at(params_start),
+                time_cost: u32_at(params_start + 4),
+                lanes: u32_at(params_start + 8),
+            },
+            salt: buf[salt_start..nonce_start].try_into().unwrap_or_default(),
+            nonce: buf[nonce_start..HEADER_LEN].try_into().unwrap_or_default(),
+            ciphertext: buf[HEADER_LEN..].to_vec(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn sealed(stype: SecretType) -> VaultFile {
+        VaultFile::seal(&KEY, KdfParams::default(), [3; SALT_LEN], stype, b"api key").unwrap()
+    }
+
+    #[test]
+    fn sealed_secret_is_opened_after_encoding() {
+        let encoded = sealed(SecretType::Configuration).encode();
+        assert!(encoded.starts_with(MAGIC));
+        assert_eq!(encoded[MAGIC.len()], VERSION);
+
+        let file = VaultFile::decode(&encoded).unwrap().unwrap();
+        assert_eq!(file.params, KdfParams::default());
+        assert_eq!(file.salt, [3; SALT_LEN]);
+        assert_eq!(
+            file.open(&KEY, SecretType::Configuration).unwrap(),
+            b"api key"
+        );
+    }
+
+    #[test]
+    fn tampered_file_is_rejected() {
+        let encoded = sealed(SecretType::Configuration).encode();
+        // A ciphertext byte, then a KDF parameter of the authenticated header
+        for offset in [encoded.len() - 1, MAGIC.len() + 1] {
+            let mut tampered = encoded.clone();
+            tampered[offset] ^= 1;
+            let file = VaultFile::decode(&tampered).unwrap().unwrap();
+            assert!(matches!(
+                file.open(&KEY, SecretType::Configuration),
+                Err(VaultError::MasterPasswordVerifyError)
+            ));
+        }
+    }
+
+    #[test]
+    fn file_of_another_secret_type_is_rejected() {
+        assert!(matches!(
+            sealed(SecretType::Configuration).open(&KEY, SecretType::Management),
+            Err(VaultError::MasterPasswordVerifyError)
+        ));
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        assert!(matches!(
+            sealed(SecretType::Configuration).open(&[8; 32], SecretType::Configuration),
+            Err(VaultError::MasterPasswordVerifyError)
+        ));
+    }
+
+    #[test]
+    fn headers_are_checked() {
+        assert!(VaultFile::decode(&[0; 112]).unwrap().is_none());
+        assert!(matches!(
+            VaultFile::decode(b"MGWV\x02"),
+            Err(VaultError::Corrupted(_))
+        ));
+        let mut encoded = sealed(SecretType::Configuration).encode();
+        encoded[MAGIC.len()] = 3;
+        assert!(matches!(
+            VaultFile::decode(&encoded),
+            Err(VaultError::UnsupportedVersion(3))
+        ));
+    }
+
+    #[test]
+    fn out_of_bounds_parameters_are_refused() {
+        let params = KdfParams {
+            mem_cost: u32::MAX,
+            ..KdfParams::default()
+        };
+        assert!(matches!(
+            params.derive("master", &[0; SALT_LEN]),
+            Err(VaultError::Corrupted(_))
+        ));
+    }
+}
@@ -1,14 +1,27 @@
-use std::slice::Iter;
+use std::{slice::Iter, sync::Arc};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+#[cfg(feature = "keyring")]
+pub use backend::KeyringBackend;
+pub use backend::{CommandBackend, EnvBackend, SecretBackend, SecretSource};
+#[cfg(feature = "store")]
+pub use format::KdfParams;
+pub use location::VaultLocation;
+pub use prompt::PromptBackend;
+#[cfg(feature = "store")]
+pub use store::{FileStore, SecretState, SecretStatus};
+
+mod backend;
 #[cfg(feature = "store")]
 mod error;
-#[cfg(not(feature = "store"))]
+#[cfg(feature = "store")]
+mod format;
+mod location;
 mod prompt;
 #[cfg(feature = "store")]
 mod store;
 
-#[derive(Copy, Clone, Debug, Default, Zeroize)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Zeroize)]
 pub enum SecretType {
     #[default]
     Configuration,
@@ -28,6 +41,19 @@ impl std::fmt::Display for SecretType {
     }
 }
 
+impl std::str::FromStr for SecretType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SecretType::iterator()
+            .find(|stype| stype.to_string().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                format!("{s} is not a secret type, expected configuration, monitoring, management or encrypt")
+            })
+    }
+}
+
 impl SecretType {
     pub fn iterator() -> Iter<'static, SecretType> {
         static SECRETTYPES: [SecretType; 4] = [
@@ -40,7 +66,7 @@ impl SecretType {
     }
 }
 
-#[cfg(not(feature = "store"))]
+/// Secrets of the gateway, read once from a `SecretBackend`
 #[derive(Default, Debug, Zeroize, ZeroizeOnDrop, Clone)]
 pub struct SecretsVault {
     configuration: String,
@@ -48,21 +74,63 @@ pub struct SecretsVault {
     management: String,
     encrypt: String,
 
+    #[zeroize(skip)]
+    backend: Option<Arc<dyn SecretBackend>>,
     initialized: bool,
 }
 
-#[cfg(feature = "store")]
-#[derive(Default, Debug, Zeroize, ZeroizeOnDrop, Clone)]
-pub struct SecretsVault {
-    configuration: String,
-    monitoring: String,
-    management: String,
-    encrypt: String,
+impl SecretsVault {
+    pub fn new(backend: Arc<dyn SecretBackend>) -> SecretsVault {
+        SecretsVault {
+            configuration: String::new(),
+            monitoring: String::new(),
+            management: String::new(),
+            encrypt: String::new(),
+            backend: Some(backend),
+            initialized: false,
+        }
+    }
 
-    key: [u8; 32],
-    key_salt: [u8; 32],
-    pt_len: usize,
-    master: String,
+    /// Read all secrets from the backend
+    /// After this function execution, Self will contains all secrets
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if one of secrets can't be read
+    pub fn read_all_secrets(&mut self) {
+        let backend = self
+            .backend
+            .take()
+            .expect("Vault secrets have already been read");
+        for stype in SecretType::iterator() {
+            let value = backend.read_secret(*stype).unwrap_or_else(|e| {
+                panic!("Can't read {stype} secret from {} : {e}", backend.name())
+            });
+            match stype {
+                SecretType::Configuration => self.configuration = value,
+                SecretType::Monitoring => self.monitoring = value,
+                SecretType::Management => self.management = value,
+                SecretType::Encrypt => self.encrypt = value,
+            }
+        }
+        self.initialized = true;
+        // The backend, and the master key of the store, is dropped there
+    }
 
-    initialized: bool,
+    /// Get the current `SecretType` stored in the Vault
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the current Vault is not initialized correctly
+    pub fn get_secret(&self, stype: SecretType) -> &str {
+        if !self.initialized {
+            panic!("Vault has not yet been initilized");
+        }
+        match stype {
+            SecretType::Configuration => &self.configuration,
+            SecretType::Monitoring => &self.monitoring,
+            SecretType::Management => &self.management,
+            SecretType::Encrypt => &self.encrypt,
+        }
+    }
 }
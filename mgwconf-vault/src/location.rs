@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+use crate::SecretType;
+
+/// Directory of the legacy vault, relative to the working directory
+const LEGACY_DIR: &str = "./vault";
+
+/// Where vault files are stored, each gateway may use its own namespace with its own API keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultLocation {
+    dir: PathBuf,
+    namespace: Option<String>,
+}
+
+impl Default for VaultLocation {
+    fn default() -> Self {
+        VaultLocation::new(None, None)
+    }
+}
+
+impl VaultLocation {
+    /// `dir` defaults to `default_dir()`, `namespace` is reduced to a single path component
+    pub fn new(dir: Option<PathBuf>, namespace: Option<&str>) -> VaultLocation {
+        VaultLocation {
+            dir: dir.unwrap_or_else(Self::default_dir),
+            namespace: namespace.map(sanitize),
+        }
+    }
+
+    /// `mgwconf/vault` in the user data directory, `~/.local/share` on Linux
+    ///
+    /// A vault created in `./vault` by previous versions is still used until it is moved
+    pub fn default_dir() -> PathBuf {
+        let dir = dirs::data_dir()
+            .map(|dir| dir.join("mgwconf").join("vault"))
+            .unwrap_or_else(|| PathBuf::from(LEGACY_DIR));
+        let legacy = Path::new(LEGACY_DIR);
+        if !dir.is_dir() && legacy.is_dir() {
+            log::warn!("Using legacy vault {:?}, move it to {:?}", legacy, dir);
+            return legacy.to_path_buf();
+        }
+        dir
+    }
+
+    /// Directory holding the files of the namespace
+    pub fn dir(&self) -> PathBuf {
+        match &self.namespace {
+            Some(namespace) => self.dir.join(namespace),
+            None => self.dir.clone(),
+        }
+    }
+
+    /// Sanitized namespace, when one is set
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    pub fn file(&self, stype: SecretType) -> PathBuf {
+        self.dir().join(format!("vault.{}", stype))
+    }
+}
+
+/// Gateway names such as `mgw.example.com:9003` become `mgw.example.com_9003`
+fn sanitize(namespace: &str) -> String {
+    let namespace = namespace
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || ['-', '_', '.'].contains(&c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if namespace.chars().all(|c| c == '.') {
+        namespace.replace('.', "_")
+    } else {
+        namespace
+    }
+}
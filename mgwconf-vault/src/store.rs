@@ -1,131 +1,465 @@
 use aes::cipher::block_padding::Pkcs7;
 use aes::cipher::generic_array::GenericArray;
-use aes::cipher::{typenum, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
-use argon2::Config;
+use aes::cipher::{typenum, BlockDecryptMut, KeyIvInit};
 use base64::engine::general_purpose;
 use base64::Engine;
 use rand::Rng;
-use std::fs::{create_dir, File};
-use std::io::Write;
-use std::path::Path;
-use zeroize::Zeroize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use crate::backend::SecretBackend;
+use crate::format::{KdfParams, VaultFile, SALT_LEN, VERSION};
 use crate::SecretType;
-use crate::{error::VaultError, SecretsVault};
+use crate::{error::VaultError, VaultLocation};
 
-type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
 type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 
-fn generate_hash(s: &str) -> Result<(Vec<u8>, [u8; 32]), VaultError> {
-    let mut rng = rand::thread_rng();
-    let mut salt: [u8; 32] = [0; 32];
-    salt.iter_mut().for_each(|s| *s = rng.gen());
-    let config = { Config::owasp5() };
-    Ok((argon2::hash_raw(s.as_bytes(), &salt, &config)?, salt))
+/// Format of the file of a secret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretState {
+    Missing,
+    /// Written before the format version 2, migrated when read
+    Legacy,
+    Version(u8),
+    Invalid(String),
 }
 
-impl SecretsVault {
-    pub fn new(master: &str) -> Result<SecretsVault, VaultError> {
-        let (hash, salt) = generate_hash(master)?;
-        let key: [u8; 32] = hash[..32].try_into().unwrap();
+impl std::fmt::Display for SecretState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretState::Missing => write!(f, "missing"),
+            SecretState::Legacy => write!(f, "legacy format"),
+            SecretState::Version(v) => write!(f, "format v{}", v),
+            SecretState::Invalid(e) => write!(f, "invalid : {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretStatus {
+    pub stype: SecretType,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub state: SecretState,
+}
+
+/// Legacy files hold a 16 bytes IV, a 32 bytes salt and 64 bytes of AES-256-CBC ciphertext
+const LEGACY_LEN: usize = 16 + 32 + 64;
+/// Length of the base64 plaintext of legacy files
+const LEGACY_PT_LEN: usize = 48;
+
+/// Write `content` to a temporary file readable by its owner only, then rename it over `path`
+///
+/// A crash during the write leaves the previous vault file untouched
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), VaultError> {
+    let tmp = write_temp(path, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Temporary file next to `path`, readable by its owner only, holding `content`
+fn write_temp(path: &Path, content: &[u8]) -> Result<PathBuf, VaultError> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    if !dir.is_dir() {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(dir)?;
+    }
+    let tmp = dir.join(format!(
+        ".{}.tmp",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let res = options.open(&tmp).and_then(|mut output: File| {
+        output.write_all(content)?;
+        output.sync_all()
+    });
+    if let Err(e) = res {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(tmp)
+}
+
+/// Secrets encrypted with a key derived from the master key, one file per secret type
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct FileStore {
+    key: [u8; 32],
+    key_salt: [u8; 32],
+    #[zeroize(skip)]
+    kdf: KdfParams,
+    master: String,
+    #[zeroize(skip)]
+    location: VaultLocation,
+}
+
+impl std::fmt::Debug for FileStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileStore")
+            .field("kdf", &self.kdf)
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
 
-        Ok(SecretsVault {
+impl SecretBackend for FileStore {
+    fn name(&self) -> &'static str {
+        "store"
+    }
+
+    fn read_secret(&self, stype: SecretType) -> anyhow::Result<String> {
+        Ok(self.read_secret_from_file(stype)?)
+    }
+
+    fn write_secret(&self, stype: SecretType, value: &str) -> anyhow::Result<()> {
+        Ok(self.create_secret(stype, value.to_owned())?)
+    }
+}
+
+impl FileStore {
+    pub fn new(master: &str, location: VaultLocation) -> Result<FileStore, VaultError> {
+        let kdf = KdfParams::default();
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill(&mut salt);
+        let key = kdf.derive(master, &salt)?;
+
+        Ok(FileStore {
             key,
-            pt_len: 48,
             key_salt: salt,
+            kdf,
             master: String::from(master),
-            configuration: String::new(),
-            monitoring: String::new(),
-            management: String::new(),
-            encrypt: String::new(),
-            ..Default::default()
+            location,
         })
     }
 
-    pub fn create_secret(&self, stype: SecretType, mut value: String) -> Result<(), VaultError> {
-        while value.len() < 36 {
-            value += "0";
+    pub fn create_secret(&self, stype: SecretType, value: String) -> Result<(), VaultError> {
+        let file = VaultFile::seal(&self.key, self.kdf, self.key_salt, stype, value.as_bytes())?;
+        write_atomic(&self.location.file(stype), &file.encode())
+    }
+
+    /// Legacy files are migrated to the current format once read
+    pub fn read_secret_from_file(&self, stype: SecretType) -> Result<String, VaultError> {
+        let (value, legacy) = self.decrypt_file(stype)?;
+        if legacy {
+            log::info!("Migrating vault {} to format version {}", stype, VERSION);
+            self.create_secret(stype, value.clone())?;
         }
-        let binding = general_purpose::STANDARD.encode(value.as_bytes());
-        let text = binding.as_bytes();
-
-        let mut buf = [0u8; 64];
-        buf[..self.pt_len].copy_from_slice(text);
-        let mut rng = rand::thread_rng();
-        let mut iv: [u8; 16] = [0; 16];
-        for x in iv.iter_mut() {
-            *x = rng.gen();
+        Ok(value)
+    }
+
+    /// Secret stored for `stype`, and whether its file uses the legacy format
+    fn decrypt_file(&self, stype: SecretType) -> Result<(String, bool), VaultError> {
+        let buf = fs::read(self.location.file(stype))?;
+        match VaultFile::decode(&buf)? {
+            Some(file) => {
+                let key = if file.params == self.kdf && file.salt == self.key_salt {
+                    self.key
+                } else {
+                    file.params.derive(&self.master, &file.salt)?
+                };
+                Ok((String::from_utf8(file.open(&key, stype)?)?, false))
+            }
+            None => Ok((self.read_legacy_secret(&buf)?, true)),
+        }
+    }
+
+    /// Files of every secret type, whether they exist and their format, no master key is needed
+    pub fn status(location: &VaultLocation) -> Vec<SecretStatus> {
+        SecretType::iterator()
+            .map(|stype| {
+                let path = location.file(*stype);
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                let state = match fs::read(&path) {
+                    Err(e) if e.kind() == ErrorKind::NotFound => SecretState::Missing,
+                    Err(e) => SecretState::Invalid(e.to_string()),
+                    Ok(buf) => match VaultFile::decode(&buf) {
+                        Ok(Some(_)) => SecretState::Version(VERSION),
+                        Ok(None) => SecretState::Legacy,
+                        Err(e) => SecretState::Invalid(e.to_string()),
+                    },
+                };
+                SecretStatus {
+                    stype: *stype,
+                    path,
+                    modified,
+                    state,
+                }
+            })
+            .collect()
+    }
+
+    /// Try to decrypt every existing secret file with the master key, nothing is written
+    pub fn verify(&self) -> Vec<(SecretType, Result<(), VaultError>)> {
+        SecretType::iterator()
+            .filter(|stype| self.location.file(**stype).exists())
+            .map(|stype| (*stype, self.decrypt_file(*stype).map(|_| ())))
+            .collect()
+    }
+
+    /// Replace a single secret, the master key must decrypt the other secrets
+    ///
+    /// A vault is never left with secrets encrypted by different master keys
+    pub fn update_secret(&self, stype: SecretType, value: String) -> Result<(), VaultError> {
+        for (other, res) in self.verify() {
+            if other != stype {
+                res?;
+            }
         }
-        let enc = Aes256CbcEnc::new(&self.key.into(), &iv.into());
-        enc.encrypt_padded_mut::<Pkcs7>(&mut buf, self.pt_len)?;
-        let vault_dir = Path::new("./vault");
-        if !vault_dir.is_dir() {
-            create_dir(vault_dir)?;
+        self.create_secret(stype, value)
+    }
+
+    /// Encrypt every existing secret with `new_master`, returns the number of re-encrypted secrets
+    ///
+    /// All secrets are decrypted and written to temporary files before the first one is
+    /// replaced, a failing replacement restores the files already replaced
+    pub fn rekey(&self, new_master: &str) -> Result<usize, VaultError> {
+        let mut secrets = SecretType::iterator()
+            .filter(|stype| self.location.file(**stype).exists())
+            .map(|stype| self.decrypt_file(*stype).map(|(value, _)| (*stype, value)))
+            .collect::<Result<Vec<(SecretType, String)>, VaultError>>()?;
+        let vault = FileStore::new(new_master, self.location.clone())?;
+        let staged = vault.stage(&secrets);
+        let count = secrets.len();
+        secrets.iter_mut().for_each(|(_, value)| value.zeroize());
+        let staged = staged?;
+        for (i, (path, _, tmp)) in staged.iter().enumerate() {
+            if let Err(e) = fs::rename(tmp, path) {
+                for (path, previous, _) in staged[..i].iter() {
+                    if let Err(e) = write_atomic(path, previous) {
+                        log::error!("Cannot restore {:?} : {}", path, e);
+                    }
+                }
+                for (_, _, tmp) in staged[i..].iter() {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(e.into());
+            }
         }
-        let mut output = File::create(format!("./vault/vault.{}", stype))?;
-        let cipher = [iv.as_slice(), self.key_salt.as_slice(), buf.as_slice()].concat();
-        output.write_all(&cipher)?;
-        output.flush()?;
-        Ok(())
+        Ok(count)
     }
 
-    pub fn read_secret_from_file(&mut self, stype: SecretType) -> Result<(), VaultError> {
-        let buf = std::fs::read(format!("./vault/vault.{}", stype))?;
-        let iv: &GenericArray<u8, typenum::U16> = GenericArray::from_slice(&buf[..16]);
-        let salt: &GenericArray<u8, typenum::U32> = GenericArray::from_slice(&buf[16..48]);
-        let cipher = &mut buf.clone()[48..];
-        match argon2::hash_raw(self.master.as_bytes(), salt, &Config::owasp5()) {
-            Ok(hash) => {
-                if !argon2::verify_raw(self.master.as_bytes(), salt, &hash, &Config::owasp5())? {
-                    return Err(VaultError::MasterPasswordVerifyError);
+    /// Temporary files of `secrets` encrypted with this store's key, with the path they replace
+    /// and its current content. Nothing is left behind on error
+    fn stage(
+        &self,
+        secrets: &[(SecretType, String)],
+    ) -> Result<Vec<(PathBuf, Vec<u8>, PathBuf)>, VaultError> {
+        let mut staged = Vec::new();
+        for (stype, value) in secrets.iter() {
+            let path = self.location.file(*stype);
+            let res = VaultFile::seal(&self.key, self.kdf, self.key_salt, *stype, value.as_bytes())
+                .and_then(|file| Ok((fs::read(&path)?, write_temp(&path, &file.encode())?)));
+            match res {
+                Ok((previous, tmp)) => staged.push((path, previous, tmp)),
+                Err(e) => {
+                    for (_, _, tmp) in staged.iter() {
+                        let _ = fs::remove_file(tmp);
+                    }
+                    return Err(e);
                 }
-                Aes256CbcDec::new(GenericArray::from_slice(&hash), iv)
-                    .decrypt_padded_mut::<Pkcs7>(cipher)?;
             }
-            Err(_) => return Err(VaultError::MasterPasswordVerifyError),
         }
-        let value = String::from_utf8(
-            (general_purpose::STANDARD.decode(&cipher[..self.pt_len]))?[..16].to_vec(),
-        )?;
-        match stype {
-            SecretType::Configuration => self.configuration = value,
-            SecretType::Monitoring => self.monitoring = value,
-            SecretType::Management => self.management = value,
-            SecretType::Encrypt => self.encrypt = value,
+        Ok(staged)
+    }
+
+    /// Secrets of legacy files were padded with `0` and only their first 16 characters were used,
+    /// the migrated secret keeps these 16 characters
+    fn read_legacy_secret(&self, buf: &[u8]) -> Result<String, VaultError> {
+        if buf.len() != LEGACY_LEN {
+            return Err(VaultError::Corrupted(format!(
+                "Legacy vault file has {} bytes instead of {}",
+                buf.len(),
+                LEGACY_LEN
+            )));
         }
-        Ok(())
+        let iv: &GenericArray<u8, typenum::U16> = GenericArray::from_slice(&buf[..16]);
+        let salt = &buf[16..48];
+        let mut cipher = buf[48..].to_vec();
+        let mut key = KdfParams::legacy().derive(&self.master, salt)?;
+        let res = Aes256CbcDec::new(GenericArray::from_slice(&key), iv)
+            .decrypt_padded_mut::<Pkcs7>(&mut cipher)
+            .map(|_| ());
+        key.zeroize();
+        res.map_err(|_| VaultError::MasterPasswordVerifyError)?;
+        let mut decoded = general_purpose::STANDARD.decode(&cipher[..LEGACY_PT_LEN])?;
+        cipher.zeroize();
+        let value = String::from_utf8(decoded[..16].to_vec());
+        decoded.zeroize();
+        Ok(value?)
     }
+}
 
-    /// Read all secrets from all vault
-    /// After this function execution, Self will contains all secrets
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if one of vaults can't be read
-    pub fn read_all_secrets(&mut self) {
+#[cfg(test)]
+mod tests {
+    use aes::cipher::BlockEncryptMut;
+
+    use super::*;
+
+    fn store(master: &str, dir: &Path) -> FileStore {
+        FileStore::new(
+            master,
+            VaultLocation::new(Some(dir.to_path_buf()), Some("gw")),
+        )
+        .unwrap()
+    }
+
+    /// File written by the versions before the format version 2
+    fn legacy_file(master: &str, secret: &str) -> Vec<u8> {
+        let iv = [1u8; 16];
+        let salt = [2u8; 32];
+        let key = KdfParams::legacy().derive(master, &salt).unwrap();
+        let plaintext = general_purpose::STANDARD.encode(format!("{secret:0<36}"));
+        let mut cipher = [0u8; LEGACY_LEN - 48];
+        cipher[..LEGACY_PT_LEN].copy_from_slice(plaintext.as_bytes());
+        cbc::Encryptor::<aes::Aes256>::new(&key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut cipher, LEGACY_PT_LEN)
+            .unwrap();
+        [&iv[..], &salt[..], &cipher[..]].concat()
+    }
+
+    #[test]
+    fn secret_is_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        store("master", dir.path())
+            .create_secret(SecretType::Configuration, "api key".to_owned())
+            .unwrap();
+        assert_eq!(
+            store("master", dir.path())
+                .read_secret_from_file(SecretType::Configuration)
+                .unwrap(),
+            "api key"
+        );
+    }
+
+    #[test]
+    fn wrong_master_key_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        store("master", dir.path())
+            .create_secret(SecretType::Configuration, "api key".to_owned())
+            .unwrap();
+        assert!(matches!(
+            store("other", dir.path()).read_secret_from_file(SecretType::Configuration),
+            Err(VaultError::MasterPasswordVerifyError)
+        ));
+    }
+
+    #[test]
+    fn legacy_file_is_migrated_once_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = store("master", dir.path());
+        let path = vault.location.file(SecretType::Monitoring);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, legacy_file("master", "0123456789abcdef")).unwrap();
+        assert_eq!(
+            FileStore::status(&vault.location)[1].state,
+            SecretState::Legacy
+        );
+
+        assert_eq!(
+            vault.read_secret_from_file(SecretType::Monitoring).unwrap(),
+            "0123456789abcdef"
+        );
+        assert_eq!(
+            FileStore::status(&vault.location)[1].state,
+            SecretState::Version(VERSION)
+        );
+        assert_eq!(
+            store("master", dir.path())
+                .read_secret_from_file(SecretType::Monitoring)
+                .unwrap(),
+            "0123456789abcdef"
+        );
+    }
+
+    #[test]
+    fn legacy_file_with_a_wrong_master_key_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = store("other", dir.path());
+        let path = vault.location.file(SecretType::Monitoring);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let legacy = legacy_file("master", "0123456789abcdef");
+        fs::write(&path, &legacy).unwrap();
+
+        assert!(vault.read_secret_from_file(SecretType::Monitoring).is_err());
+        assert_eq!(fs::read(&path).unwrap(), legacy);
+    }
+
+    /// Every secret type of `vault`, one value each
+    fn fill(vault: &FileStore) {
         for stype in SecretType::iterator() {
-            self.read_secret_from_file(*stype)
-                .unwrap_or_else(|e| panic!("Can't open vault {stype} : {e}"));
+            vault.create_secret(*stype, stype.to_string()).unwrap();
         }
-        self.initialized = true;
-        // We remove the master key from memory there
-        self.master.zeroize();
     }
 
-    /// Get the current `SecretType` stored in the Vault
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if the current Vault is not initialized correctly
-    pub fn get_secret(&self, stype: SecretType) -> &str {
-        if !self.initialized {
-            panic!("Vault has not yet been initilized");
+    #[test]
+    fn files_are_namespaced_and_private() {
+        let dir = tempfile::tempdir().unwrap();
+        store("master", dir.path())
+            .create_secret(SecretType::Configuration, "api key".to_owned())
+            .unwrap();
+        let path = dir.path().join("gw").join("vault.Configuration");
+        assert!(path.is_file());
+        assert!(!dir
+            .path()
+            .join("gw")
+            .join(".vault.Configuration.tmp")
+            .exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(&dir.path().join("gw")), 0o700);
         }
-        match stype {
-            SecretType::Configuration => &self.configuration,
-            SecretType::Monitoring => &self.monitoring,
-            SecretType::Management => &self.management,
-            SecretType::Encrypt => &self.encrypt,
+    }
+
+    #[test]
+    fn rekey_encrypts_every_secret_with_the_new_key() {
+        let dir = tempfile::tempdir().unwrap();
+        fill(&store("master", dir.path()));
+
+        assert_eq!(store("master", dir.path()).rekey("new").unwrap(), 4);
+        let vault = store("new", dir.path());
+        for stype in SecretType::iterator() {
+            assert_eq!(
+                vault.read_secret_from_file(*stype).unwrap(),
+                stype.to_string()
+            );
         }
+        assert!(store("master", dir.path())
+            .verify()
+            .iter()
+            .all(|(_, res)| res.is_err()));
+    }
+
+    #[test]
+    fn failed_rekey_keeps_every_secret_under_the_old_key() {
+        let dir = tempfile::tempdir().unwrap();
+        fill(&store("master", dir.path()));
+        // The temporary file of the last secret cannot be created
+        let blocker = dir.path().join("gw").join(".vault.Encrypt.tmp");
+        fs::create_dir(&blocker).unwrap();
+
+        assert!(store("master", dir.path()).rekey("new").is_err());
+        assert!(store("master", dir.path())
+            .verify()
+            .iter()
+            .all(|(_, res)| res.is_ok()));
+        let leftovers = fs::read_dir(dir.path().join("gw"))
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.path() != blocker && entry.file_name().to_string_lossy().ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
     }
 }
@@ -1,9 +1,4 @@
-use std::{
-    io::{stdin, stdout, Write},
-    panic,
-    sync::Arc,
-    time::Instant,
-};
+use std::{panic, sync::Arc, time::Instant};
 
 use clap::Parser;
 use log::{error, info};
@@ -22,8 +17,35 @@ use anyhow::Result;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
+    if let Err(e) = args.validate() {
+        e.exit();
+    }
+    if let Some(command) = &args.command {
+        if let Some(report) = command.run_offline() {
+            print!("{}", report?);
+            return Ok(());
+        }
+    }
+    #[cfg(feature = "store-cli")]
+    if let Some(mgwconf_cli::command::Command::Vault(vault)) = &args.command {
+        let vault_key = match &args.vault_key {
+            Some(vault_key) => Some(vault_key.to_owned()),
+            None if vault.needs_master_key() => Some(ask_master_key()),
+            None => None,
+        };
+        let config = Config::init(&args).unwrap_or_else(|e| {
+            eprintln!("Invalid configuration : {e}");
+            std::process::exit(1)
+        });
+        print!(
+            "{}",
+            vault.run(vault_key.as_deref(), config.vault_location())?
+        );
+        return Ok(());
+    }
     let (sync_io_tx, sync_io_rx) = channel(100);
-    let (app, config) = create_app(sync_io_tx).await;
+    let (app, config) = create_app(args, sync_io_tx).await;
     let cloned_app = Arc::clone(&app);
 
     let orig = panic::take_hook();
@@ -43,20 +65,12 @@ async fn main() -> Result<()> {
         .read_all_secrets();
     let now = Instant::now();
     log::info!("Starting Network");
-    if let Some(ref playbook) = config.playbook {
-        for host in playbook.entries.hosts.iter() {
-            let notify2 = notify.clone();
-            let sync_io_rx = sync_io_rx.resubscribe();
-            let app = app.clone();
-            let mut config = config.clone();
-            config.remote_addr = *host;
-            std::thread::spawn(move || {
-                let mut net = Network::new(&app, &config).expect("Network Error");
-                start_tokio(sync_io_rx, &mut net, notify2);
-            });
-        }
-    } else {
+    for host in config.gateways() {
         let notify2 = notify.clone();
+        let sync_io_rx = sync_io_rx.resubscribe();
+        let app = app.clone();
+        let mut config = config.clone();
+        config.remote_addr = host;
         std::thread::spawn(move || {
             match Network::new(&app, &config) {
                 Ok(mut net) => start_tokio(sync_io_rx, &mut net, notify2),
@@ -64,33 +78,55 @@ async fn main() -> Result<()> {
             };
         });
     }
-    match <CliApp as AppTrait<Config>>::run(cloned_app, Some(notify)).await {
+    match <CliApp as AppTrait<Config>>::run(cloned_app.clone(), Some(notify)).await {
         Ok(_) => {
             info!("Elapsed time : {:.9}s", now.elapsed().as_secs_f64(),);
+            let exit_code = cloned_app.lock().await.exit_code();
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
             Ok(())
         }
         Err(e) => {
             error!("{}, exiting", e);
+            if let Some(report) = cloned_app.lock().await.certificate_report.as_mut() {
+                // Monitoring commands must report an unreachable gateway as critical
+                report.add_error(e.to_string());
+                print!("{}", report);
+                std::process::exit(report.exit_code());
+            }
+            if let Some(report) = cloned_app.lock().await.lint_report.as_mut() {
+                report.add_error(e.to_string());
+                print!("{}", report);
+                std::process::exit(report.exit_code());
+            }
             Err(e)
         }
     }
 }
 
-pub async fn create_app(io_tx: Sender<IoEvent>) -> (Arc<Mutex<CliApp>>, Config) {
-    let args = Args::parse();
-    let vault_key = if args.vault_key.is_some() {
-        args.vault_key.as_ref().unwrap().to_owned()
-    } else {
-        ask_master_key()
+pub async fn create_app(args: Args, io_tx: Sender<IoEvent>) -> (Arc<Mutex<CliApp>>, Config) {
+    let mut config = Config::init(&args).unwrap_or_else(|e| {
+        // Logging is not initialized yet
+        eprintln!("Invalid configuration : {e}");
+        std::process::exit(1)
+    });
+    let vault_key = match &args.vault_key {
+        Some(vault_key) => vault_key.to_owned(),
+        None if config.secrets.needs_master_key() => ask_master_key(),
+        None => String::new(),
     };
-    let mut config = Config::init(&args).unwrap();
+    let backend = config.secret_backend(&vault_key).unwrap_or_else(|e| {
+        eprintln!("Invalid secret backend {} : {e}", config.secrets);
+        std::process::exit(1)
+    });
     config.init_logging();
     if args.create_secret {
-        <CliApp as AppTrait<Config>>::ask_secrets(&vault_key).unwrap();
+        <CliApp as AppTrait<Config>>::ask_secrets(backend.as_ref()).unwrap();
     }
     (
         Arc::new(Mutex::new(
-            CliApp::new(io_tx, config.clone(), &vault_key).await,
+            CliApp::new(io_tx, config.clone(), backend).await,
         )),
         config,
     )
@@ -4,14 +4,15 @@ use mgwconf_network::{event::IoEvent, AppConfig, AppTrait, Network};
 use std::sync::Arc;
 use tokio::sync::{mpsc::Receiver, Mutex};
 
-use tokio::sync::mpsc::Sender;
-
 pub use mgwconf_ui::{
     app::{UiApp, UiAppTrait},
     event::Key,
 };
 
-use mgwconf_ui::config::{Args, Config};
+use mgwconf_ui::{
+    app::state::gateway::Gateway,
+    config::{Args, Config},
+};
 
 use anyhow::Result;
 use std::panic;
@@ -19,8 +20,7 @@ use tokio::sync::Notify;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let (sync_io_tx, sync_io_rx) = tokio::sync::mpsc::channel::<IoEvent>(100);
-    let (app, config) = create_app(sync_io_tx).await;
+    let (app, gateways) = create_app().await;
     let cloned_app = Arc::clone(&app);
     let orig = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
@@ -31,20 +31,20 @@ async fn main() -> Result<()> {
     let notify = Arc::new(Notify::new());
     let notify2: Arc<Notify> = notify.clone();
     println!("Reading secrets from vault");
-    cloned_app
-        .lock()
-        .await
-        .vault
-        .as_mut()
-        .expect("Vault not initialized correctly")
-        .read_all_secrets();
+    for gateway in cloned_app.lock().await.gateways.iter_mut() {
+        gateway.vault.read_all_secrets();
+    }
     log::info!("Starting Network");
-    std::thread::spawn(move || {
-        match Network::new(&app, &config) {
-            Ok(mut net) => start_tokio(sync_io_rx, &mut net, notify2),
-            Err(err) => log::error!("{:?}", err),
-        };
-    });
+    for (config, sync_io_rx) in gateways {
+        let notify2 = notify2.clone();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            match Network::new(&app, &config) {
+                Ok(mut net) => start_tokio(sync_io_rx, &mut net, notify2),
+                Err(err) => log::error!("{:?}", err),
+            };
+        });
+    }
     use mgwconf_ui::app::UiApp;
     use mgwconf_ui::config::Config;
     <UiApp as AppTrait<Config>>::run(cloned_app, None).await?;
@@ -52,26 +52,59 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-pub async fn create_app(io_tx: Sender<IoEvent>) -> (Arc<Mutex<UiApp>>, Config) {
+/// The app and, for each gateway, the configuration and the events of its network thread
+pub async fn create_app() -> (Arc<Mutex<UiApp>>, Vec<(Config, Receiver<IoEvent>)>) {
     use clap::Parser;
 
     let args = Args::parse();
-    let vault_key = if args.vault_key.is_some() {
-        args.vault_key.as_ref().unwrap().to_owned()
-    } else {
-        ask_master_key()
+    let mut config = Config::init(&args).unwrap_or_else(|e| {
+        // Logging is not initialized yet
+        eprintln!("Invalid configuration : {e}");
+        std::process::exit(1)
+    });
+    let gateway_configs = config.gateways(&args);
+    let vault_key = match &args.vault_key {
+        Some(vault_key) => vault_key.to_owned(),
+        None if gateway_configs
+            .iter()
+            .any(|(_, c)| c.secrets.needs_master_key()) =>
+        {
+            ask_master_key()
+        }
+        None => String::new(),
     };
-    let mut config = Config::init(&args).unwrap();
     config.init_logging();
-    if args.create_secret {
-        <UiApp as AppTrait<Config>>::ask_secrets(&vault_key).unwrap();
+    let mut gateways = Vec::new();
+    let mut networks = Vec::new();
+    for (i, (name, gateway_config)) in gateway_configs.into_iter().enumerate() {
+        // Each gateway has its own secrets, only the gateway of the command line is required
+        let backend = match gateway_config.secret_backend(&vault_key) {
+            Ok(backend) => backend,
+            Err(e) if i == 0 => {
+                eprintln!("Invalid secret backend {} : {e}", gateway_config.secrets);
+                std::process::exit(1)
+            }
+            Err(e) => {
+                log::error!("Gateway {} skipped, invalid secret backend : {}", name, e);
+                continue;
+            }
+        };
+        if args.create_secret && i == 0 {
+            <UiApp as AppTrait<Config>>::ask_secrets(backend.as_ref()).unwrap();
+        }
+        let (sync_io_tx, sync_io_rx) = tokio::sync::mpsc::channel::<IoEvent>(100);
+        gateways.push(Gateway::new(
+            name,
+            gateway_config.remote_addr,
+            gateway_config.live,
+            backend,
+            sync_io_tx,
+        ));
+        networks.push((gateway_config, sync_io_rx));
     }
-
     (
-        Arc::new(Mutex::new(
-            UiApp::new(io_tx, config.clone(), &vault_key).await,
-        )),
-        config,
+        Arc::new(Mutex::new(UiApp::new(gateways, config).await)),
+        networks,
     )
 }
 